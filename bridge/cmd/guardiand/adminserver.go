@@ -46,13 +46,18 @@ func adminGuardianSetUpdateToVAA(req *nodev1.GuardianSetUpdate) (*vaa.VAA, error
 		addrs[i] = ethcommon.HexToAddress(g.Pubkey)
 	}
 
+	if req.Threshold > uint32(len(addrs)) {
+		return nil, fmt.Errorf("threshold %d exceeds the %d guardians of the set", req.Threshold, len(addrs))
+	}
+
 	v := &vaa.VAA{
 		Version:          vaa.SupportedVAAVersion,
 		GuardianSetIndex: req.CurrentSetIndex,
 		Timestamp:        time.Unix(int64(req.Timestamp), 0),
 		Payload: &vaa.BodyGuardianSetUpdate{
-			Keys:     addrs,
-			NewIndex: req.CurrentSetIndex + 1,
+			Keys:      addrs,
+			NewIndex:  req.CurrentSetIndex + 1,
+			Threshold: uint8(req.Threshold),
 		},
 	}
 
@@ -286,6 +286,9 @@ func runBridge(cmd *cobra.Command, args []string) {
 	// Ethereum lock event channel
 	lockC := make(chan *common.ChainLock)
 
+	// Message publication channel
+	msgC := make(chan *common.MessagePublication)
+
 	// Ethereum incoming guardian set updates
 	setC := make(chan *common.GuardianSet)
 
@@ -355,13 +358,14 @@ func runBridge(cmd *cobra.Command, args []string) {
 		}
 
 		if err := supervisor.Run(ctx, "solwatch",
-			solana.NewSolanaBridgeWatcher(*agentRPC, lockC, solanaVaaC).Run); err != nil {
+			solana.NewSolanaBridgeWatcher(*agentRPC, lockC, msgC, solanaVaaC).Run); err != nil {
 			return err
 		}
 
 		// TODO: this thing has way too many arguments at this point - make it an options struct
 		p := processor.NewProcessor(ctx,
 			lockC,
+			msgC,
 			setC,
 			sendC,
 			obsvC,
@@ -26,4 +26,6 @@ type ChainLock struct {
 	TokenDecimals uint8
 
 	Amount *big.Int
+	// Fee is the part of Amount paid to the relayer on TargetChain
+	Fee *big.Int
 }
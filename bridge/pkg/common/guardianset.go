@@ -18,6 +18,8 @@ type GuardianSet struct {
 	Keys []common.Address
 	// On-chain set index
 	Index uint32
+	// Number of signatures a VAA needs, 0 if the set uses the default quorum
+	Threshold uint8
 }
 
 func (g *GuardianSet) KeysAsHexStrings() []string {
@@ -0,0 +1,22 @@
+package common
+
+import (
+	"time"
+
+	"github.com/ethereum/go-ethereum/common"
+
+	"github.com/certusone/wormhole/bridge/pkg/vaa"
+)
+
+type MessagePublication struct {
+	TxHash    common.Hash // On Solana, this is the address of the message account
+	Timestamp time.Time
+
+	Nonce    uint32
+	Sequence uint64
+
+	EmitterChain   vaa.ChainID
+	EmitterAddress vaa.Address
+
+	Payload []byte
+}
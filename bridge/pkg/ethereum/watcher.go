@@ -86,14 +86,16 @@ func (e *EthBridgeWatcher) Run(ctx context.Context) error {
 	// because both sets are synchronized, we simply made an arbitrary decision to use Ethereum.
 	timeout, cancel = context.WithTimeout(ctx, 15*time.Second)
 	defer cancel()
-	idx, gs, err := FetchCurrentGuardianSet(timeout, e.url, e.bridge)
+	idx, gs, threshold, err := FetchCurrentGuardianSet(timeout, e.url, e.bridge)
 	if err != nil {
 		return fmt.Errorf("failed requesting guardian set from Ethereum: %w", err)
 	}
-	logger.Info("initial guardian set fetched", zap.Any("value", gs), zap.Uint32("index", idx))
+	logger.Info("initial guardian set fetched", zap.Any("value", gs), zap.Uint32("index", idx),
+		zap.Uint8("threshold", threshold))
 	e.setChan <- &common.GuardianSet{
-		Keys:  gs.Keys,
-		Index: idx,
+		Keys:      gs.Keys,
+		Index:     idx,
+		Threshold: threshold,
 	}
 
 	go func() {
@@ -151,10 +153,18 @@ func (e *EthBridgeWatcher) Run(ctx context.Context) error {
 					return
 				}
 
-				logger.Info("new guardian set fetched", zap.Any("value", gs), zap.Uint32("index", ev.NewGuardianIndex))
+				threshold, err := caller.GuardianSetThresholds(&bind.CallOpts{Context: timeout}, ev.NewGuardianIndex)
+				if err != nil {
+					errC <- fmt.Errorf("error requesting threshold of new guardian set %d: %w", ev.NewGuardianIndex, err)
+					return
+				}
+
+				logger.Info("new guardian set fetched", zap.Any("value", gs), zap.Uint32("index", ev.NewGuardianIndex),
+					zap.Uint8("threshold", threshold))
 				e.setChan <- &common.GuardianSet{
-					Keys:  gs.Keys,
-					Index: ev.NewGuardianIndex,
+					Keys:      gs.Keys,
+					Index:     ev.NewGuardianIndex,
+					Threshold: threshold,
 				}
 			}
 		}
@@ -219,10 +229,10 @@ func (e *EthBridgeWatcher) Run(ctx context.Context) error {
 }
 
 // Fetch the current guardian set ID and guardian set from the chain.
-func FetchCurrentGuardianSet(ctx context.Context, rpcURL string, bridgeContract eth_common.Address) (uint32, *abi.WormholeGuardianSet, error) {
+func FetchCurrentGuardianSet(ctx context.Context, rpcURL string, bridgeContract eth_common.Address) (uint32, *abi.WormholeGuardianSet, uint8, error) {
 	c, err := ethclient.DialContext(ctx, rpcURL)
 	if err != nil {
-		return 0, nil, fmt.Errorf("dialing eth client failed: %w", err)
+		return 0, nil, 0, fmt.Errorf("dialing eth client failed: %w", err)
 	}
 
 	caller, err := abi.NewAbiCaller(bridgeContract, c)
@@ -234,13 +244,18 @@ func FetchCurrentGuardianSet(ctx context.Context, rpcURL string, bridgeContract
 
 	currentIndex, err := caller.GuardianSetIndex(opts)
 	if err != nil {
-		return 0, nil, fmt.Errorf("error requesting current guardian set index: %w", err)
+		return 0, nil, 0, fmt.Errorf("error requesting current guardian set index: %w", err)
 	}
 
 	gs, err := caller.GetGuardianSet(opts, currentIndex)
 	if err != nil {
-		return 0, nil, fmt.Errorf("error requesting current guardian set value: %w", err)
+		return 0, nil, 0, fmt.Errorf("error requesting current guardian set value: %w", err)
+	}
+
+	threshold, err := caller.GuardianSetThresholds(opts, currentIndex)
+	if err != nil {
+		return 0, nil, 0, fmt.Errorf("error requesting current guardian set threshold: %w", err)
 	}
 
-	return currentIndex, &gs, nil
+	return currentIndex, &gs, threshold, nil
 }
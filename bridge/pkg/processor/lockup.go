@@ -55,6 +55,7 @@ func (p *Processor) handleLockup(ctx context.Context, k *common.ChainLock) {
 				Decimals: k.TokenDecimals,
 			},
 			Amount: k.Amount,
+			Fee:    k.Fee,
 		},
 	}
 
@@ -0,0 +1,77 @@
+package processor
+
+import (
+	"context"
+	"encoding/hex"
+
+	"github.com/ethereum/go-ethereum/crypto"
+	"go.uber.org/zap"
+
+	"github.com/certusone/wormhole/bridge/pkg/common"
+	"github.com/certusone/wormhole/bridge/pkg/supervisor"
+	"github.com/certusone/wormhole/bridge/pkg/vaa"
+)
+
+// handleMessage processes a message published on a chain and instantiates our deterministic copy of the VAA
+func (p *Processor) handleMessage(ctx context.Context, k *common.MessagePublication) {
+	supervisor.Logger(ctx).Info("message publication confirmed",
+		zap.Stringer("emitter_chain", k.EmitterChain),
+		zap.Stringer("emitter_addr", k.EmitterAddress),
+		zap.Uint32("nonce", k.Nonce),
+		zap.Uint64("sequence", k.Sequence),
+		zap.Int("payload_len", len(k.Payload)),
+		zap.Stringer("txhash", k.TxHash),
+		zap.Time("timestamp", k.Timestamp),
+	)
+
+	us, ok := p.gs.KeyIndex(p.ourAddr)
+	if !ok {
+		p.logger.Error("we're not in the guardian set - refusing to sign",
+			zap.Uint32("index", p.gs.Index),
+			zap.Stringer("our_addr", p.ourAddr),
+			zap.Any("set", p.gs.KeysAsHexStrings()))
+		return
+	}
+
+	// All nodes will create the exact same VAA and sign its digest.
+	// Consensus is established on this digest.
+
+	v := &vaa.VAA{
+		Version:          vaa.SupportedVAAVersion,
+		GuardianSetIndex: p.gs.Index,
+		Signatures:       nil,
+		Timestamp:        k.Timestamp,
+		Payload: &vaa.BodyMessage{
+			EmitterChain:   k.EmitterChain,
+			EmitterAddress: k.EmitterAddress,
+			Nonce:          k.Nonce,
+			Sequence:       k.Sequence,
+			Payload:        k.Payload,
+		},
+	}
+
+	// Generate digest of the unsigned VAA.
+	digest, err := v.SigningMsg()
+	if err != nil {
+		// Payloads that do not fit into a VAA are never signed
+		p.logger.Error("failed to serialize message VAA",
+			zap.Stringer("txhash", k.TxHash),
+			zap.Error(err))
+		return
+	}
+
+	// Sign the digest using our node's guardian key.
+	s, err := crypto.Sign(digest.Bytes(), p.gk)
+	if err != nil {
+		panic(err)
+	}
+
+	p.logger.Info("observed and signed confirmed message publication",
+		zap.Stringer("emitter_chain", k.EmitterChain),
+		zap.Stringer("txhash", k.TxHash),
+		zap.String("digest", hex.EncodeToString(digest.Bytes())),
+		zap.String("signature", hex.EncodeToString(s)),
+		zap.Int("our_index", us))
+
+	p.broadcastSignature(v, s)
+}
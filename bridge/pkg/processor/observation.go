@@ -123,8 +123,12 @@ func (p *Processor) handleObservation(ctx context.Context, m *gossipv1.SignedObs
 			Payload:          v.Payload,
 		}
 
-		// 2/3+ majority required for VAA to be valid - wait until we have quorum to submit VAA.
+		// 2/3+ majority (or the threshold of the set) required for VAA to be valid - wait until we
+		// have quorum to submit VAA.
 		quorum := CalculateQuorum(len(p.gs.Keys))
+		if p.gs.Threshold != 0 {
+			quorum = int(p.gs.Threshold)
+		}
 
 		p.logger.Info("aggregation state for VAA",
 			zap.String("digest", hash),
@@ -172,6 +176,26 @@ func (p *Processor) handleObservation(ctx context.Context, m *gossipv1.SignedObs
 				// A guardian set update is broadcast to every chain that we talk to.
 				p.devnetVAASubmission(ctx, signed, hash)
 				p.terraVAASubmission(ctx, signed, hash)
+			case *vaa.BodyAssetMetadata:
+				// Metadata is needed wherever wrapped assets are created, Solana already has it.
+				go p.terraVAASubmission(ctx, signed, hash)
+			case *vaa.BodyGovernance:
+				// Governance only applies to the bridge on its target chain.
+				switch t.TargetChain {
+				case vaa.ChainIDSolana:
+					// No-op.
+				case vaa.ChainIDTerra:
+					go p.terraVAASubmission(ctx, signed, hash)
+				default:
+					p.logger.Error("unsupported governance target chain ID",
+						zap.String("digest", hash),
+						zap.Any("vaa", signed),
+						zap.String("bytes", hex.EncodeToString(vaaBytes)),
+						zap.Stringer("target_chain", t.TargetChain))
+				}
+			case *vaa.BodyMessage, *vaa.BodyRefund:
+				// No-op. Messages are consumed by the contracts they are addressed to and refunds
+				// only exist on Solana, both are available there.
 			default:
 				panic(fmt.Sprintf("unknown VAA payload type: %+v", v))
 			}
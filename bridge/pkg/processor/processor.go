@@ -40,6 +40,8 @@ type (
 type Processor struct {
 	// lockC is a channel of observed chain lockups
 	lockC chan *common.ChainLock
+	// msgC is a channel of observed message publications
+	msgC chan *common.MessagePublication
 	// setC is a channel of guardian set updates
 	setC chan *common.GuardianSet
 
@@ -85,6 +87,7 @@ type Processor struct {
 func NewProcessor(
 	ctx context.Context,
 	lockC chan *common.ChainLock,
+	msgC chan *common.MessagePublication,
 	setC chan *common.GuardianSet,
 	sendC chan []byte,
 	obsvC chan *gossipv1.SignedObservation,
@@ -102,6 +105,7 @@ func NewProcessor(
 
 	return &Processor{
 		lockC:              lockC,
+		msgC:               msgC,
 		setC:               setC,
 		sendC:              sendC,
 		obsvC:              obsvC,
@@ -143,6 +147,8 @@ func (p *Processor) Run(ctx context.Context) error {
 			}
 		case k := <-p.lockC:
 			p.handleLockup(ctx, k)
+		case k := <-p.msgC:
+			p.handleMessage(ctx, k)
 		case v := <-p.injectC:
 			p.handleInjection(ctx, v)
 		case m := <-p.obsvC:
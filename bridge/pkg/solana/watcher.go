@@ -28,12 +28,13 @@ type (
 		url string
 
 		lockChan chan *common.ChainLock
+		msgChan  chan *common.MessagePublication
 		vaaChan  chan *vaa.VAA
 	}
 )
 
-func NewSolanaBridgeWatcher(url string, lockEvents chan *common.ChainLock, vaaQueue chan *vaa.VAA) *SolanaBridgeWatcher {
-	return &SolanaBridgeWatcher{url: url, lockChan: lockEvents, vaaChan: vaaQueue}
+func NewSolanaBridgeWatcher(url string, lockEvents chan *common.ChainLock, messageEvents chan *common.MessagePublication, vaaQueue chan *vaa.VAA) *SolanaBridgeWatcher {
+	return &SolanaBridgeWatcher{url: url, lockChan: lockEvents, msgChan: messageEvents, vaaChan: vaaQueue}
 }
 
 func (e *SolanaBridgeWatcher) Run(ctx context.Context) error {
@@ -92,6 +93,7 @@ func (e *SolanaBridgeWatcher) Run(ctx context.Context) error {
 					TokenChain:    vaa.ChainID(event.New.TokenChain),
 					TokenDecimals: uint8(event.New.TokenDecimals),
 					Amount:        new(big.Int).SetBytes(event.New.Amount),
+					Fee:           new(big.Int).SetBytes(event.New.Fee),
 				}
 				copy(lock.TokenAddress[:], event.New.TokenAddress)
 				copy(lock.SourceAddress[:], event.New.SourceAddress)
@@ -99,6 +101,27 @@ func (e *SolanaBridgeWatcher) Run(ctx context.Context) error {
 
 				e.lockChan <- lock
 				logger.Info("found new lockup transaction", zap.String("lockup_address", ev.LockupAddress))
+			case *agentv1.LockupEvent_Message:
+				// The message account is updated again once its VAA is posted, only new messages are observed
+				if len(event.Message.Vaa) != 0 {
+					continue
+				}
+
+				logger.Debug("received message event",
+					zap.Any("event", ev))
+
+				msg := &common.MessagePublication{
+					TxHash:       eth_common.HexToHash(ev.LockupAddress),
+					Timestamp:    time.Unix(int64(ev.Time), 0),
+					Nonce:        event.Message.Nonce,
+					Sequence:     event.Message.Sequence,
+					EmitterChain: vaa.ChainIDSolana,
+					Payload:      event.Message.Payload,
+				}
+				copy(msg.EmitterAddress[:], event.Message.EmitterAddress)
+
+				e.msgChan <- msg
+				logger.Info("found new message publication", zap.String("message_address", ev.LockupAddress))
 			}
 		}
 	}()
@@ -103,6 +103,8 @@ func (e *BridgeWatcher) Run(ctx context.Context) error {
 			sender := gjson.Get(json, "result.events.from_contract\\.locked\\.sender.0")
 			recipient := gjson.Get(json, "result.events.from_contract\\.locked\\.recipient.0")
 			amount := gjson.Get(json, "result.events.from_contract\\.locked\\.amount.0")
+			// Lockups of older contract versions do not log a fee, which then reads as zero
+			fee := gjson.Get(json, "result.events.from_contract\\.locked\\.fee.0")
 			nonce := gjson.Get(json, "result.events.from_contract\\.locked\\.nonce.0")
 			txHash := gjson.Get(json, "result.events.tx\\.hash.0")
 			blockTime := gjson.Get(json, "result.events.from_contract\\.locked\\.block_time.0")
@@ -119,6 +121,7 @@ func (e *BridgeWatcher) Run(ctx context.Context) error {
 					zap.String("sender", sender.String()),
 					zap.String("recipient", recipient.String()),
 					zap.String("amount", amount.String()),
+					zap.String("fee", fee.String()),
 					zap.String("nonce", nonce.String()),
 					zap.String("blockTime", blockTime.String()),
 				)
@@ -155,6 +158,7 @@ func (e *BridgeWatcher) Run(ctx context.Context) error {
 					TokenAddress:  tokenAddress,
 					TokenDecimals: uint8(tokenDecimals.Uint()),
 					Amount:        new(big.Int).SetUint64(amount.Uint()),
+					Fee:           new(big.Int).SetUint64(fee.Uint()),
 				}
 				e.lockChan <- lock
 			}
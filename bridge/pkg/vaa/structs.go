@@ -7,6 +7,8 @@ import (
 	"encoding/hex"
 	"fmt"
 	"io"
+	"io/ioutil"
+	"math"
 	"math/big"
 	"time"
 
@@ -77,6 +79,8 @@ type (
 		Asset *AssetMeta
 		// Amount is the amount of tokens to be transferred
 		Amount *big.Int
+		// Fee is the part of Amount paid to the relayer on TargetChain, nil or zero if none
+		Fee *big.Int
 	}
 
 	BodyGuardianSetUpdate struct {
@@ -84,6 +88,48 @@ type (
 		Keys []common.Address
 		// NewIndex is the index of the new guardian set
 		NewIndex uint32
+		// Threshold is the number of signatures the new set requires, 0 for the default quorum
+		Threshold uint8
+	}
+
+	// BodyGovernance is a change of the bridge configuration on a single chain
+	BodyGovernance struct {
+		// TargetChain is the id of the chain whose bridge is reconfigured
+		TargetChain ChainID
+		// Change is the kind of the configuration change
+		Change uint8
+		// Data is the encoded value of the change, its layout depends on Change
+		Data []byte
+	}
+
+	// BodyAssetMetadata is the metadata of an asset as attested by the guardians
+	BodyAssetMetadata struct {
+		// Asset is the asset the metadata belongs to
+		Asset *AssetMeta
+		// Symbol of the asset, UTF-8 and right zero-padded
+		Symbol [32]byte
+		// Name of the asset, UTF-8 and right zero-padded
+		Name [32]byte
+	}
+
+	// BodyMessage is an arbitrary message published by an emitter on a chain
+	BodyMessage struct {
+		// EmitterChain is the id of the chain the message was published on
+		EmitterChain ChainID
+		// EmitterAddress is the address of the emitter on EmitterChain
+		EmitterAddress Address
+		// Nonce is the nonce given by the emitter
+		Nonce uint32
+		// Sequence is the sequence number assigned to the message by the bridge of EmitterChain
+		Sequence uint64
+		// Payload is the message itself
+		Payload []byte
+	}
+
+	// BodyRefund refunds a transfer out of the source chain that was never completed
+	BodyRefund struct {
+		// Transfer is the refunded transfer
+		Transfer *BodyTransfer
 	}
 )
 
@@ -105,8 +151,14 @@ func (c ChainID) String() string {
 }
 
 const (
-	ActionGuardianSetUpdate Action = 0x01
-	ActionTransfer          Action = 0x10
+	ActionGuardianSetUpdate              Action = 0x01
+	ActionGovernance                     Action = 0x02
+	ActionGuardianSetUpdateWithThreshold Action = 0x03
+	ActionTransfer                       Action = 0x10
+	ActionAssetMetadata                  Action = 0x11
+	ActionMessage                        Action = 0x12
+	ActionTransferWithFee                Action = 0x14
+	ActionRefund                         Action = 0x15
 
 	// ChainIDSolana is the ChainID of Solana
 	ChainIDSolana = 1
@@ -180,8 +232,20 @@ func Unmarshal(data []byte) (*VAA, error) {
 	switch Action(action) {
 	case ActionGuardianSetUpdate:
 		v.Payload, err = parseBodyGuardianSetUpdate(payloadReader)
+	case ActionGuardianSetUpdateWithThreshold:
+		v.Payload, err = parseBodyGuardianSetUpdateWithThreshold(payloadReader)
+	case ActionGovernance:
+		v.Payload, err = parseBodyGovernance(payloadReader)
 	case ActionTransfer:
 		v.Payload, err = parseBodyTransfer(payloadReader)
+	case ActionAssetMetadata:
+		v.Payload, err = parseBodyAssetMetadata(payloadReader)
+	case ActionMessage:
+		v.Payload, err = parseBodyMessage(payloadReader)
+	case ActionTransferWithFee:
+		v.Payload, err = parseBodyTransferWithFee(payloadReader)
+	case ActionRefund:
+		v.Payload, err = parseBodyRefund(payloadReader)
 	default:
 		return nil, fmt.Errorf("unknown action: %d", action)
 	}
@@ -338,7 +402,37 @@ func parseBodyTransfer(r io.Reader) (*BodyTransfer, error) {
 	return b, nil
 }
 
+// parseBodyTransferWithFee parses a transfer that pays a relayer fee, which is appended to the
+// payload of a plain transfer
+func parseBodyTransferWithFee(r io.Reader) (*BodyTransfer, error) {
+	b, err := parseBodyTransfer(r)
+	if err != nil {
+		return nil, err
+	}
+
+	var feeBytes [32]byte
+	if n, err := r.Read(feeBytes[:]); err != nil || n != 32 {
+		return nil, fmt.Errorf("failed to read fee: %w", err)
+	}
+	b.Fee = new(big.Int).SetBytes(feeBytes[:])
+	if b.Fee.Sign() == 0 {
+		return nil, fmt.Errorf("transfers without a fee must use action %d", ActionTransfer)
+	}
+	if b.Fee.Cmp(b.Amount) > 0 {
+		return nil, fmt.Errorf("fee exceeds amount")
+	}
+
+	return b, nil
+}
+
+func (v *BodyTransfer) hasFee() bool {
+	return v.Fee != nil && v.Fee.Sign() != 0
+}
+
 func (v *BodyTransfer) getActionID() Action {
+	if v.hasFee() {
+		return ActionTransferWithFee
+	}
 	return ActionTransfer
 }
 
@@ -362,6 +456,13 @@ func (v *BodyTransfer) serialize() ([]byte, error) {
 	}
 	buf.Write(common.LeftPadBytes(v.Amount.Bytes(), 32))
 
+	if v.hasFee() {
+		if v.Fee.Cmp(v.Amount) > 0 {
+			return nil, fmt.Errorf("fee exceeds amount")
+		}
+		buf.Write(common.LeftPadBytes(v.Fee.Bytes(), 32))
+	}
+
 	return buf.Bytes(), nil
 }
 
@@ -387,7 +488,36 @@ func parseBodyGuardianSetUpdate(r io.Reader) (*BodyGuardianSetUpdate, error) {
 	return b, nil
 }
 
+// parseBodyGuardianSetUpdateWithThreshold parses a guardian set update with the threshold of the
+// new set appended
+func parseBodyGuardianSetUpdateWithThreshold(r io.Reader) (*BodyGuardianSetUpdate, error) {
+	b, err := parseBodyGuardianSetUpdate(r)
+	if err != nil {
+		return nil, err
+	}
+
+	if err := binary.Read(r, binary.BigEndian, &b.Threshold); err != nil {
+		return nil, fmt.Errorf("failed to read threshold: %w", err)
+	}
+	if err := b.checkThreshold(); err != nil {
+		return nil, err
+	}
+
+	return b, nil
+}
+
+// checkThreshold verifies that the threshold is set and can be reached by the new set
+func (v *BodyGuardianSetUpdate) checkThreshold() error {
+	if v.Threshold == 0 || int(v.Threshold) > len(v.Keys) {
+		return fmt.Errorf("threshold %d exceeds the %d keys of the set", v.Threshold, len(v.Keys))
+	}
+	return nil
+}
+
 func (v *BodyGuardianSetUpdate) getActionID() Action {
+	if v.Threshold != 0 {
+		return ActionGuardianSetUpdateWithThreshold
+	}
 	return ActionGuardianSetUpdate
 }
 
@@ -400,9 +530,172 @@ func (v *BodyGuardianSetUpdate) serialize() ([]byte, error) {
 		buf.Write(key.Bytes())
 	}
 
+	if v.Threshold != 0 {
+		if err := v.checkThreshold(); err != nil {
+			return nil, err
+		}
+		MustWrite(buf, binary.BigEndian, v.Threshold)
+	}
+
+	return buf.Bytes(), nil
+}
+
+func parseBodyGovernance(r io.Reader) (*BodyGovernance, error) {
+	b := &BodyGovernance{}
+
+	if err := binary.Read(r, binary.BigEndian, &b.TargetChain); err != nil {
+		return nil, fmt.Errorf("failed to read target chain: %w", err)
+	}
+
+	if err := binary.Read(r, binary.BigEndian, &b.Change); err != nil {
+		return nil, fmt.Errorf("failed to read change: %w", err)
+	}
+
+	// The value of the change spans the rest of the payload
+	data, err := ioutil.ReadAll(r)
+	if err != nil {
+		return nil, fmt.Errorf("failed to read change data: %w", err)
+	}
+	b.Data = data
+
+	return b, nil
+}
+
+func (v *BodyGovernance) getActionID() Action {
+	return ActionGovernance
+}
+
+func (v *BodyGovernance) serialize() ([]byte, error) {
+	buf := new(bytes.Buffer)
+
+	MustWrite(buf, binary.BigEndian, v.TargetChain)
+	MustWrite(buf, binary.BigEndian, v.Change)
+	buf.Write(v.Data)
+
+	return buf.Bytes(), nil
+}
+
+func parseBodyAssetMetadata(r io.Reader) (*BodyAssetMetadata, error) {
+	b := &BodyAssetMetadata{}
+
+	b.Asset = &AssetMeta{}
+	if err := binary.Read(r, binary.BigEndian, &b.Asset.Chain); err != nil {
+		return nil, fmt.Errorf("failed to read asset chain: %w", err)
+	}
+	if n, err := r.Read(b.Asset.Address[:]); err != nil || n != 32 {
+		return nil, fmt.Errorf("failed to read asset address: %w", err)
+	}
+	if err := binary.Read(r, binary.BigEndian, &b.Asset.Decimals); err != nil {
+		return nil, fmt.Errorf("failed to read asset decimals: %w", err)
+	}
+
+	if n, err := r.Read(b.Symbol[:]); err != nil || n != 32 {
+		return nil, fmt.Errorf("failed to read symbol: %w", err)
+	}
+
+	if n, err := r.Read(b.Name[:]); err != nil || n != 32 {
+		return nil, fmt.Errorf("failed to read name: %w", err)
+	}
+
+	return b, nil
+}
+
+func (v *BodyAssetMetadata) getActionID() Action {
+	return ActionAssetMetadata
+}
+
+func (v *BodyAssetMetadata) serialize() ([]byte, error) {
+	buf := new(bytes.Buffer)
+
+	if v.Asset == nil {
+		return nil, fmt.Errorf("asset is empty")
+	}
+	MustWrite(buf, binary.BigEndian, v.Asset.Chain)
+	buf.Write(v.Asset.Address[:])
+	MustWrite(buf, binary.BigEndian, v.Asset.Decimals)
+	buf.Write(v.Symbol[:])
+	buf.Write(v.Name[:])
+
+	return buf.Bytes(), nil
+}
+
+func parseBodyMessage(r io.Reader) (*BodyMessage, error) {
+	b := &BodyMessage{}
+
+	if err := binary.Read(r, binary.BigEndian, &b.EmitterChain); err != nil {
+		return nil, fmt.Errorf("failed to read emitter chain: %w", err)
+	}
+
+	if n, err := r.Read(b.EmitterAddress[:]); err != nil || n != 32 {
+		return nil, fmt.Errorf("failed to read emitter address: %w", err)
+	}
+
+	if err := binary.Read(r, binary.BigEndian, &b.Nonce); err != nil {
+		return nil, fmt.Errorf("failed to read nonce: %w", err)
+	}
+
+	if err := binary.Read(r, binary.BigEndian, &b.Sequence); err != nil {
+		return nil, fmt.Errorf("failed to read sequence: %w", err)
+	}
+
+	payloadLen := uint16(0)
+	if err := binary.Read(r, binary.BigEndian, &payloadLen); err != nil {
+		return nil, fmt.Errorf("failed to read payload len: %w", err)
+	}
+	b.Payload = make([]byte, payloadLen)
+	if _, err := io.ReadFull(r, b.Payload); err != nil {
+		return nil, fmt.Errorf("failed to read payload: %w", err)
+	}
+
+	return b, nil
+}
+
+func (v *BodyMessage) getActionID() Action {
+	return ActionMessage
+}
+
+func (v *BodyMessage) serialize() ([]byte, error) {
+	buf := new(bytes.Buffer)
+
+	if len(v.Payload) > math.MaxUint16 {
+		return nil, fmt.Errorf("payload is too long")
+	}
+
+	MustWrite(buf, binary.BigEndian, v.EmitterChain)
+	buf.Write(v.EmitterAddress[:])
+	MustWrite(buf, binary.BigEndian, v.Nonce)
+	MustWrite(buf, binary.BigEndian, v.Sequence)
+	MustWrite(buf, binary.BigEndian, uint16(len(v.Payload)))
+	buf.Write(v.Payload)
+
 	return buf.Bytes(), nil
 }
 
+func parseBodyRefund(r io.Reader) (*BodyRefund, error) {
+	transfer, err := parseBodyTransfer(r)
+	if err != nil {
+		return nil, err
+	}
+
+	return &BodyRefund{Transfer: transfer}, nil
+}
+
+func (v *BodyRefund) getActionID() Action {
+	return ActionRefund
+}
+
+func (v *BodyRefund) serialize() ([]byte, error) {
+	if v.Transfer == nil {
+		return nil, fmt.Errorf("transfer is empty")
+	}
+	// The whole amount is returned to the sender
+	if v.Transfer.hasFee() {
+		return nil, fmt.Errorf("refunds cannot pay a relayer fee")
+	}
+
+	return v.Transfer.serialize()
+}
+
 // MustWrite calls binary.Write and panics on errors
 func MustWrite(w io.Writer, order binary.ByteOrder, data interface{}) {
 	if err := binary.Write(w, order, data); err != nil {
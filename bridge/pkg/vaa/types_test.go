@@ -43,6 +43,33 @@ func TestSerializeDeserialize(t *testing.T) {
 				},
 			},
 		},
+		{
+			name: "BodyTransferWithFee",
+			vaa: &VAA{
+				Version:          1,
+				GuardianSetIndex: 9,
+				Signatures: []*Signature{
+					{
+						Index:     1,
+						Signature: [65]byte{},
+					},
+				},
+				Timestamp: time.Unix(2837, 0),
+				Payload: &BodyTransfer{
+					Nonce:         38,
+					SourceChain:   2,
+					TargetChain:   1,
+					SourceAddress: Address{2, 1, 4},
+					TargetAddress: Address{2, 1, 3},
+					Asset: &AssetMeta{
+						Chain:   9,
+						Address: Address{9, 2, 4},
+					},
+					Amount: big.NewInt(29),
+					Fee:    big.NewInt(3),
+				},
+			},
+		},
 		{
 			name: "GuardianSetUpdate",
 			vaa: &VAA{
@@ -61,6 +88,116 @@ func TestSerializeDeserialize(t *testing.T) {
 				},
 			},
 		},
+		{
+			name: "GuardianSetUpdateWithThreshold",
+			vaa: &VAA{
+				Version:          1,
+				GuardianSetIndex: 9,
+				Signatures: []*Signature{
+					{
+						Index:     1,
+						Signature: [65]byte{},
+					},
+				},
+				Timestamp: time.Unix(2837, 0),
+				Payload: &BodyGuardianSetUpdate{
+					Keys:      []common.Address{{}, {}, {}},
+					NewIndex:  2,
+					Threshold: 2,
+				},
+			},
+		},
+		{
+			name: "Governance",
+			vaa: &VAA{
+				Version:          1,
+				GuardianSetIndex: 9,
+				Signatures: []*Signature{
+					{
+						Index:     1,
+						Signature: [65]byte{},
+					},
+				},
+				Timestamp: time.Unix(2837, 0),
+				Payload: &BodyGovernance{
+					TargetChain: 3,
+					Change:      4,
+					Data:        []byte{1},
+				},
+			},
+		},
+		{
+			name: "AssetMetadata",
+			vaa: &VAA{
+				Version:          1,
+				GuardianSetIndex: 9,
+				Signatures: []*Signature{
+					{
+						Index:     1,
+						Signature: [65]byte{},
+					},
+				},
+				Timestamp: time.Unix(2837, 0),
+				Payload: &BodyAssetMetadata{
+					Asset: &AssetMeta{
+						Chain:    2,
+						Address:  Address{9, 2, 4},
+						Decimals: 18,
+					},
+					Symbol: [32]byte{'W', 'E', 'T', 'H'},
+					Name:   [32]byte{'E', 't', 'h', 'e', 'r'},
+				},
+			},
+		},
+		{
+			name: "Message",
+			vaa: &VAA{
+				Version:          1,
+				GuardianSetIndex: 9,
+				Signatures: []*Signature{
+					{
+						Index:     1,
+						Signature: [65]byte{},
+					},
+				},
+				Timestamp: time.Unix(2837, 0),
+				Payload: &BodyMessage{
+					EmitterChain:   1,
+					EmitterAddress: Address{7, 7},
+					Nonce:          38,
+					Sequence:       12,
+					Payload:        []byte("hello"),
+				},
+			},
+		},
+		{
+			name: "Refund",
+			vaa: &VAA{
+				Version:          1,
+				GuardianSetIndex: 9,
+				Signatures: []*Signature{
+					{
+						Index:     1,
+						Signature: [65]byte{},
+					},
+				},
+				Timestamp: time.Unix(2837, 0),
+				Payload: &BodyRefund{
+					Transfer: &BodyTransfer{
+						Nonce:         38,
+						SourceChain:   1,
+						TargetChain:   1,
+						SourceAddress: Address{2, 1, 4},
+						TargetAddress: Address{2, 1, 4},
+						Asset: &AssetMeta{
+							Chain:   9,
+							Address: Address{9, 2, 4},
+						},
+						Amount: big.NewInt(29),
+					},
+				},
+			},
+		},
 	}
 	for _, test := range tests {
 		t.Run(test.name, func(t *testing.T) {
@@ -53,6 +53,8 @@ contract Wormhole is ReentrancyGuard {
     mapping(uint32 => GuardianSet) public guardian_sets;
     // Current active guardian set
     uint32 public guardian_set_index;
+    // Mapping of guardian_set_index => number of signatures required, 0 for the default quorum (2/3 + 1)
+    mapping(uint32 => uint8) public guardian_set_thresholds;
 
     // Period for which a guardian set stays active after it has been replaced
     uint32 public guardian_set_expirity;
@@ -99,8 +101,13 @@ contract Wormhole is ReentrancyGuard {
         GuardianSet memory guardian_set = guardian_sets[vaa_guardian_set_index];
         require(guardian_set.keys.length > 0, "invalid guardian set");
         require(guardian_set.expiration_time == 0 || guardian_set.expiration_time > block.timestamp, "guardian set has expired");
-        // We're using a fixed point number transformation with 1 decimal to deal with rounding.
-        require(((guardian_set.keys.length * 10 / 3) * 2) / 10 + 1 <= len_signers, "no quorum");
+        uint8 threshold = guardian_set_thresholds[vaa_guardian_set_index];
+        if (threshold != 0) {
+            require(threshold <= len_signers, "no quorum");
+        } else {
+            // We're using a fixed point number transformation with 1 decimal to deal with rounding.
+            require(((guardian_set.keys.length * 10 / 3) * 2) / 10 + 1 <= len_signers, "no quorum");
+        }
 
         int16 last_index = - 1;
         for (uint i = 0; i < len_signers; i++) {
@@ -119,11 +126,20 @@ contract Wormhole is ReentrancyGuard {
         bytes memory payload = vaa.slice(offset + 5, vaa.length - (offset + 5));
 
         // Process VAA
-        if (action == 0x01) {
+        if (action == 0x01 || action == 0x03) {
             require(vaa_guardian_set_index == guardian_set_index, "only the current guardian set can change the guardian set");
-            vaaUpdateGuardianSet(payload);
+            vaaUpdateGuardianSet(payload, action == 0x03);
         } else if (action == 0x10) {
             vaaTransfer(payload);
+        } else if (action == 0x12) {
+            // Messages are verified and consumed by the contracts they are addressed to
+            revert("messages are not executed by the bridge");
+        } else if (action == 0x15) {
+            // Transfers out of Ethereum are final, refunds only exist for transfers out of Solana
+            revert("refunds are not supported on Ethereum");
+        } else if (action == 0x02 || action == 0x11) {
+            // Governance and asset metadata are not implemented on Ethereum yet, wrapped assets use default metadata
+            revert("VAA action not supported on Ethereum");
         } else {
             revert("invalid VAA action");
         }
@@ -132,7 +148,7 @@ contract Wormhole is ReentrancyGuard {
         consumedVAAs[hash] = true;
     }
 
-    function vaaUpdateGuardianSet(bytes memory data) private {
+    function vaaUpdateGuardianSet(bytes memory data, bool with_threshold) private {
         uint32 new_guardian_set_index = data.toUint32(0);
         require(new_guardian_set_index == guardian_set_index + 1, "index must increase in steps of 1");
         uint8 len = data.toUint8(4);
@@ -143,11 +159,19 @@ contract Wormhole is ReentrancyGuard {
             new_guardians[i] = addr;
         }
 
+        // Updates with action 0x03 append the number of signatures the new set requires
+        uint8 threshold = 0;
+        if (with_threshold) {
+            threshold = data.toUint8(5 + uint(len) * 20);
+            require(threshold > 0 && threshold <= len, "invalid threshold");
+        }
+
         uint32 old_guardian_set_index = guardian_set_index;
         guardian_set_index = new_guardian_set_index;
 
         GuardianSet memory new_guardian_set = GuardianSet(new_guardians, 0);
         guardian_sets[guardian_set_index] = new_guardian_set;
+        guardian_set_thresholds[guardian_set_index] = threshold;
         guardian_sets[old_guardian_set_index].expiration_time = uint32(block.timestamp) + guardian_set_expirity;
 
         emit LogGuardianSetChanged(old_guardian_set_index, guardian_set_index);
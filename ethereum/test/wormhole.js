@@ -224,6 +224,34 @@ contract("Wormhole", function () {
         await bridge.submitVAA("0x01000000020500e94bec8a17bd313522cdfea30cec5406a41a4cc4b6ec416a633ebe3aca070ae448e370e0a2e7c67fed04a2b825f56cf226c76e6ecd2e71865642393bf729dad80101ccf89506bef58d8cb12baabd60e3304cfb90ef0ef0657caba9c37ffa0d34a54c3aacd1a475ef4c72f24e8d9ce1e2de51e580ce85b18356436b6cda9e2ae9abc001033e9b4ff5fb545e964e907349e3dab0057c408c832bb31fb76fae7f81c3e488ea4897ce14db61c46d1169bd64b449498b1a18dee4de0ef2038b1c7e3a4a0239a0010432eac9532a4c0ce279d6a3018a5ea0d74402eb6969df5d444f20e0cca66d3b4c53e41cb18648f64af100c7410692e83fa16e5696b1f5f0d517653b003e22689800055859330bd1fee76d99728803fa26d739e494e1a232f5658150c2a2c97e1c9722793bdd83bd7cbb4a39b587b45093ee76187c72dfd68d64b7c0abc32bfef5d55c0000000fa010000000390102020105000000000000000000000000000000000000000000000000000000000000000000000000000000000090f8bf6a479f320ead074411a4b0e7944ea8c9c1010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a7640000")
     });
 
+    it("should honor the threshold of a guardian set", async function () {
+        // Fresh bridge with a single guardian, the shared one has moved on to a larger set
+        let bridge = await Wormhole.new({
+            keys: ["0x19e7e376e7c213b7e7e7e46cc70a5dd086daff2a"],
+            expiration_time: 0
+        }, WrappedAsset.address, 1000);
+
+        // Threshold of 2 with a single key; timestamp 4000
+        let threw = false;
+        try {
+            await bridge.submitVAA("0x01000000000100ea5fc749eca1f2cf9b01d868afc1497cb513033463fd94216adaf78bff4a29786aaa233019de424e72594acba1c0cddb61ccd7f3fa377347c36e34b37ec41c200000000fa003000000010119e7e376e7c213b7e7e7e46cc70a5dd086daff2a02")
+        } catch (e) {
+            threw = true
+            assert.equal(e.reason, "invalid threshold")
+        }
+        assert.isTrue(threw, "unreachable threshold was accepted")
+
+        // Guardian set 1 with 2 keys and a threshold of 1; timestamp 4000
+        await bridge.submitVAA("0x010000000001004274466d948bd7b8cc19d08e53ff9224a4651692bf26dc0ac5452cd0e65d4e0e3f9c229d677f78c4a0ce0a4af2f21c5b00c2113d49cac1370a30113248d5f1c30000000fa003000000010219e7e376e7c213b7e7e7e46cc70a5dd086daff2ae06a9adfeb38a8ee4d00e89307c016d0749679bd01")
+        assert.equal(await bridge.guardian_set_index(), 1)
+        assert.equal(await bridge.guardian_set_thresholds(1), 1)
+
+        // A single signature is enough for set 1 although the default quorum of 2 keys is 2
+        await bridge.submitVAA("0x0100000001010008e9a9653c9fac6b4b045f02cd80906c97d8c68d823282e305ef1c7b8ab3b7357693e8d0d00e716d246e8eb977eb8f93858a36373fc6f49faabaaac2256fa66a0100000fa001000000020119e7e376e7c213b7e7e7e46cc70a5dd086daff2a")
+        assert.equal(await bridge.guardian_set_index(), 2)
+        assert.equal(await bridge.guardian_set_thresholds(2), 0)
+    });
+
     it("should correctly adjust decimals", async function () {
         let bridge = await Wormhole.deployed();
         let token = await ERC20.new("Test Token", "TKN");
@@ -23,17 +23,18 @@ use tonic::{transport::Server, Code, Request, Response, Status};
 use service::{
     agent_server::{Agent, AgentServer},
     lockup_event::Event,
-    Empty, LockupEvent, LockupEventNew, LockupEventVaaPosted, SubmitVaaRequest, SubmitVaaResponse,
+    Empty, LockupEvent, LockupEventMessage, LockupEventNew, LockupEventVaaPosted, SubmitVaaRequest,
+    SubmitVaaResponse,
     GetBalanceResponse, GetBalanceRequest,
     WatchLockupsRequest,
 };
 use spl_bridge::{
     instruction::{post_vaa, verify_signatures, VerifySigPayload, CHAIN_ID_SOLANA},
-    state::{Bridge, GuardianSet, TransferOutProposal},
-    vaa::VAA,
+    state::{Bridge, TransferOutProposal},
 };
+use wormhole_vaa::VAA;
 
-use crate::monitor::PubsubClient;
+use crate::monitor::{ProgramNotificationMessage, PubsubClient};
 
 mod monitor;
 mod socket;
@@ -84,7 +85,7 @@ impl Agent for AgentImpl {
             };
             let verify_txs = pack_sig_verification_txs(&rpc, &bridge, &vaa, &key)?;
 
-            // Strip signatures
+            // Strip signatures, the program attaches the signatures it verified to the posted VAA
             vaa.signatures = Vec::new();
             let ix = match post_vaa(&bridge, &key.pubkey(), vaa.serialize().unwrap()) {
                 Ok(v) => v,
@@ -166,75 +167,94 @@ impl Agent for AgentImpl {
         let rpc_url = self.rpc_url.clone();
 
         tokio::spawn(async move {
-            let _rpc = RpcClient::new(rpc_url.to_string());
+            let rpc = RpcClient::new(rpc_url.to_string());
             let sub = PubsubClient::program_subscribe(&url, &bridge).unwrap();
             // looping and sending our response using stream
             loop {
                 let item = sub.1.recv();
                 match item {
                     Ok(v) => {
-                        // We only want to track lockups
-                        if v.value.account.data.len() != size_of::<TransferOutProposal>() {
-                            continue;
-                        }
-
-                        println!("lockup changed in slot: {}", v.context.slot);
-
-                        let b = match Bridge::unpack_immutable::<TransferOutProposal>(
-                            v.value.account.data.as_slice(),
-                        ) {
-                            Ok(v) => v,
-                            Err(e) => {
-                                println!("failed to deserialize lockup: {}", e);
+                        // Messages vary in size and are recognized by their derivation
+                        let event = if let Some(event) = message_event(&rpc, &bridge, &v) {
+                            event
+                        } else {
+                            // We only want to track lockups
+                            if v.value.account.data.len() != size_of::<TransferOutProposal>() {
                                 continue;
                             }
-                        };
 
-                        let mut amount_b: [u8; 32] = [0; 32];
-                        b.amount.to_big_endian(&mut amount_b);
-
-                        let event = if b.vaa_time == 0 {
-                            // The Lockup was created
-                            LockupEvent {
-                                slot: v.context.slot,
-                                lockup_address: v.value.pubkey.to_string(),
-                                time: b.lockup_time as u64,
-                                event: Some(Event::New(LockupEventNew {
-                                    nonce: b.nonce,
-                                    source_chain: CHAIN_ID_SOLANA as u32,
-                                    target_chain: b.to_chain_id as u32,
-                                    source_address: b.source_address.to_vec(),
-                                    target_address: b.foreign_address.to_vec(),
-                                    token_chain: b.asset.chain as u32,
-                                    token_address: b.asset.address.to_vec(),
-                                    token_decimals: b.asset.decimals as u32,
-                                    amount: amount_b.to_vec(),
-                                })),
+                            println!("lockup changed in slot: {}", v.context.slot);
+
+                            let b = match Bridge::unpack_immutable::<TransferOutProposal>(
+                                v.value.account.data.as_slice(),
+                            ) {
+                                Ok(v) => v,
+                                Err(e) => {
+                                    println!("failed to deserialize lockup: {}", e);
+                                    continue;
+                                }
+                            };
+
+                            // Refunded transfers must not be signed anymore
+                            if b.refunded {
+                                continue;
                             }
-                        } else {
-                            // The VAA was submitted
-                            LockupEvent {
-                                slot: v.context.slot,
-                                lockup_address: v.value.pubkey.to_string(),
-                                time: b.lockup_time as u64,
-                                event: Some(Event::VaaPosted(LockupEventVaaPosted {
-                                    nonce: b.nonce,
-                                    source_chain: CHAIN_ID_SOLANA as u32,
-                                    target_chain: b.to_chain_id as u32,
-                                    source_address: b.source_address.to_vec(),
-                                    target_address: b.foreign_address.to_vec(),
-                                    token_chain: b.asset.chain as u32,
-                                    token_address: b.asset.address.to_vec(),
-                                    token_decimals: b.asset.decimals as u32,
-                                    amount: amount_b.to_vec(),
-                                    vaa: b.vaa.to_vec(),
-                                })),
+
+                            let mut amount_b: [u8; 32] = [0; 32];
+                            b.amount.to_big_endian(&mut amount_b);
+                            let mut fee_b: [u8; 32] = [0; 32];
+                            b.fee.to_big_endian(&mut fee_b);
+
+                            if b.vaa_time == 0 {
+                                // The Lockup was created
+                                LockupEvent {
+                                    slot: v.context.slot,
+                                    lockup_address: v.value.pubkey.to_string(),
+                                    time: b.lockup_time as u64,
+                                    event: Some(Event::New(LockupEventNew {
+                                        nonce: b.nonce,
+                                        source_chain: CHAIN_ID_SOLANA as u32,
+                                        target_chain: b.to_chain_id as u32,
+                                        source_address: b.source_address.to_vec(),
+                                        target_address: b.foreign_address.to_vec(),
+                                        token_chain: b.asset.chain as u32,
+                                        token_address: b.asset.address.to_vec(),
+                                        token_decimals: b.asset.decimals as u32,
+                                        amount: amount_b.to_vec(),
+                                        sequence: b.sequence,
+                                        fee: fee_b.to_vec(),
+                                    })),
+                                }
+                            } else {
+                                // The VAA was submitted, it is stored in a separate account
+                                let vaa = match fetch_posted_vaa(&rpc, &b.posted_vaa) {
+                                    Ok(v) => v,
+                                    Err(e) => {
+                                        println!("{}", e);
+                                        continue;
+                                    }
+                                };
+
+                                LockupEvent {
+                                    slot: v.context.slot,
+                                    lockup_address: v.value.pubkey.to_string(),
+                                    time: b.lockup_time as u64,
+                                    event: Some(Event::VaaPosted(LockupEventVaaPosted {
+                                        nonce: b.nonce,
+                                        source_chain: CHAIN_ID_SOLANA as u32,
+                                        target_chain: b.to_chain_id as u32,
+                                        source_address: b.source_address.to_vec(),
+                                        target_address: b.foreign_address.to_vec(),
+                                        token_chain: b.asset.chain as u32,
+                                        token_address: b.asset.address.to_vec(),
+                                        token_decimals: b.asset.decimals as u32,
+                                        amount: amount_b.to_vec(),
+                                        vaa,
+                                    })),
+                                }
                             }
                         };
 
-                        let mut amount_b: [u8; 32] = [0; 32];
-                        b.amount.to_big_endian(&mut amount_b);
-
                         if let Err(e) = tx.send(Ok(event)).await {
                             println!("sending event failed: {}", e);
                             return;
@@ -268,6 +288,59 @@ impl Agent for AgentImpl {
     }
 }
 
+/// Builds the event of a message account, returns `None` if the account is not a message
+fn message_event(
+    rpc: &RpcClient,
+    bridge: &Pubkey,
+    v: &ProgramNotificationMessage,
+) -> Option<LockupEvent> {
+    let (message, payload) = Bridge::unpack_message(&v.value.account.data).ok()?;
+    let bridge_key = Bridge::derive_bridge_id(bridge).ok()?;
+    let expected_message =
+        Bridge::derive_message_id(bridge, &bridge_key, &message.emitter, message.nonce).ok()?;
+    if expected_message != v.value.pubkey {
+        return None;
+    }
+
+    println!("message changed in slot: {}", v.context.slot);
+
+    // The VAA is empty until it was posted for data availability
+    let vaa = if message.vaa_time == 0 {
+        Vec::new()
+    } else {
+        match fetch_posted_vaa(rpc, &message.posted_vaa) {
+            Ok(v) => v,
+            Err(e) => {
+                println!("{}", e);
+                return None;
+            }
+        }
+    };
+
+    Some(LockupEvent {
+        slot: v.context.slot,
+        lockup_address: v.value.pubkey.to_string(),
+        time: message.submission_time as u64,
+        event: Some(Event::Message(LockupEventMessage {
+            emitter_address: message.emitter.to_bytes().to_vec(),
+            nonce: message.nonce,
+            sequence: message.sequence,
+            payload: payload.to_vec(),
+            vaa,
+        })),
+    })
+}
+
+/// Fetches the signed VAA stored in a PostedVAA account
+fn fetch_posted_vaa(rpc: &RpcClient, posted_vaa: &Pubkey) -> Result<Vec<u8>, String> {
+    let data = rpc
+        .get_account_data(posted_vaa)
+        .map_err(|e| format!("failed to fetch posted vaa: {}", e))?;
+    let (_, vaa) = Bridge::unpack_posted_vaa(&data)
+        .map_err(|e| format!("failed to deserialize posted vaa: {}", e))?;
+    Ok(vaa.to_vec())
+}
+
 fn pack_sig_verification_txs<'a>(
     rpc: &RpcClient,
     bridge: &Pubkey,
@@ -289,7 +362,7 @@ fn pack_sig_verification_txs<'a>(
         .value
         .unwrap_or_default();
     let data = guardian_account.data;
-    let guardian_set: &GuardianSet = Bridge::unpack_immutable(data.as_slice()).unwrap();
+    let (guardian_set, keys) = Bridge::unpack_guardian_set_immutable(data.as_slice()).unwrap();
 
     // Map signatures to guardian set
     let mut signature_items: Vec<SignatureItem> = Vec::new();
@@ -303,10 +376,20 @@ fn pack_sig_verification_txs<'a>(
         item.signature[0..32].copy_from_slice(&s.r);
         item.signature[32..64].copy_from_slice(&s.s);
         item.signature[64] = s.v;
-        item.key = guardian_set.keys[s.index as usize];
+        item.key = match keys.get(s.index as usize) {
+            Some(key) => *key,
+            None => {
+                return Err(Status::new(
+                    Code::InvalidArgument,
+                    format!("signer {} is not part of the guardian set", s.index),
+                ));
+            }
+        };
 
         signature_items.push(item);
     }
+    // The program expects the signatures ordered by signer
+    signature_items.sort_by_key(|item| item.index);
 
     let vaa_hash = match vaa.body_hash() {
         Ok(v) => v,
@@ -333,7 +416,11 @@ fn pack_sig_verification_txs<'a>(
     let mut verify_txs: Vec<Transaction> = Vec::new();
     for (tx_index, chunk) in signature_items.chunks(6).enumerate() {
         let mut secp_payload = Vec::new();
-        let mut signature_status = [-1i8; 20];
+        let mut payload = VerifySigPayload {
+            hash: vaa_hash,
+            initial_creation: tx_index == 0,
+            ..Default::default()
+        };
 
         let data_offset = 1 + chunk.len() * 11;
         let message_offset = data_offset + chunk.len() * 85;
@@ -350,7 +437,7 @@ fn pack_sig_verification_txs<'a>(
             secp_payload.write_u16::<LittleEndian>(message_offset as u16);
             secp_payload.write_u16::<LittleEndian>(vaa_body.len() as u16);
             secp_payload.write_u8(0);
-            signature_status[s.index as usize] = i as i8;
+            payload.set_signer(s.index);
         }
 
         // Write signatures and addresses
@@ -368,12 +455,6 @@ fn pack_sig_verification_txs<'a>(
             accounts: vec![],
         };
 
-        let payload = VerifySigPayload {
-            signers: signature_status,
-            hash: vaa_hash,
-            initial_creation: tx_index == 0,
-        };
-
         let verify_ix = match verify_signatures(
             &bridge,
             &signature_acc,
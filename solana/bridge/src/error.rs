@@ -106,6 +106,51 @@ pub enum Error {
     /// Insufficient fees
     #[error("InsufficientFees")]
     InsufficientFees,
+    /// The VAA is older than the VAA expiration time
+    #[error("VAAExpired")]
+    VAAExpired,
+    /// The VAA has not expired yet or was not submitted
+    #[error("VAANotExpired")]
+    VAANotExpired,
+    /// The VAA is addressed to a different chain
+    #[error("WrongTargetChain")]
+    WrongTargetChain,
+    /// The message payload is longer than the maximum size
+    #[error("MessageTooLong")]
+    MessageTooLong,
+    /// The receiver program or its token account does not match the transfer
+    #[error("InvalidReceiver")]
+    InvalidReceiver,
+    /// The relayer fee account is not a token account of the submitter of the VAA
+    #[error("InvalidRelayerFeeAccount")]
+    InvalidRelayerFeeAccount,
+    /// Transfers in this direction are paused by governance
+    #[error("BridgePaused")]
+    BridgePaused,
+    /// The recipient wallet or its associated token account does not match the transfer
+    #[error("InvalidRecipient")]
+    InvalidRecipient,
+    /// An amount overflows when it is scaled to the decimals of a token
+    #[error("AmountOverflow")]
+    AmountOverflow,
+    /// The amount of a token transfer does not fit into the u64 amounts of SPL tokens
+    #[error("AmountTooLarge")]
+    AmountTooLarge,
+    /// Token transfers must have a non-zero amount
+    #[error("ZeroAmount")]
+    ZeroAmount,
+    /// The transfer was refunded and cannot be completed anymore
+    #[error("TransferRefunded")]
+    TransferRefunded,
+    /// The governance VAA is not newer than the last applied one
+    #[error("OldGovernanceVAA")]
+    OldGovernanceVAA,
+    /// The relayer fee of a transfer exceeds its amount
+    #[error("FeeTooHigh")]
+    FeeTooHigh,
+    /// The configured token program is not the SPL token program instructions are built for
+    #[error("InvalidTokenProgram")]
+    InvalidTokenProgram,
 }
 
 impl From<Error> for ProgramError {
@@ -120,6 +165,21 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<wormhole_vaa::Error> for Error {
+    fn from(e: wormhole_vaa::Error) -> Self {
+        match e {
+            wormhole_vaa::Error::UnexpectedEnd => Error::ParseFailed,
+            wormhole_vaa::Error::MissingPayload => Error::InvalidVAAAction,
+            wormhole_vaa::Error::TrailingBytes
+            | wormhole_vaa::Error::LengthOverflow
+            | wormhole_vaa::Error::InvalidValue => Error::InvalidVAAFormat,
+            wormhole_vaa::Error::InvalidSignature | wormhole_vaa::Error::RecoveryFailed => {
+                Error::InvalidVAASignature
+            }
+        }
+    }
+}
+
 impl<T> DecodeError<T> for Error {
     fn type_of() -> &'static str {
         "Swap Error"
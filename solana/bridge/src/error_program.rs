@@ -40,6 +40,21 @@ impl PrintProgramError for Error {
             Error::VAAAlreadySubmitted => info!("Error: VAAAlreadySubmitted"),
             Error::GuardianSetMismatch => info!("Error: GuardianSetMismatch"),
             Error::InsufficientFees => info!("Error: InsufficientFees"),
+            Error::VAAExpired => info!("Error: VAAExpired"),
+            Error::VAANotExpired => info!("Error: VAANotExpired"),
+            Error::WrongTargetChain => info!("Error: WrongTargetChain"),
+            Error::MessageTooLong => info!("Error: MessageTooLong"),
+            Error::InvalidReceiver => info!("Error: InvalidReceiver"),
+            Error::InvalidRelayerFeeAccount => info!("Error: InvalidRelayerFeeAccount"),
+            Error::BridgePaused => info!("Error: BridgePaused"),
+            Error::InvalidRecipient => info!("Error: InvalidRecipient"),
+            Error::AmountOverflow => info!("Error: AmountOverflow"),
+            Error::AmountTooLarge => info!("Error: AmountTooLarge"),
+            Error::ZeroAmount => info!("Error: ZeroAmount"),
+            Error::TransferRefunded => info!("Error: TransferRefunded"),
+            Error::OldGovernanceVAA => info!("Error: OldGovernanceVAA"),
+            Error::FeeTooHigh => info!("Error: FeeTooHigh"),
+            Error::InvalidTokenProgram => info!("Error: InvalidTokenProgram"),
         }
     }
 }
@@ -12,18 +12,28 @@ use solana_program::{
 
 use crate::{
     instruction::BridgeInstruction::{
-        CreateWrapped, Initialize, PokeProposal, PostVAA, TransferOut, VerifySignatures,
+        EvictClaimedVAA, EvictTransferOut, Initialize, PokeProposal, PostVAA, PublishMessage,
+        TransferOut, VerifySignatures,
     },
-    state::{AssetMeta, Bridge, BridgeConfig},
-    vaa::{VAABody, VAA},
+    error::Error,
+    state::{associated_token_program, AssetMeta, Bridge, BridgeConfig, GuardianAddress},
+    vaa::{BodyTransfer, GovernanceChange, VAABody, HEADER_LEN, SIGNATURE_LEN, VAA},
 };
 
 /// chain id of this chain
 pub const CHAIN_ID_SOLANA: u8 = 1;
-/// maximum number of guardians
-pub const MAX_LEN_GUARDIAN_KEYS: usize = 20;
-/// maximum size of a posted VAA
-pub const MAX_VAA_SIZE: usize = 1000;
+/// maximum number of guardians. The keys of a guardian set are carried by the `Initialize`
+/// instruction and by guardian set update VAAs, both of which need to fit into a transaction.
+pub const MAX_LEN_GUARDIAN_KEYS: usize = 32;
+/// maximum size of the body of a posted VAA
+pub const MAX_VAA_BODY_SIZE: usize = 1000;
+/// maximum size of a posted VAA, which carries a signature of up to every guardian of the set
+pub const MAX_VAA_SIZE: usize =
+    HEADER_LEN + SIGNATURE_LEN * MAX_LEN_GUARDIAN_KEYS + MAX_VAA_BODY_SIZE;
+/// maximum size of the payload of a published message
+pub const MAX_MESSAGE_PAYLOAD_SIZE: usize = 512;
+/// maximum decimals of wrapped assets and of the amounts of native tokens transferred out
+pub const MAX_WRAPPED_DECIMALS: u8 = 8;
 /// size of a foreign address in bytes
 const FOREIGN_ADDRESS_SIZE: usize = 32;
 
@@ -34,13 +44,10 @@ pub type GuardianKey = [u8; 64];
 /// address on a foreign chain
 pub type ForeignAddress = [u8; FOREIGN_ADDRESS_SIZE];
 
-#[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Debug)]
 pub struct InitializePayload {
-    /// number of initial guardians
-    pub len_guardians: u8,
     /// guardians that are allowed to sign mints
-    pub initial_guardian: [[u8; 20]; MAX_LEN_GUARDIAN_KEYS],
+    pub initial_guardian: Vec<GuardianAddress>,
     /// config for the bridge
     pub config: BridgeConfig,
 }
@@ -58,6 +65,8 @@ pub struct TransferOutPayload {
     pub target: ForeignAddress,
     /// unique nonce of the transfer
     pub nonce: u32,
+    /// part of `amount` paid to the relayer of the VAA on the target chain, zero if none
+    pub fee: U256,
 }
 
 #[repr(C)]
@@ -73,18 +82,53 @@ pub struct TransferOutPayloadRaw {
     pub target: ForeignAddress,
     /// unique nonce of the transfer
     pub nonce: u32,
+    /// relayer fee
+    pub fee: [u8; 32],
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
+pub struct PublishMessagePayload {
+    /// nonce of the message, unique per emitter
+    pub nonce: u32,
+    /// arbitrary payload of the message
+    pub payload: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct VerifySigPayload {
     /// hash of the VAA
     pub hash: [u8; 32],
-    /// instruction indices of signers (-1 for missing)
-    pub signers: [i8; MAX_LEN_GUARDIAN_KEYS],
+    /// bitmap of the guardians that signed, bit `i % 8` of byte `i / 8` is set for guardian `i`.
+    /// The signatures in the secp instruction are ordered by guardian index.
+    pub signers: Vec<u8>,
     /// indicates whether this verification should only succeed if the sig account does not exist
     pub initial_creation: bool,
 }
 
+impl VerifySigPayload {
+    /// Marks the guardian at `index` as a signer
+    pub fn set_signer(&mut self, index: u8) {
+        let byte = index as usize / 8;
+        if self.signers.len() <= byte {
+            self.signers.resize(byte + 1, 0);
+        }
+        self.signers[byte] |= 1 << (index % 8);
+    }
+
+    /// Indices of the guardians that signed, in ascending order
+    pub fn signer_indices(&self) -> Vec<usize> {
+        let mut indices = Vec::new();
+        for (byte, bits) in self.signers.iter().enumerate() {
+            for bit in 0..8 {
+                if bits & (1 << bit) != 0 {
+                    indices.push(byte * 8 + bit);
+                }
+            }
+        }
+        indices
+    }
+}
+
 /// Instructions supported by the SwapInfo program.
 #[repr(C)]
 pub enum BridgeInstruction {
@@ -126,12 +170,12 @@ pub enum BridgeInstruction {
     /// See docs for accounts
     PostVAA(VAAData),
 
-    /// Deletes a `proposal` after the `VAA_EXPIRATION_TIME` is over to free up space on chain.
-    /// This returns the rent to the sender.
+    /// Deletes a `proposal` after the `vaa_expiration_time` is over to free up space on chain.
+    /// This returns the rent to the sender, which can be any signer.
     EvictTransferOut(),
 
-    /// Deletes a `ExecutedVAA` after the `VAA_EXPIRATION_TIME` is over to free up space on chain.
-    /// This returns the rent to the sender.
+    /// Deletes a `ExecutedVAA` and its `PostedVAA` after the `vaa_expiration_time` is over to free
+    /// up space on chain. This returns the rent to the sender, which can be any signer.
     EvictClaimedVAA(),
 
     /// Pokes a proposal with no valid VAAs attached so guardians reprocess it.
@@ -140,8 +184,19 @@ pub enum BridgeInstruction {
     /// Verifies signature instructions
     VerifySignatures(VerifySigPayload),
 
-    /// Creates a new wrapped asset
-    CreateWrapped(AssetMeta),
+    /// Publishes an arbitrary message of `emitter` that guardians sign as a VAA.
+    ///
+    ///   0. `[]` The bridge program
+    ///   1. `[]` The System program
+    ///   2. `[]` The rent SysVar
+    ///   3. `[]` The clock SysVar
+    ///   4. `[]` The instructions SysVar
+    ///   5. `[writable, derived]` The bridge config
+    ///   6. `[writable, derived]` The fee state
+    ///   7. `[writable, derived, empty]` The new message account
+    ///   8. `[signer]` The emitter of the message, a user or a program signing via CPI
+    ///   9. `[writable, signer]` The fee payer for new account creation
+    PublishMessage(PublishMessagePayload),
 }
 
 impl BridgeInstruction {
@@ -152,9 +207,23 @@ impl BridgeInstruction {
         }
         Ok(match input[0] {
             0 => {
-                let payload: &InitializePayload = unpack(input)?;
-
-                Initialize(*payload)
+                let config: &BridgeConfig = unpack(input)?;
+                let keys = &input[1 + size_of::<BridgeConfig>()..];
+                if keys.len() % size_of::<GuardianAddress>() != 0 {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+
+                Initialize(InitializePayload {
+                    initial_guardian: keys
+                        .chunks(size_of::<GuardianAddress>())
+                        .map(|k| {
+                            let mut key = GuardianAddress::default();
+                            key.copy_from_slice(k);
+                            key
+                        })
+                        .collect(),
+                    config: *config,
+                })
             }
             1 => {
                 let payload: &TransferOutPayloadRaw = unpack(input)?;
@@ -166,22 +235,41 @@ impl BridgeInstruction {
                     asset: payload.asset,
                     target: payload.target,
                     nonce: payload.nonce,
+                    fee: U256::from_big_endian(&payload.fee),
                 })
             }
             2 => {
                 let payload: VAAData = input[1..].to_vec();
                 PostVAA(payload)
             }
+            3 => EvictTransferOut(),
+            4 => EvictClaimedVAA(),
             5 => PokeProposal(),
             6 => {
-                let payload: &VerifySigPayload = unpack(input)?;
-
-                VerifySignatures(*payload)
+                if input.len() < 34 {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                let mut hash = [0u8; 32];
+                hash.copy_from_slice(&input[1..33]);
+
+                VerifySignatures(VerifySigPayload {
+                    hash,
+                    initial_creation: input[33] != 0,
+                    signers: input[34..].to_vec(),
+                })
             }
-            7 => {
-                let payload: &AssetMeta = unpack(input)?;
-
-                CreateWrapped(*payload)
+            // 7 was `CreateWrapped`, wrapped assets are now created by asset metadata VAAs
+            8 => {
+                if input.len() < 5 {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                let mut nonce = [0u8; 4];
+                nonce.copy_from_slice(&input[1..5]);
+
+                PublishMessage(PublishMessagePayload {
+                    nonce: u32::from_le_bytes(nonce),
+                    payload: input[5..].to_vec(),
+                })
             }
             _ => return Err(ProgramError::InvalidInstructionData),
         })
@@ -193,13 +281,16 @@ impl BridgeInstruction {
 
         match self {
             Self::Initialize(payload) => {
-                output.resize(size_of::<InitializePayload>() + 1, 0);
+                output.resize(size_of::<BridgeConfig>() + 1, 0);
                 output[0] = 0;
                 #[allow(clippy::cast_ptr_alignment)]
                     let value = unsafe {
-                    &mut *(&mut output[size_of::<u8>()] as *mut u8 as *mut InitializePayload)
+                    &mut *(&mut output[size_of::<u8>()] as *mut u8 as *mut BridgeConfig)
                 };
-                *value = payload;
+                *value = payload.config;
+                for key in payload.initial_guardian.iter() {
+                    output.extend_from_slice(key);
+                }
             }
             Self::TransferOut(payload) => {
                 output.resize(size_of::<TransferOutPayloadRaw>() + 1, 0);
@@ -211,6 +302,8 @@ impl BridgeInstruction {
 
                 let mut amount_bytes = [0u8; 32];
                 payload.amount.to_big_endian(&mut amount_bytes);
+                let mut fee_bytes = [0u8; 32];
+                payload.fee.to_big_endian(&mut fee_bytes);
 
                 *value = TransferOutPayloadRaw {
                     amount: amount_bytes,
@@ -218,6 +311,7 @@ impl BridgeInstruction {
                     asset: payload.asset,
                     target: payload.target,
                     nonce: payload.nonce,
+                    fee: fee_bytes,
                 };
             }
             Self::PostVAA(payload) => {
@@ -239,21 +333,17 @@ impl BridgeInstruction {
                 output[0] = 5;
             }
             Self::VerifySignatures(payload) => {
-                output.resize(size_of::<VerifySigPayload>() + 1, 0);
+                output.resize(1, 0);
                 output[0] = 6;
-                #[allow(clippy::cast_ptr_alignment)]
-                    let value = unsafe {
-                    &mut *(&mut output[size_of::<u8>()] as *mut u8 as *mut VerifySigPayload)
-                };
-                *value = payload;
+                output.extend_from_slice(&payload.hash);
+                output.push(payload.initial_creation as u8);
+                output.extend_from_slice(&payload.signers);
             }
-            Self::CreateWrapped(payload) => {
-                output.resize(size_of::<AssetMeta>() + 1, 0);
-                output[0] = 7;
-                #[allow(clippy::cast_ptr_alignment)]
-                    let value =
-                    unsafe { &mut *(&mut output[size_of::<u8>()] as *mut u8 as *mut AssetMeta) };
-                *value = payload;
+            Self::PublishMessage(payload) => {
+                output.resize(1, 0);
+                output[0] = 8;
+                output.extend_from_slice(&payload.nonce.to_le_bytes());
+                output.extend_from_slice(&payload.payload);
             }
         }
         Ok(output)
@@ -268,17 +358,15 @@ pub fn initialize(
     initial_guardian: Vec<[u8; 20]>,
     config: &BridgeConfig,
 ) -> Result<Instruction, ProgramError> {
-    if initial_guardian.len() > MAX_LEN_GUARDIAN_KEYS {
+    if initial_guardian.is_empty() || initial_guardian.len() > MAX_LEN_GUARDIAN_KEYS {
         return Err(ProgramError::InvalidArgument);
     }
-    let mut initial_g = [[0u8; 20]; MAX_LEN_GUARDIAN_KEYS];
-    for (i, key) in initial_guardian.iter().enumerate() {
-        initial_g[i] = *key;
+    if config.token_program != spl_token::id() {
+        return Err(ProgramError::InvalidArgument);
     }
     let data = BridgeInstruction::Initialize(InitializePayload {
         config: *config,
-        len_guardians: initial_guardian.len() as u8,
-        initial_guardian: initial_g,
+        initial_guardian,
     })
         .serialize()?;
 
@@ -322,6 +410,7 @@ pub fn transfer_out(
         token_account.to_bytes(),
         t.nonce,
     )?;
+    let fee_state_key = Bridge::derive_fee_state_id(program_id, &bridge_key)?;
 
     let mut accounts = vec![
         AccountMeta::new_readonly(*program_id, false),
@@ -331,16 +420,20 @@ pub fn transfer_out(
         AccountMeta::new_readonly(solana_program::sysvar::clock::id(), false),
         AccountMeta::new_readonly(solana_program::sysvar::instructions::id(), false),
         AccountMeta::new(*token_account, false),
-        AccountMeta::new_readonly(bridge_key, false),
+        AccountMeta::new(bridge_key, false),
+        AccountMeta::new(fee_state_key, false),
         AccountMeta::new(transfer_key, false),
         AccountMeta::new(*token_mint, false),
         AccountMeta::new(*payer, true),
     ];
 
-    // If the token is a native solana token add a custody account
+    // If the token is a native solana token add a custody account, otherwise the wrapped meta
     if t.asset.chain == CHAIN_ID_SOLANA {
         let custody_key = Bridge::derive_custody_id(program_id, &bridge_key, token_mint)?;
         accounts.push(AccountMeta::new(custody_key, false));
+    } else {
+        let wrapped_meta_key = Bridge::derive_wrapped_meta_id(program_id, &bridge_key, token_mint)?;
+        accounts.push(AccountMeta::new_readonly(wrapped_meta_key, false));
     }
 
     Ok(Instruction {
@@ -359,7 +452,7 @@ pub fn verify_signatures(
     guardian_set_id: u32,
     p: &VerifySigPayload,
 ) -> Result<Instruction, ProgramError> {
-    let data = BridgeInstruction::VerifySignatures(*p).serialize()?;
+    let data = BridgeInstruction::VerifySignatures(p.clone()).serialize()?;
 
     let bridge_key = Bridge::derive_bridge_id(program_id)?;
     let guardian_set_key =
@@ -393,31 +486,10 @@ pub fn post_vaa(
     data.insert(0, 2);
 
     // Parse VAA
-    let vaa = VAA::deserialize(&v[..])?;
+    let vaa = VAA::deserialize(&v[..]).map_err(Error::from)?;
 
     let bridge_key = Bridge::derive_bridge_id(program_id)?;
-    let guardian_set_key =
-        Bridge::derive_guardian_set_id(program_id, &bridge_key, vaa.guardian_set_index)?;
-    let claim_key = Bridge::derive_claim_id(program_id, &bridge_key, vaa.signature_body()?)?;
-
-    let signature_acc = Bridge::derive_signature_id(
-        program_id,
-        &bridge_key,
-        &vaa.body_hash()?,
-        vaa.guardian_set_index,
-    )?;
-
-    let mut accounts = vec![
-        AccountMeta::new_readonly(*program_id, false),
-        AccountMeta::new_readonly(solana_program::system_program::id(), false),
-        AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
-        AccountMeta::new_readonly(solana_program::sysvar::clock::id(), false),
-        AccountMeta::new(bridge_key, false),
-        AccountMeta::new(guardian_set_key, false),
-        AccountMeta::new(claim_key, false),
-        AccountMeta::new(signature_acc, false),
-        AccountMeta::new(*payer, true),
-    ];
+    let mut accounts = post_vaa_accounts(program_id, payer, &bridge_key, &vaa)?;
 
     match vaa.payload.unwrap() {
         VAABody::UpdateGuardianSet(u) => {
@@ -439,31 +511,66 @@ pub fn post_vaa(
                     t.nonce,
                 )?;
                 accounts.push(AccountMeta::new(transfer_key, false))
-            } else if t.asset.chain == CHAIN_ID_SOLANA {
-                // Foreign (wrapped) -> Solana (native)
-                let mint_key = Pubkey::new(&t.asset.address);
-                let custody_key = Bridge::derive_custody_id(program_id, &bridge_key, &mint_key)?;
-                accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
-                accounts.push(AccountMeta::new(mint_key, false));
-                accounts.push(AccountMeta::new(Pubkey::new(&t.target_address), false));
-                accounts.push(AccountMeta::new(custody_key, false));
             } else {
-                // Foreign (native) -> Solana (wrapped)
-                let wrapped_key = Bridge::derive_wrapped_asset_id(
-                    program_id,
-                    &bridge_key,
-                    t.asset.chain,
-                    t.asset.decimals,
-                    t.asset.address,
-                )?;
-                let wrapped_meta_key =
-                    Bridge::derive_wrapped_meta_id(program_id, &bridge_key, &wrapped_key)?;
-                accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
-                accounts.push(AccountMeta::new(wrapped_key, false));
-                accounts.push(AccountMeta::new(Pubkey::new(&t.target_address), false));
-                accounts.push(AccountMeta::new(wrapped_meta_key, false));
+                push_transfer_to_wallet_accounts(program_id, &bridge_key, &t, None, &mut accounts)?;
             }
         }
+        VAABody::Governance(g) => {
+            if let GovernanceChange::WithdrawFees { recipient, .. } = g.change {
+                accounts.push(AccountMeta::new(Pubkey::new(&recipient), false));
+            }
+        }
+        VAABody::Message(m) if m.emitter_chain == CHAIN_ID_SOLANA => {
+            let message_key = Bridge::derive_message_id(
+                program_id,
+                &bridge_key,
+                &Pubkey::new(&m.emitter_address),
+                m.nonce,
+            )?;
+            accounts.push(AccountMeta::new(message_key, false));
+        }
+        VAABody::AssetMetadata(m) if m.asset.chain != CHAIN_ID_SOLANA => {
+            let wrapped_mint_key = Bridge::derive_wrapped_asset_id(
+                program_id,
+                &bridge_key,
+                m.asset.chain,
+                m.asset.address,
+            )?;
+            let wrapped_meta_key =
+                Bridge::derive_wrapped_meta_id(program_id, &bridge_key, &wrapped_mint_key)?;
+            accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+            accounts.push(AccountMeta::new(wrapped_mint_key, false));
+            accounts.push(AccountMeta::new(wrapped_meta_key, false));
+        }
+        VAABody::Refund(r) if r.transfer.source_chain == CHAIN_ID_SOLANA => {
+            let t = &r.transfer;
+            let transfer_key = Bridge::derive_transfer_id(
+                program_id,
+                &bridge_key,
+                t.asset.chain,
+                t.asset.address,
+                t.target_chain,
+                t.target_address,
+                t.source_address,
+                t.nonce,
+            )?;
+            accounts.push(AccountMeta::new(transfer_key, false));
+            push_transfer_in_accounts(
+                program_id,
+                &bridge_key,
+                t,
+                &Pubkey::new(&t.source_address),
+                None,
+                &mut accounts,
+            )?;
+        }
+        // Transfers with payload are submitted using `post_transfer_with_payload` and transfers
+        // with a relayer fee using `post_transfer_with_relayer_fee`
+        VAABody::Message(_)
+        | VAABody::TransferWithPayload(_)
+        | VAABody::AssetMetadata(_)
+        | VAABody::Refund(_)
+        | VAABody::Unknown { .. } => return Err(Error::InvalidVAAAction.into()),
     }
 
     Ok(Instruction {
@@ -473,33 +580,273 @@ pub fn post_vaa(
     })
 }
 
-/// Creates a 'CreateWrapped' instruction.
-pub fn create_wrapped(
+/// Creates a 'PostVAA' instruction for an inbound transfer with a relayer fee. The fee is paid to
+/// `fee_account`, which must be a token account of `payer` for the transferred asset.
+pub fn post_transfer_with_relayer_fee(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    v: VAAData,
+    fee_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let mut data = v.clone();
+    data.insert(0, 2);
+
+    // Parse VAA
+    let vaa = VAA::deserialize(&v[..]).map_err(Error::from)?;
+    let t = match vaa.payload.as_ref() {
+        Some(VAABody::Transfer(t)) if t.source_chain != CHAIN_ID_SOLANA => t,
+        _ => return Err(Error::InvalidVAAAction.into()),
+    };
+
+    let bridge_key = Bridge::derive_bridge_id(program_id)?;
+    let mut accounts = post_vaa_accounts(program_id, payer, &bridge_key, &vaa)?;
+    push_transfer_to_wallet_accounts(program_id, &bridge_key, t, Some(fee_account), &mut accounts)?;
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'PostVAA' instruction for a transfer with payload. The tokens are delivered to
+/// `destination`, which must be owned by the token owner derived by the receiving program, and
+/// `receiver_accounts` are passed on to the receiving program. A relayer fee is paid to
+/// `fee_account` like in `post_transfer_with_relayer_fee`.
+pub fn post_transfer_with_payload(
     program_id: &Pubkey,
     payer: &Pubkey,
-    meta: AssetMeta,
+    v: VAAData,
+    destination: &Pubkey,
+    fee_account: Option<&Pubkey>,
+    receiver_accounts: Vec<AccountMeta>,
 ) -> Result<Instruction, ProgramError> {
-    let data = BridgeInstruction::CreateWrapped(meta).serialize()?;
+    let mut data = v.clone();
+    data.insert(0, 2);
+
+    // Parse VAA
+    let vaa = VAA::deserialize(&v[..]).map_err(Error::from)?;
+    let t = match vaa.payload.as_ref() {
+        Some(VAABody::TransferWithPayload(t)) if t.transfer.source_chain != CHAIN_ID_SOLANA => t,
+        _ => return Err(Error::InvalidVAAAction.into()),
+    };
 
     let bridge_key = Bridge::derive_bridge_id(program_id)?;
-    let wrapped_mint_key = Bridge::derive_wrapped_asset_id(
+    let mut accounts = post_vaa_accounts(program_id, payer, &bridge_key, &vaa)?;
+    push_transfer_in_accounts(
         program_id,
         &bridge_key,
-        meta.chain,
-        meta.decimals,
-        meta.address,
+        &t.transfer,
+        destination,
+        fee_account,
+        &mut accounts,
     )?;
-    let wrapped_meta_key =
-        Bridge::derive_wrapped_meta_id(program_id, &bridge_key, &wrapped_mint_key)?;
+    accounts.push(AccountMeta::new_readonly(
+        Pubkey::new(&t.transfer.target_address),
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(
+        Bridge::derive_payload_authority_id(program_id, &bridge_key)?,
+        false,
+    ));
+    accounts.extend(receiver_accounts);
 
-    let accounts = vec![
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Accounts required by every 'PostVAA' instruction
+fn post_vaa_accounts(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    bridge_key: &Pubkey,
+    vaa: &VAA,
+) -> Result<Vec<AccountMeta>, ProgramError> {
+    let guardian_set_key =
+        Bridge::derive_guardian_set_id(program_id, bridge_key, vaa.guardian_set_index)?;
+    let hash = vaa.body_hash().map_err(Error::from)?;
+    let claim_key = Bridge::derive_claim_id(program_id, bridge_key, &hash)?;
+    let posted_vaa_key = Bridge::derive_posted_vaa_id(program_id, bridge_key, &hash)?;
+
+    let signature_acc =
+        Bridge::derive_signature_id(program_id, bridge_key, &hash, vaa.guardian_set_index)?;
+
+    Ok(vec![
+        AccountMeta::new_readonly(*program_id, false),
         AccountMeta::new_readonly(solana_program::system_program::id(), false),
-        AccountMeta::new_readonly(spl_token::id(), false),
         AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::id(), false),
+        AccountMeta::new(*bridge_key, false),
+        AccountMeta::new(guardian_set_key, false),
+        AccountMeta::new(claim_key, false),
+        AccountMeta::new(signature_acc, false),
+        AccountMeta::new(posted_vaa_key, false),
+        AccountMeta::new(*payer, true),
+    ])
+}
+
+/// Accounts of a transfer in that is delivered to the associated token account of the wallet at
+/// the target address of the transfer
+fn push_transfer_to_wallet_accounts(
+    program_id: &Pubkey,
+    bridge_key: &Pubkey,
+    t: &BodyTransfer,
+    fee_account: Option<&Pubkey>,
+    accounts: &mut Vec<AccountMeta>,
+) -> Result<(), ProgramError> {
+    let wallet = Pubkey::new(&t.target_address);
+    let mint_key = transfer_in_mint(program_id, bridge_key, t)?;
+    let destination = Bridge::derive_associated_token_id(&wallet, &spl_token::id(), &mint_key)?;
+    push_transfer_in_accounts(
+        program_id,
+        bridge_key,
+        t,
+        &destination,
+        fee_account,
+        accounts,
+    )?;
+    accounts.push(AccountMeta::new_readonly(wallet, false));
+    accounts.push(AccountMeta::new_readonly(
+        associated_token_program::id(),
+        false,
+    ));
+
+    Ok(())
+}
+
+/// Mint of the tokens delivered by a transfer in
+fn transfer_in_mint(
+    program_id: &Pubkey,
+    bridge_key: &Pubkey,
+    t: &BodyTransfer,
+) -> Result<Pubkey, ProgramError> {
+    if t.asset.chain == CHAIN_ID_SOLANA {
+        Ok(Pubkey::new(&t.asset.address))
+    } else {
+        Ok(Bridge::derive_wrapped_asset_id(
+            program_id,
+            bridge_key,
+            t.asset.chain,
+            t.asset.address,
+        )?)
+    }
+}
+
+/// Accounts of a transfer of foreign assets or Solana native assets in, delivered to `destination`
+fn push_transfer_in_accounts(
+    program_id: &Pubkey,
+    bridge_key: &Pubkey,
+    t: &BodyTransfer,
+    destination: &Pubkey,
+    fee_account: Option<&Pubkey>,
+    accounts: &mut Vec<AccountMeta>,
+) -> Result<(), ProgramError> {
+    let mint_key = transfer_in_mint(program_id, bridge_key, t)?;
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new(mint_key, false));
+    accounts.push(AccountMeta::new(*destination, false));
+    if t.asset.chain == CHAIN_ID_SOLANA {
+        // Foreign (wrapped) -> Solana (native)
+        let custody_key = Bridge::derive_custody_id(program_id, bridge_key, &mint_key)?;
+        accounts.push(AccountMeta::new(custody_key, false));
+    } else {
+        // Foreign (native) -> Solana (wrapped)
+        let wrapped_meta_key = Bridge::derive_wrapped_meta_id(program_id, bridge_key, &mint_key)?;
+        accounts.push(AccountMeta::new(wrapped_meta_key, false));
+    }
+
+    // The relayer fee account is only passed if there is a fee to pay out
+    if !t.fee.is_zero() {
+        let fee_account = fee_account.ok_or(Error::InvalidRelayerFeeAccount)?;
+        accounts.push(AccountMeta::new(*fee_account, false));
+    }
+
+    Ok(())
+}
+
+/// Creates an 'EvictTransferOut' instruction.
+#[cfg(not(target_arch = "bpf"))]
+pub fn evict_transfer_out(
+    program_id: &Pubkey,
+    guardian: &Pubkey,
+    transfer_proposal: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = BridgeInstruction::EvictTransferOut().serialize()?;
+
+    let bridge_key = Bridge::derive_bridge_id(program_id)?;
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*program_id, false),
+        AccountMeta::new(*guardian, true),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::id(), false),
         AccountMeta::new_readonly(bridge_key, false),
+        AccountMeta::new(*transfer_proposal, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an 'EvictClaimedVAA' instruction.
+#[cfg(not(target_arch = "bpf"))]
+pub fn evict_claimed_vaa(
+    program_id: &Pubkey,
+    guardian: &Pubkey,
+    hash: &[u8; 32],
+) -> Result<Instruction, ProgramError> {
+    let data = BridgeInstruction::EvictClaimedVAA().serialize()?;
+
+    let bridge_key = Bridge::derive_bridge_id(program_id)?;
+    let claim_key = Bridge::derive_claim_id(program_id, &bridge_key, hash)?;
+    let posted_vaa_key = Bridge::derive_posted_vaa_id(program_id, &bridge_key, hash)?;
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*program_id, false),
+        AccountMeta::new(*guardian, true),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::id(), false),
+        AccountMeta::new(bridge_key, false),
+        AccountMeta::new(claim_key, false),
+        AccountMeta::new(posted_vaa_key, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an 'PublishMessage' instruction.
+#[cfg(not(target_arch = "bpf"))]
+pub fn publish_message(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    emitter: &Pubkey,
+    p: PublishMessagePayload,
+) -> Result<Instruction, ProgramError> {
+    let bridge_key = Bridge::derive_bridge_id(program_id)?;
+    let fee_state_key = Bridge::derive_fee_state_id(program_id, &bridge_key)?;
+    let message_key = Bridge::derive_message_id(program_id, &bridge_key, emitter, p.nonce)?;
+
+    let data = BridgeInstruction::PublishMessage(p).serialize()?;
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*program_id, false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::clock::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::instructions::id(), false),
+        AccountMeta::new(bridge_key, false),
+        AccountMeta::new(fee_state_key, false),
+        AccountMeta::new(message_key, false),
+        AccountMeta::new_readonly(*emitter, true),
         AccountMeta::new(*payer, true),
-        AccountMeta::new(wrapped_mint_key, false),
-        AccountMeta::new(wrapped_meta_key, false),
     ];
 
     Ok(Instruction {
@@ -1,7 +1,7 @@
 //! Program instruction processing logic
 #![cfg(feature = "program")]
 
-use std::{borrow::Borrow, cell::RefCell, io::Write, mem::size_of, slice::Iter};
+use std::{borrow::Borrow, cell::RefCell, convert::TryFrom, io::Write, mem::size_of, slice::Iter};
 
 use byteorder::ByteOrder;
 use num_traits::AsPrimitive;
@@ -14,7 +14,7 @@ use solana_program::{
     entrypoint::ProgramResult,
     hash::Hasher,
     info,
-    instruction::Instruction,
+    instruction::{AccountMeta, Instruction},
     program_error::ProgramError,
     pubkey::Pubkey,
     rent::Rent,
@@ -26,11 +26,15 @@ use spl_token::{state::Mint};
 use crate::{
     error::Error,
     instruction::{
-        BridgeInstruction, BridgeInstruction::*, TransferOutPayload, VAAData, VerifySigPayload,
-        CHAIN_ID_SOLANA, MAX_LEN_GUARDIAN_KEYS, MAX_VAA_SIZE,
+        BridgeInstruction, BridgeInstruction::*, PublishMessagePayload, TransferOutPayload,
+        VerifySigPayload, CHAIN_ID_SOLANA, MAX_LEN_GUARDIAN_KEYS, MAX_MESSAGE_PAYLOAD_SIZE,
+        MAX_VAA_SIZE, MAX_WRAPPED_DECIMALS,
     },
     state::*,
-    vaa::{BodyTransfer, BodyUpdateGuardianSet, VAABody, VAA},
+    vaa::{
+        default_quorum, BodyAssetMetadata, BodyGovernance, BodyMessage, BodyTransfer,
+        BodyTransferWithPayload, BodyUpdateGuardianSet, GovernanceChange, Signature, VAABody, VAA,
+    },
 };
 use solana_program::program_pack::Pack;
 use std::borrow::BorrowMut;
@@ -63,8 +67,7 @@ impl Bridge {
                 Self::process_initialize(
                     program_id,
                     accounts,
-                    payload.len_guardians,
-                    payload.initial_guardian,
+                    &payload.initial_guardian,
                     payload.config,
                 )
             }
@@ -79,9 +82,19 @@ impl Bridge {
             }
             PostVAA(vaa_body) => {
                 info!("Instruction: PostVAA");
-                let vaa = VAA::deserialize(&vaa_body)?;
+                let vaa = VAA::deserialize(&vaa_body).map_err(Error::from)?;
+
+                Self::process_vaa(program_id, accounts, &vaa)
+            }
+            EvictTransferOut() => {
+                info!("Instruction: EvictTransferOut");
+
+                Self::process_evict_transfer_out(program_id, accounts)
+            }
+            EvictClaimedVAA() => {
+                info!("Instruction: EvictClaimedVAA");
 
-                Self::process_vaa(program_id, accounts, vaa_body, &vaa)
+                Self::process_evict_claimed_vaa(program_id, accounts)
             }
             PokeProposal() => {
                 info!("Instruction: PokeProposal");
@@ -93,11 +106,11 @@ impl Bridge {
 
                 Self::process_verify_signatures(program_id, accounts, &p)
             }
-            CreateWrapped(meta) => {
-                info!("Instruction: CreateWrapped");
-                Self::process_create_wrapped(program_id, accounts, &meta)
+            PublishMessage(p) => {
+                info!("Instruction: PublishMessage");
+
+                Self::process_publish_message(program_id, accounts, &p)
             }
-            _ => panic!(""),
         }
     }
 
@@ -105,8 +118,7 @@ impl Bridge {
     pub fn process_initialize(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
-        len_guardians: u8,
-        initial_guardian_key: [[u8; 20]; MAX_LEN_GUARDIAN_KEYS],
+        initial_guardian_key: &[GuardianAddress],
         config: BridgeConfig,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
@@ -136,28 +148,35 @@ impl Bridge {
             return Err(Error::AlreadyExists.into());
         }
 
+        if initial_guardian_key.is_empty() || initial_guardian_key.len() > MAX_LEN_GUARDIAN_KEYS {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // Instruction builders and clients derive associated token accounts with the SPL token
+        // program, the bridge must not move tokens with another one
+        if config.token_program != spl_token::id() {
+            return Err(Error::InvalidTokenProgram.into());
+        }
+
         // Create guardian set account
         let guardian_seed = Bridge::derive_guardian_set_seeds(new_bridge_info.key, 0);
-        Bridge::check_and_create_account::<GuardianSet>(
+        Bridge::check_and_create_account_sized(
             program_id,
             accounts,
             new_guardian_info.key,
             payer_info,
             program_id,
             &guardian_seed,
+            GuardianSet::size(initial_guardian_key.len()),
             None,
         )?;
 
         let mut new_guardian_data = new_guardian_info.try_borrow_mut_data().map_err(|_| ProgramError::AccountBorrowFailed)?;
-        let mut guardian_info: &mut GuardianSet = Self::unpack_unchecked(&mut new_guardian_data)?;
+        let (guardian_info, keys) = Self::unpack_guardian_set_unchecked(&mut new_guardian_data)?;
         if guardian_info.is_initialized {
             return Err(Error::AlreadyExists.into());
         }
 
-        if len_guardians > MAX_LEN_GUARDIAN_KEYS as u8 {
-            return Err(ProgramError::InvalidInstructionData);
-        }
-
         // Initialize bridge params
         bridge.is_initialized = true;
         bridge.guardian_set_index = 0;
@@ -167,8 +186,9 @@ impl Bridge {
         guardian_info.is_initialized = true;
         guardian_info.index = 0;
         guardian_info.creation_time = clock.unix_timestamp.as_();
-        guardian_info.keys = initial_guardian_key;
-        guardian_info.len_keys = len_guardians;
+        keys.copy_from_slice(initial_guardian_key);
+        guardian_info.len_keys = initial_guardian_key.len() as u8;
+        guardian_info.threshold = default_quorum(initial_guardian_key.len()) as u8;
 
         Ok(())
     }
@@ -190,6 +210,150 @@ impl Bridge {
         Ok(())
     }
 
+    /// Deletes a TransferOutProposal whose VAA has expired and returns the rent to the caller. Any
+    /// signer can evict, the rent is the reward for cleaning up.
+    pub fn process_evict_transfer_out(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        next_account_info(account_info_iter)?; // Bridge program
+        let guardian_info = next_account_info(account_info_iter)?;
+        let clock_info = next_account_info(account_info_iter)?;
+        let bridge_info = next_account_info(account_info_iter)?;
+        let proposal_info = next_account_info(account_info_iter)?;
+
+        if !guardian_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_info)?;
+
+        let expected_bridge = Bridge::derive_bridge_id(program_id)?;
+        if expected_bridge != *bridge_info.key {
+            return Err(Error::InvalidDerivedAccount.into());
+        }
+
+        {
+            let transfer_data = proposal_info.try_borrow_data()?;
+            let proposal: &TransferOutProposal = Self::unpack_immutable(&transfer_data)?;
+
+            // Check whether the proposal was derived correctly
+            let expected_proposal = Bridge::derive_transfer_id(
+                program_id,
+                bridge_info.key,
+                proposal.asset.chain,
+                proposal.asset.address,
+                proposal.to_chain_id,
+                proposal.foreign_address,
+                proposal.source_address,
+                proposal.nonce,
+            )?;
+            if expected_proposal != *proposal_info.key {
+                return Err(Error::InvalidDerivedAccount.into());
+            }
+
+            // Proposals without a VAA are still pending and must not be deleted, refunded ones
+            // expire with their refund VAA
+            if proposal.vaa_time == 0 {
+                return Err(Error::VAANotExpired.into());
+            }
+            let bridge_data = bridge_info.try_borrow_data()?;
+            let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
+            Self::check_vaa_expired(proposal.vaa_time, &bridge.config, &clock)?;
+        }
+
+        Self::close_account(proposal_info, guardian_info)
+    }
+
+    /// Deletes a ClaimedVAA and the PostedVAA of a VAA that has expired and returns the rent to the
+    /// caller. Any signer can evict, the rent is the reward for cleaning up.
+    pub fn process_evict_claimed_vaa(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        next_account_info(account_info_iter)?; // Bridge program
+        let guardian_info = next_account_info(account_info_iter)?;
+        let clock_info = next_account_info(account_info_iter)?;
+        let bridge_info = next_account_info(account_info_iter)?;
+        let claim_info = next_account_info(account_info_iter)?;
+        let posted_vaa_info = next_account_info(account_info_iter)?;
+
+        if !guardian_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_info)?;
+
+        let expected_bridge = Bridge::derive_bridge_id(program_id)?;
+        if expected_bridge != *bridge_info.key {
+            return Err(Error::InvalidDerivedAccount.into());
+        }
+
+        if claim_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        {
+            let claim_data = claim_info.try_borrow_data()?;
+            let claim: &ClaimedVAA = Self::unpack_immutable(&claim_data)?;
+
+            // Claims are derived from the body hash they store
+            let expected_claim = Bridge::derive_claim_id(program_id, bridge_info.key, &claim.hash)?;
+            if expected_claim != *claim_info.key {
+                return Err(Error::InvalidDerivedAccount.into());
+            }
+
+            // The posted VAA shares the hash and expiration of the claim
+            let expected_posted_vaa =
+                Bridge::derive_posted_vaa_id(program_id, bridge_info.key, &claim.hash)?;
+            if expected_posted_vaa != *posted_vaa_info.key {
+                return Err(Error::InvalidDerivedAccount.into());
+            }
+
+            let mut bridge_data = bridge_info.try_borrow_mut_data()?;
+            let bridge: &mut Bridge = Self::unpack(&mut bridge_data)?;
+            Self::check_vaa_expired(claim.vaa_time, &bridge.config, &clock)?;
+
+            // The VAA can no longer be submitted once its claim is gone
+            bridge.evicted_vaa_time = bridge.evicted_vaa_time.max(claim.vaa_time);
+        }
+
+        Self::close_account(posted_vaa_info, guardian_info)?;
+        Self::close_account(claim_info, guardian_info)
+    }
+
+    /// Checks that a VAA can still be submitted. Transfers and all other actions expire after the
+    /// `vaa_expiration_time`, as their claims can be evicted afterwards. Guardian set updates and
+    /// governance VAAs do not expire, so a delayed guardian set update can still be applied. They
+    /// cannot be replayed either: a set update only applies to the set preceding it and governance
+    /// VAAs must be newer than the last applied one.
+    fn check_vaa_expiration(vaa: &VAA, bridge: &Bridge, clock: &Clock) -> ProgramResult {
+        match vaa.payload {
+            Some(VAABody::UpdateGuardianSet(_)) | Some(VAABody::Governance(_)) => return Ok(()),
+            _ => {}
+        }
+
+        let expiration = bridge.config.vaa_expiration_time as i64;
+        if (vaa.timestamp as i64) + expiration < clock.unix_timestamp
+            || vaa.timestamp <= bridge.evicted_vaa_time
+        {
+            return Err(Error::VAAExpired.into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a VAA with the given timestamp is past the `vaa_expiration_time`
+    fn check_vaa_expired(vaa_time: u32, config: &BridgeConfig, clock: &Clock) -> ProgramResult {
+        if (vaa_time as i64) + (config.vaa_expiration_time as i64) >= clock.unix_timestamp {
+            return Err(Error::VAANotExpired.into());
+        }
+
+        Ok(())
+    }
+
     /// Processes signature verifications
     pub fn process_verify_signatures(
         program_id: &Pubkey,
@@ -206,23 +370,20 @@ impl Bridge {
         let payer_info = next_account_info(account_info_iter)?;
 
         let guardian_data = guardian_set_info.try_borrow_data()?;
-        let guardian_set: &GuardianSet = Self::unpack_immutable(&guardian_data)?;
+        let (guardian_set, keys) = Self::unpack_guardian_set_immutable(&guardian_data)?;
 
-        let sig_infos: Vec<SigInfo> = payload
-            .signers
-            .iter()
-            .enumerate()
-            .filter_map(|(i, p)| {
-                if *p == -1 {
-                    return None;
-                }
+        // The signatures in the secp instruction are ordered by the index of their signer
+        let mut sig_infos: Vec<SigInfo> = Vec::new();
+        for (i, signer_index) in payload.signer_indices().into_iter().enumerate() {
+            if signer_index >= keys.len() || i > u8::MAX as usize {
+                return Err(ProgramError::InvalidArgument);
+            }
 
-                return Some(SigInfo {
-                    sig_index: *p as u8,
-                    signer_index: i as u8,
-                });
-            })
-            .collect();
+            sig_infos.push(SigInfo {
+                sig_index: i as u8,
+                signer_index: signer_index as u8,
+            });
+        }
 
         let current_instruction = solana_program::sysvar::instructions::load_current_index(
             &instruction_accounts.try_borrow_mut_data()?,
@@ -311,13 +472,14 @@ impl Bridge {
             let bridge_key = Bridge::derive_bridge_id(program_id)?;
             let sig_seeds =
                 Bridge::derive_signature_seeds(&bridge_key, &msg_hash, guardian_set.index);
-            Bridge::check_and_create_account::<SignatureState>(
+            Bridge::check_and_create_account_sized(
                 program_id,
                 accounts,
                 sig_info.key,
                 payer_info,
                 program_id,
                 &sig_seeds,
+                SignatureState::size(keys.len()),
                 Some(bridge_info),
             )?;
         } else if payload.initial_creation {
@@ -325,7 +487,10 @@ impl Bridge {
         }
 
         let mut sig_state_data = sig_info.try_borrow_mut_data()?;
-        let mut sig_state: &mut SignatureState = Self::unpack_unchecked(&mut sig_state_data)?;
+        let (sig_state, signatures) = Self::unpack_signature_state_unchecked(&mut sig_state_data)?;
+        if signatures.len() != keys.len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
 
         if sig_state.is_initialized {
             if sig_state.guardian_set_index != guardian_set.index {
@@ -342,21 +507,17 @@ impl Bridge {
 
         // Check addresses
         for s in sig_infos {
-            if s.signer_index > guardian_set.len_keys {
-                return Err(ProgramError::InvalidArgument);
-            }
-
             if s.sig_index + 1 > sig_len {
                 return Err(ProgramError::InvalidArgument);
             }
 
-            let key = guardian_set.keys[s.signer_index as usize];
+            let key = keys[s.signer_index as usize];
             // Check key in ix
             if key != secp_ixs[s.sig_index as usize].address {
                 return Err(ProgramError::InvalidArgument);
             }
 
-            sig_state.signatures[s.signer_index as usize]
+            signatures[s.signer_index as usize]
                 .copy_from_slice(secp_ixs[s.sig_index as usize].signature);
         }
 
@@ -379,18 +540,31 @@ impl Bridge {
         let instructions_info = next_account_info(account_info_iter)?;
         let sender_account_info = next_account_info(account_info_iter)?;
         let bridge_info = next_account_info(account_info_iter)?;
+        let fee_state_info = next_account_info(account_info_iter)?;
         let transfer_info = next_account_info(account_info_iter)?;
         let mint_info = next_account_info(account_info_iter)?;
         let payer_info = next_account_info(account_info_iter)?;
+        let wrapped_meta_info = next_account_info(account_info_iter)?;
 
         let sender = Bridge::token_account_deserialize(sender_account_info)?;
         let bridge_data = bridge_info.try_borrow_data()?;
         let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
+        if bridge.paused_outbound {
+            return Err(Error::BridgePaused.into());
+        }
         let mint = Bridge::mint_deserialize(mint_info)?;
         let clock = Clock::from_account_info(clock_info)?;
 
         // Fee handling
-        let fee = Self::transfer_fee();
+        let fee = Self::dynamic_transfer_fee(
+            program_id,
+            accounts,
+            bridge_info,
+            fee_state_info,
+            payer_info,
+            &clock,
+            bridge.config.transfer_fee,
+        )?;
         Self::check_fees(instructions_info, bridge_info, fee)?;
 
         // Does the token belong to the mint
@@ -403,13 +577,27 @@ impl Bridge {
             program_id,
             bridge_info.key,
             t.asset.chain,
-            t.asset.decimals,
             t.asset.address,
         )?;
         if expected_mint_address != *mint_info.key {
             return Err(Error::InvalidDerivedAccount.into());
         }
 
+        // The amount is sent with the attested decimals of the asset, which the wrapped mint might
+        // have capped
+        let expected_meta_address =
+            Bridge::derive_wrapped_meta_id(program_id, bridge_info.key, mint_info.key)?;
+        if expected_meta_address != *wrapped_meta_info.key {
+            return Err(Error::InvalidDerivedAccount.into());
+        }
+        let wrapped_meta_data = wrapped_meta_info.try_borrow_data()?;
+        let wrapped_meta: &WrappedAssetMeta = Self::unpack_immutable(&wrapped_meta_data)?;
+        if t.fee > t.amount {
+            return Err(Error::FeeTooHigh.into());
+        }
+        let (amount, _) = Self::scale_amount(t.amount, mint.decimals, wrapped_meta.decimals)?;
+        let (transfer_fee, _) = Self::scale_amount(t.fee, mint.decimals, wrapped_meta.decimals)?;
+
         // Create transfer account
         let transfer_seed = Bridge::derive_transfer_id_seeds(
             bridge_info.key,
@@ -444,12 +632,17 @@ impl Bridge {
             t.amount,
         )?;
 
+        // The bridge is borrowed mutably to assign the sequence
+        drop(bridge_data);
+
         // Initialize transfer
         transfer.is_initialized = true;
         transfer.nonce = t.nonce;
+        transfer.sequence = Self::next_sequence(bridge_info)?;
         transfer.source_address = sender_account_info.key.to_bytes();
         transfer.foreign_address = t.target;
-        transfer.amount = t.amount;
+        transfer.amount = amount;
+        transfer.fee = transfer_fee;
         transfer.to_chain_id = t.chain_id;
         transfer.lockup_time = clock.unix_timestamp as u32;
 
@@ -457,7 +650,7 @@ impl Bridge {
         transfer.asset = AssetMeta {
             chain: t.asset.chain, // Chain and address cannot be spoofed because the account is derived from it
             address: t.asset.address,
-            decimals: mint.decimals, // We use the info from mint because it can be spoofed
+            decimals: wrapped_meta.decimals, // We use the attested info because it can be spoofed
         };
 
         Ok(())
@@ -479,6 +672,7 @@ impl Bridge {
         let instructions_info = next_account_info(account_info_iter)?;
         let sender_account_info = next_account_info(account_info_iter)?;
         let bridge_info = next_account_info(account_info_iter)?;
+        let fee_state_info = next_account_info(account_info_iter)?;
         let transfer_info = next_account_info(account_info_iter)?;
         let mint_info = next_account_info(account_info_iter)?;
         let payer_info = next_account_info(account_info_iter)?;
@@ -488,9 +682,20 @@ impl Bridge {
         let mint = Bridge::mint_deserialize(mint_info)?;
         let bridge_data = bridge_info.try_borrow_data()?;
         let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
+        if bridge.paused_outbound {
+            return Err(Error::BridgePaused.into());
+        }
         let clock = Clock::from_account_info(clock_info)?;
 
-        let fee = Self::transfer_fee();
+        let fee = Self::dynamic_transfer_fee(
+            program_id,
+            accounts,
+            bridge_info,
+            fee_state_info,
+            payer_info,
+            &clock,
+            bridge.config.transfer_fee,
+        )?;
         Self::check_fees(instructions_info, bridge_info, fee)?;
 
         // Does the token belong to the mint
@@ -543,6 +748,15 @@ impl Bridge {
             )?;
         }
 
+        // Amounts are sent with at most MAX_WRAPPED_DECIMALS decimals. The dust that cannot be
+        // represented stays with the sender.
+        let decimals = mint.decimals.min(MAX_WRAPPED_DECIMALS);
+        if t.fee > t.amount {
+            return Err(Error::FeeTooHigh.into());
+        }
+        let (amount, dust) = Self::scale_amount(t.amount, mint.decimals, decimals)?;
+        let (transfer_fee, _) = Self::scale_amount(t.fee, mint.decimals, decimals)?;
+
         let bridge_authority = Self::derive_bridge_id(program_id)?;
 
         // Check that the custody token account is owned by the derived key
@@ -560,12 +774,17 @@ impl Bridge {
             sender_account_info.key,
             custody_info.key,
             &bridge_authority,
-            t.amount,
+            t.amount - dust,
         )?;
 
+        // The bridge is borrowed mutably to assign the sequence
+        drop(bridge_data);
+
         // Initialize proposal
         transfer.is_initialized = true;
-        transfer.amount = t.amount;
+        transfer.sequence = Self::next_sequence(bridge_info)?;
+        transfer.amount = amount;
+        transfer.fee = transfer_fee;
         transfer.to_chain_id = t.chain_id;
         transfer.source_address = sender_account_info.key.to_bytes();
         transfer.foreign_address = t.target;
@@ -576,12 +795,134 @@ impl Bridge {
         transfer.asset = AssetMeta {
             chain: CHAIN_ID_SOLANA,
             address: mint_info.key.to_bytes(),
-            decimals: mint.decimals,
+            decimals,
+        };
+
+        Ok(())
+    }
+
+    /// Publishes an arbitrary message of the emitter for the guardians to sign
+    pub fn process_publish_message(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        p: &PublishMessagePayload,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        next_account_info(account_info_iter)?; // Bridge program
+        next_account_info(account_info_iter)?; // System program
+        next_account_info(account_info_iter)?; // Rent sysvar
+        let clock_info = next_account_info(account_info_iter)?;
+        let instructions_info = next_account_info(account_info_iter)?;
+        let bridge_info = next_account_info(account_info_iter)?;
+        let fee_state_info = next_account_info(account_info_iter)?;
+        let message_info = next_account_info(account_info_iter)?;
+        let emitter_info = next_account_info(account_info_iter)?;
+        let payer_info = next_account_info(account_info_iter)?;
+
+        // Programs sign for their derived addresses via CPI
+        if !emitter_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if p.payload.len() > MAX_MESSAGE_PAYLOAD_SIZE {
+            return Err(Error::MessageTooLong.into());
+        }
+
+        let clock = Clock::from_account_info(clock_info)?;
+        let min_fee = {
+            let bridge_data = bridge_info.try_borrow_data()?;
+            let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
+            if bridge.paused_outbound {
+                return Err(Error::BridgePaused.into());
+            }
+            bridge.config.transfer_fee
         };
 
+        // Messages are charged like transfers
+        let fee = Self::dynamic_transfer_fee(
+            program_id,
+            accounts,
+            bridge_info,
+            fee_state_info,
+            payer_info,
+            &clock,
+            min_fee,
+        )?;
+        Self::check_fees(instructions_info, bridge_info, fee)?;
+
+        // Create message account
+        Bridge::check_and_create_account_sized(
+            program_id,
+            accounts,
+            message_info.key,
+            payer_info,
+            program_id,
+            &Bridge::derive_message_seeds(bridge_info.key, emitter_info.key, p.nonce),
+            size_of::<PublishedMessage>() + p.payload.len(),
+            None,
+        )?;
+
+        let mut message_data = message_info.try_borrow_mut_data()?;
+        let (header, payload) = message_data.split_at_mut(size_of::<PublishedMessage>());
+        let message: &mut PublishedMessage = Self::unpack_unchecked(header)?;
+        message.is_initialized = true;
+        message.emitter = *emitter_info.key;
+        message.nonce = p.nonce;
+        message.sequence = Self::next_sequence(bridge_info)?;
+        message.submission_time = clock.unix_timestamp as u32;
+        message.len = p.payload.len() as u32;
+        payload.copy_from_slice(&p.payload);
+
         Ok(())
     }
 
+    /// Assigns the next sequence of the bridge. The sequence is global: lockups and the messages of
+    /// all emitters draw from the same counter.
+    fn next_sequence(bridge_info: &AccountInfo) -> Result<u64, ProgramError> {
+        let mut bridge_data = bridge_info.try_borrow_mut_data()?;
+        let bridge: &mut Bridge = Self::unpack(&mut bridge_data)?;
+
+        let sequence = bridge.sequence;
+        bridge.sequence = sequence.checked_add(1).ok_or(ProgramError::InvalidArgument)?;
+        Ok(sequence)
+    }
+
+    /// Calculates the fee of a transfer out based on the recent load of the bridge and records the
+    /// transfer in the fee state. The fee never drops below `min_fee` from the bridge config.
+    fn dynamic_transfer_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        bridge_info: &AccountInfo,
+        fee_state_info: &AccountInfo,
+        payer_info: &AccountInfo,
+        clock: &Clock,
+        min_fee: u64,
+    ) -> Result<u64, ProgramError> {
+        // Create the fee state with the first transfer out
+        if fee_state_info.data_is_empty() {
+            Bridge::check_and_create_account::<FeeState>(
+                program_id,
+                accounts,
+                fee_state_info.key,
+                payer_info,
+                program_id,
+                &Bridge::derive_fee_state_seeds(bridge_info.key),
+                None,
+            )?;
+        } else if Bridge::derive_fee_state_id(program_id, bridge_info.key)? != *fee_state_info.key {
+            return Err(Error::InvalidDerivedAccount.into());
+        }
+
+        let mut fee_state_data = fee_state_info.try_borrow_mut_data()?;
+        let fee_state: &mut FeeState = Self::unpack_unchecked(&mut fee_state_data)?;
+        fee_state.is_initialized = true;
+
+        let fee = fee_state.transfer_fee(clock.slot, min_fee);
+        fee_state.record_transfer(clock.slot);
+
+        Ok(fee)
+    }
+
     /// Verify that a certain fee was sent to the bridge in the preceding instruction
     pub fn check_fees(instructions_info: &AccountInfo, bridge_info: &AccountInfo, fee: u64) -> Result<(), ProgramError> {
         let current_instruction = solana_program::sysvar::instructions::load_current_index(
@@ -653,11 +994,20 @@ impl Bridge {
         Ok(())
     }
 
+    /// Wipes the data of a program owned account and moves all its lamports to `recipient`.
+    /// The runtime purges the account once the transaction is done.
+    pub fn close_account(account: &AccountInfo, recipient: &AccountInfo) -> ProgramResult {
+        for b in account.try_borrow_mut_data()?.iter_mut() {
+            *b = 0;
+        }
+
+        Self::transfer_sol(account, recipient, account.lamports())
+    }
+
     /// Processes a VAA
     pub fn process_vaa(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
-        vaa_data: VAAData,
         vaa: &VAA,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
@@ -671,11 +1021,12 @@ impl Bridge {
         let guardian_set_info = next_account_info(account_info_iter)?;
         let claim_info = next_account_info(account_info_iter)?;
         let sig_info = next_account_info(account_info_iter)?;
+        let posted_vaa_info = next_account_info(account_info_iter)?;
         let payer_info = next_account_info(account_info_iter)?;
 
         let clock = Clock::from_account_info(clock_info)?;
         let mut guardian_data = guardian_set_info.try_borrow_mut_data()?;
-        let guardian_set: &mut GuardianSet = Bridge::unpack(&mut guardian_data)?;
+        let (guardian_set, _) = Bridge::unpack_guardian_set(&mut guardian_data)?;
 
         // Check that the guardian set is valid
         let expected_guardian_set =
@@ -689,32 +1040,42 @@ impl Bridge {
             return Err(Error::GuardianSetExpired.into());
         }
 
+        // Expired VAAs must be rejected because their claims might already have been evicted
+        {
+            let bridge_data = bridge_info.try_borrow_data()?;
+            let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
+            Self::check_vaa_expiration(vaa, bridge, &clock)?;
+        }
+
         // Verify sig state
         let mut sig_state_data = sig_info.try_borrow_mut_data()?;
-        let sig_state: &SignatureState = Self::unpack(&mut sig_state_data)?;
+        let (sig_state, signatures) = Self::unpack_signature_state(&mut sig_state_data)?;
 
         // Verify that signatures were made using the correct set
         if sig_state.guardian_set_index != guardian_set.index {
             return Err(Error::GuardianSetMismatch.into());
         }
 
-        let hash = vaa.body_hash()?;
+        let hash = vaa.body_hash().map_err(Error::from)?;
         if sig_state.hash != hash {
             return Err(ProgramError::InvalidAccountData);
         }
 
-        let signature_count = (sig_state
-            .signatures
+        let signature_count = (signatures
             .iter()
             .filter(|v| v.iter().filter(|v| **v != 0).count() != 0)
             .count() as u8);
         // Check quorum
-        // We're using a fixed point number transformation with 1 decimal to deal with rounding.
-        // The cast to u16 exists to prevent issues where len_keys * 10 might overflow.
-        if (signature_count as u16) < ((((guardian_set.len_keys as u16) * 10 / 3) * 2) / 10 + 1) {
+        if signature_count < guardian_set.threshold {
             return Err(ProgramError::InvalidArgument);
         }
 
+        // The posted VAA carries the signatures that were verified on chain, so that anyone can
+        // verify it after the signature state has been evicted
+        let signed_vaa = Self::signed_vaa(vaa, signatures)
+            .serialize()
+            .map_err(Error::from)?;
+
         let mut evict_signatures = false;
         let payload = vaa.payload.as_ref().ok_or(Error::InvalidVAAAction)?;
         match payload {
@@ -742,37 +1103,121 @@ impl Bridge {
                         bridge_info,
                         vaa,
                         &v,
-                        vaa_data,
+                        posted_vaa_info.key,
                         sig_info.key,
                     )
                 } else {
                     let bridge_data = bridge_info.try_borrow_data()?;
                     let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
                     evict_signatures = true;
-                    Self::process_vaa_transfer(
+                    Self::process_vaa_transfer_to_wallet(
                         program_id,
                         accounts,
                         account_info_iter,
                         bridge_info,
+                        payer_info,
                         bridge,
                         &v,
                     )
                 }
             }
+            VAABody::TransferWithPayload(v) if v.transfer.source_chain != CHAIN_ID_SOLANA => {
+                let bridge_data = bridge_info.try_borrow_data()?;
+                let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
+                evict_signatures = true;
+                Self::process_vaa_transfer_with_payload(
+                    program_id,
+                    accounts,
+                    account_info_iter,
+                    bridge_info,
+                    payer_info,
+                    bridge,
+                    &v,
+                )
+            }
+            VAABody::Governance(v) => {
+                let mut bridge_data = bridge_info.try_borrow_mut_data()?;
+                let bridge: &mut Bridge = Self::unpack(&mut bridge_data)?;
+
+                Self::process_vaa_governance(
+                    account_info_iter,
+                    bridge_info,
+                    bridge,
+                    guardian_set,
+                    vaa.timestamp,
+                    &v,
+                )
+            }
+            VAABody::Message(v) if v.emitter_chain == CHAIN_ID_SOLANA => {
+                Self::process_vaa_message_post(
+                    program_id,
+                    account_info_iter,
+                    bridge_info,
+                    vaa,
+                    &v,
+                    posted_vaa_info.key,
+                )
+            }
+            VAABody::AssetMetadata(v) if v.asset.chain != CHAIN_ID_SOLANA => {
+                let bridge_data = bridge_info.try_borrow_data()?;
+                let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
+
+                Self::process_vaa_asset_metadata(
+                    program_id,
+                    accounts,
+                    account_info_iter,
+                    bridge_info,
+                    payer_info,
+                    bridge,
+                    &v,
+                )
+            }
+            VAABody::Refund(v) if v.transfer.source_chain == CHAIN_ID_SOLANA => {
+                let bridge_data = bridge_info.try_borrow_data()?;
+                let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
+
+                Self::process_vaa_refund(
+                    program_id,
+                    accounts,
+                    account_info_iter,
+                    &clock,
+                    bridge_info,
+                    bridge,
+                    vaa,
+                    &v.transfer,
+                )
+            }
+            // Actions this version of the program does not know how to execute
+            VAABody::Message(_)
+            | VAABody::TransferWithPayload(_)
+            | VAABody::AssetMetadata(_)
+            | VAABody::Refund(_)
+            | VAABody::Unknown { .. } => Err(Error::InvalidVAAAction.into()),
         }?;
 
         // Check and create claim
-        let claim_seeds = Bridge::derive_claim_seeds(bridge_info.key, vaa.signature_body()?);
         Bridge::check_and_create_account::<ClaimedVAA>(
             program_id,
             accounts,
             claim_info.key,
             payer_info,
             program_id,
-            &claim_seeds,
+            &Bridge::derive_claim_seeds(bridge_info.key, &hash),
             Some(bridge_info),
         )?;
 
+        // Keep the signed VAA on chain for data availability
+        Self::create_posted_vaa(
+            program_id,
+            accounts,
+            bridge_info,
+            posted_vaa_info,
+            payer_info,
+            &hash,
+            vaa.timestamp,
+            &signed_vaa,
+        )?;
+
         // If the signatures are not needed anymore, evict them and reclaim rent.
         // This should cover most of the costs of the guardian.
         if evict_signatures {
@@ -780,8 +1225,13 @@ impl Bridge {
         }
 
         // Refund tx fee if possible
-        if bridge_info.lamports().checked_sub(Self::MIN_BRIDGE_BALANCE).unwrap_or(0) >= Self::VAA_TX_FEE {
-            Self::transfer_sol(bridge_info, payer_info, Self::VAA_TX_FEE)?;
+        let vaa_tx_refund = {
+            let bridge_data = bridge_info.try_borrow_data()?;
+            let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
+            bridge.config.vaa_tx_refund
+        };
+        if Self::fee_surplus(bridge_info) >= vaa_tx_refund {
+            Self::transfer_sol(bridge_info, payer_info, vaa_tx_refund)?;
         }
 
         // Load claim account
@@ -793,12 +1243,14 @@ impl Bridge {
 
         // Set claimed
         claim.is_initialized = true;
-        claim.vaa_time = clock.unix_timestamp as u32;
+        claim.hash = hash;
+        claim.vaa_time = vaa.timestamp;
 
         Ok(())
     }
 
-    /// Processes a Guardian set update
+    /// Processes a Guardian set update. Updates are not subject to a pause of the bridge, the
+    /// current guardians must be able to sign the governance VAA that unpauses it.
     pub fn process_vaa_set_update(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
@@ -827,44 +1279,42 @@ impl Bridge {
         old_guardian_set.expiration_time =
             (clock.unix_timestamp as u32) + bridge.config.guardian_set_expiration_time;
 
+        if b.new_keys.len() == 0 {
+            return Err(Error::InvalidVAAFormat.into());
+        }
+
+        if b.new_keys.len() > MAX_LEN_GUARDIAN_KEYS {
+            return Err(Error::InvalidVAAFormat.into());
+        }
+
         // Check whether the new guardian set was derived correctly
         let guardian_seed = Bridge::derive_guardian_set_seeds(bridge_info.key, b.new_index);
-        Bridge::check_and_create_account::<GuardianSet>(
+        Bridge::check_and_create_account_sized(
             program_id,
             accounts,
             new_guardian_info.key,
             payer_info,
             program_id,
             &guardian_seed,
+            GuardianSet::size(b.new_keys.len()),
             Some(bridge_info),
         )?;
 
         let mut guardian_set_new_data = new_guardian_info.try_borrow_mut_data()?;
-        let guardian_set_new: &mut GuardianSet =
-            Bridge::unpack_unchecked(&mut guardian_set_new_data)?;
+        let (guardian_set_new, keys) =
+            Bridge::unpack_guardian_set_unchecked(&mut guardian_set_new_data)?;
 
         // The new guardian set must not exist
         if guardian_set_new.is_initialized {
             return Err(Error::AlreadyExists.into());
         }
 
-        if b.new_keys.len() == 0 {
-            return Err(Error::InvalidVAAFormat.into());
-        }
-
-        if b.new_keys.len() > MAX_LEN_GUARDIAN_KEYS {
-            return Err(Error::InvalidVAAFormat.into());
-        }
-
         // Set values on the new guardian set
         guardian_set_new.is_initialized = true;
         guardian_set_new.index = b.new_index;
-        let mut new_guardians = [[0u8; 20]; MAX_LEN_GUARDIAN_KEYS];
-        for n in 0..b.new_keys.len() {
-            new_guardians[n] = b.new_keys[n]
-        }
-        guardian_set_new.keys = new_guardians;
+        keys.copy_from_slice(&b.new_keys);
         guardian_set_new.len_keys = b.new_keys.len() as u8;
+        guardian_set_new.threshold = b.quorum() as u8;
         guardian_set_new.creation_time = clock.unix_timestamp as u32;
 
         // Update the bridge guardian set id
@@ -873,26 +1323,125 @@ impl Bridge {
         Ok(())
     }
 
-    /// Processes a VAA transfer in
-    pub fn process_vaa_transfer(
-        program_id: &Pubkey,
-        accounts: &[AccountInfo],
+    /// Processes a governance VAA that changes the config of the bridge or withdraws fees. Governance
+    /// is not subject to a pause of the bridge, it is the only way to unpause it.
+    pub fn process_vaa_governance(
         account_info_iter: &mut Iter<AccountInfo>,
         bridge_info: &AccountInfo,
-        bridge: &Bridge,
-        b: &BodyTransfer,
+        bridge: &mut Bridge,
+        guardian_set: &GuardianSet,
+        vaa_time: u32,
+        b: &BodyGovernance,
+    ) -> ProgramResult {
+        // Governance VAAs must be signed by the current guardian set
+        if bridge.guardian_set_index != guardian_set.index {
+            return Err(Error::OldGuardianSet.into());
+        }
+
+        if b.target_chain != CHAIN_ID_SOLANA {
+            return Err(Error::WrongTargetChain.into());
+        }
+
+        // Governance VAAs do not expire. They are applied in the order of their timestamps, so
+        // they cannot be replayed once their claims have been evicted.
+        if vaa_time <= bridge.governance_vaa_time {
+            return Err(Error::OldGovernanceVAA.into());
+        }
+
+        match b.change {
+            GovernanceChange::GuardianSetExpiration(expiration) => {
+                bridge.config.guardian_set_expiration_time =
+                    u32::try_from(expiration).map_err(|_| Error::InvalidVAAFormat)?;
+            }
+            GovernanceChange::TransferFee(fee) => {
+                bridge.config.transfer_fee = fee;
+            }
+            GovernanceChange::VAARefund(refund) => {
+                bridge.config.vaa_tx_refund = refund;
+            }
+            GovernanceChange::VAAExpiration(expiration) => {
+                bridge.config.vaa_expiration_time =
+                    u32::try_from(expiration).map_err(|_| Error::InvalidVAAFormat)?;
+            }
+            GovernanceChange::Active(active) => {
+                bridge.paused_inbound = !active;
+                bridge.paused_outbound = !active;
+            }
+            GovernanceChange::Pause { inbound, outbound } => {
+                bridge.paused_inbound = inbound;
+                bridge.paused_outbound = outbound;
+            }
+            GovernanceChange::WithdrawFees { recipient, amount } => {
+                let recipient_info = next_account_info(account_info_iter)?;
+                if recipient_info.key.to_bytes() != recipient {
+                    return Err(ProgramError::InvalidArgument);
+                }
+
+                // Only the fees on top of the balance that keeps the bridge rent exempt can be withdrawn
+                if Self::fee_surplus(bridge_info) < amount {
+                    return Err(ProgramError::InsufficientFunds);
+                }
+                Self::transfer_sol(bridge_info, recipient_info, amount)?;
+            }
+            // Config of the Terra contract and changes this version of the program does not know
+            GovernanceChange::WrappedAssetCodeId(_)
+            | GovernanceChange::Owner(_)
+            | GovernanceChange::Unknown { .. } => return Err(Error::InvalidVAAAction.into()),
+        }
+        bridge.governance_vaa_time = vaa_time;
+
+        Ok(())
+    }
+
+    /// Processes a VAA transfer in. The relayer fee is carved out of the amount and paid to a
+    /// token account of the payer, who submitted the VAA.
+    pub fn process_vaa_transfer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        account_info_iter: &mut Iter<AccountInfo>,
+        bridge_info: &AccountInfo,
+        payer_info: &AccountInfo,
+        bridge: &Bridge,
+        b: &BodyTransfer,
     ) -> ProgramResult {
+        if bridge.paused_inbound {
+            return Err(Error::BridgePaused.into());
+        }
+
         next_account_info(account_info_iter)?; // Token program
         let mint_info = next_account_info(account_info_iter)?;
         let destination_info = next_account_info(account_info_iter)?;
+        // Custody account or wrapped asset meta
+        let custody_info = next_account_info(account_info_iter)?;
 
         let destination = Self::token_account_deserialize(destination_info)?;
         if destination.mint != *mint_info.key {
             return Err(Error::TokenMintMismatch.into());
         }
+        let mint = Self::mint_deserialize(mint_info)?;
+
+        let fee_info = if b.fee.is_zero() {
+            None
+        } else {
+            let fee_info = next_account_info(account_info_iter)?;
+            let fee_account = Self::token_account_deserialize(fee_info)?;
+            if fee_account.mint != *mint_info.key {
+                return Err(Error::TokenMintMismatch.into());
+            }
+            if fee_account.owner != *payer_info.key {
+                return Err(Error::InvalidRelayerFeeAccount.into());
+            }
+            Some(fee_info)
+        };
+        // The fee cannot exceed the amount, this is checked when the VAA is decoded. Both are scaled
+        // from the decimals of the transfer to those of the mint, dust that a wrapped mint cannot
+        // represent is not minted.
+        let (amount, _) = Self::scale_amount(b.amount - b.fee, b.asset.decimals, mint.decimals)?;
+        let (fee, _) = Self::scale_amount(b.fee, b.asset.decimals, mint.decimals)?;
+        // A fee that is only dust is not paid out
+        let fee_info = fee_info.filter(|_| !fee.is_zero());
 
         if b.asset.chain == CHAIN_ID_SOLANA {
-            let custody_info = next_account_info(account_info_iter)?;
             let expected_custody_id =
                 Bridge::derive_custody_id(program_id, bridge_info.key, mint_info.key)?;
             if expected_custody_id != *custody_info.key {
@@ -906,15 +1455,24 @@ impl Bridge {
                 &bridge.config.token_program,
                 custody_info.key,
                 destination_info.key,
-                b.amount,
+                amount,
             )?;
+            if let Some(fee_info) = fee_info {
+                Bridge::token_transfer_custody(
+                    program_id,
+                    accounts,
+                    &bridge.config.token_program,
+                    custody_info.key,
+                    fee_info.key,
+                    fee,
+                )?;
+            }
         } else {
             // Foreign chain asset, mint wrapped asset
             let expected_mint_address = Bridge::derive_wrapped_asset_id(
                 program_id,
                 bridge_info.key,
                 b.asset.chain,
-                b.asset.decimals,
                 b.asset.address,
             )?;
             if expected_mint_address != *mint_info.key {
@@ -929,21 +1487,171 @@ impl Bridge {
                 &bridge.config.token_program,
                 mint_info.key,
                 destination_info.key,
-                b.amount,
+                amount,
             )?;
+            if let Some(fee_info) = fee_info {
+                Bridge::wrapped_mint_to(
+                    program_id,
+                    accounts,
+                    &bridge.config.token_program,
+                    mint_info.key,
+                    fee_info.key,
+                    fee,
+                )?;
+            }
         }
 
         Ok(())
     }
 
-    /// Processes a VAA post for data availability (for Solana -> foreign transfers)
+    /// Processes a VAA transfer in to the wallet at the target address of the transfer. The tokens
+    /// are delivered to the associated token account of the wallet, which is created if it does
+    /// not exist yet.
+    pub fn process_vaa_transfer_to_wallet(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        account_info_iter: &mut Iter<AccountInfo>,
+        bridge_info: &AccountInfo,
+        payer_info: &AccountInfo,
+        bridge: &Bridge,
+        b: &BodyTransfer,
+    ) -> ProgramResult {
+        // The transfer itself is processed with its own iterator once the destination exists
+        let transfer_accounts = &mut account_info_iter.clone();
+        next_account_info(account_info_iter)?; // Token program
+        let mint_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        next_account_info(account_info_iter)?; // Custody account or wrapped asset meta
+        if !b.fee.is_zero() {
+            next_account_info(account_info_iter)?; // Relayer fee account
+        }
+        let wallet_info = next_account_info(account_info_iter)?;
+        next_account_info(account_info_iter)?; // Associated token program
+
+        if wallet_info.key.to_bytes() != b.target_address {
+            return Err(Error::InvalidRecipient.into());
+        }
+        let expected_destination = Bridge::derive_associated_token_id(
+            wallet_info.key,
+            &bridge.config.token_program,
+            mint_info.key,
+        )?;
+        if expected_destination != *destination_info.key {
+            return Err(Error::InvalidRecipient.into());
+        }
+
+        if destination_info.data_is_empty() {
+            Self::create_associated_token_account(
+                accounts,
+                &bridge.config.token_program,
+                bridge_info,
+                payer_info,
+                wallet_info.key,
+                destination_info.key,
+                mint_info.key,
+            )?;
+        }
+
+        Self::process_vaa_transfer(
+            program_id,
+            accounts,
+            transfer_accounts,
+            bridge_info,
+            payer_info,
+            bridge,
+            b,
+        )
+    }
+
+    /// Processes a VAA transfer in that carries a payload for the receiving program. The tokens
+    /// are delivered to a token account of the receiver, then the receiver is invoked with the
+    /// payload as instruction data.
+    pub fn process_vaa_transfer_with_payload(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        account_info_iter: &mut Iter<AccountInfo>,
+        bridge_info: &AccountInfo,
+        payer_info: &AccountInfo,
+        bridge: &Bridge,
+        b: &BodyTransferWithPayload,
+    ) -> ProgramResult {
+        // The transfer itself is processed with its own iterator once the receiver was checked
+        let transfer_accounts = &mut account_info_iter.clone();
+        next_account_info(account_info_iter)?; // Token program
+        let mint_info = next_account_info(account_info_iter)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        next_account_info(account_info_iter)?; // Custody account or wrapped asset meta
+        if !b.transfer.fee.is_zero() {
+            next_account_info(account_info_iter)?; // Relayer fee account
+        }
+        let receiver_info = next_account_info(account_info_iter)?;
+        let payload_authority_info = next_account_info(account_info_iter)?;
+
+        // The target address of the transfer is the receiving program
+        if receiver_info.key.to_bytes() != b.transfer.target_address {
+            return Err(Error::InvalidReceiver.into());
+        }
+
+        // Only the receiver may control the delivered tokens
+        let destination = Self::token_account_deserialize(destination_info)?;
+        let expected_owner = Bridge::derive_receiver_id(receiver_info.key, bridge_info.key)?;
+        if destination.owner != expected_owner {
+            return Err(Error::InvalidReceiver.into());
+        }
+
+        // The bridge itself is the authority of all custody accounts and wrapped mints, so the
+        // receiver is invoked by a separate authority that does not control any funds.
+        let (expected_authority, authority_seeds) = Bridge::derive_key(
+            program_id,
+            &Bridge::derive_payload_authority_seeds(bridge_info.key),
+        )?;
+        if expected_authority != *payload_authority_info.key {
+            return Err(Error::InvalidDerivedAccount.into());
+        }
+
+        Self::process_vaa_transfer(
+            program_id,
+            accounts,
+            transfer_accounts,
+            bridge_info,
+            payer_info,
+            bridge,
+            &b.transfer,
+        )?;
+
+        let mut receiver_accounts = vec![
+            AccountMeta::new_readonly(expected_authority, true),
+            AccountMeta::new(*destination_info.key, false),
+            AccountMeta::new_readonly(*mint_info.key, false),
+        ];
+        // Remaining accounts are passed on as non-signers, the payload authority is the only
+        // account the bridge signs for
+        for info in account_info_iter {
+            receiver_accounts.push(if info.is_writable {
+                AccountMeta::new(*info.key, false)
+            } else {
+                AccountMeta::new_readonly(*info.key, false)
+            });
+        }
+
+        let ix = Instruction {
+            program_id: *receiver_info.key,
+            accounts: receiver_accounts,
+            data: b.payload.clone(),
+        };
+        Self::invoke_vec_seed(program_id, &ix, accounts, &authority_seeds)
+    }
+
+    /// Processes a VAA post for data availability (for Solana -> foreign transfers). Posts are not
+    /// subject to an outbound pause: the transfer was locked before the pause and its tokens have
+    /// already left the sender.
     pub fn process_vaa_transfer_post(
         program_id: &Pubkey,
         account_info_iter: &mut Iter<AccountInfo>,
         bridge_info: &AccountInfo,
         vaa: &VAA,
         b: &BodyTransfer,
-        vaa_data: VAAData,
+        posted_vaa: &Pubkey,
         sig_account: &Pubkey,
     ) -> ProgramResult {
         info!("posting VAA");
@@ -969,116 +1677,369 @@ impl Bridge {
         if !proposal.matches_vaa(b) {
             return Err(Error::VAAProposalMismatch.into());
         }
+        if proposal.refunded {
+            return Err(Error::TransferRefunded.into());
+        }
         if proposal.vaa_time != 0 {
             return Err(Error::VAAAlreadySubmitted.into());
         }
-        if vaa_data.len() > MAX_VAA_SIZE {
-            return Err(Error::VAATooLong.into());
-        }
 
-        // Set vaa
-        for i in 0..vaa_data.len() {
-            proposal.vaa[i] = vaa_data[i]
-        }
-        // Stop byte
-        proposal.vaa[vaa_data.len()] = 0xff;
+        // Reference the posted vaa
+        proposal.posted_vaa = *posted_vaa;
         proposal.vaa_time = vaa.timestamp;
         proposal.signature_account = *sig_account;
 
         Ok(())
     }
 
-    /// Creates a new wrapped asset
-    pub fn process_create_wrapped(
+    /// Processes a refund of a transfer out of Solana. The tokens are released from custody or
+    /// minted again to the source address of the proposal, which can then no longer be posted.
+    /// The proposal expires with the refund VAA.
+    ///
+    /// The transfer VAA is timestamped with the `lockup_time` and target chains reject transfer
+    /// VAAs older than the `vaa_expiration_time`. Refunds are only possible after that, when a
+    /// transfer VAA that was signed but never posted back can no longer be redeemed.
+    pub fn process_vaa_refund(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
-        a: &AssetMeta,
+        account_info_iter: &mut Iter<AccountInfo>,
+        clock: &Clock,
+        bridge_info: &AccountInfo,
+        bridge: &Bridge,
+        vaa: &VAA,
+        b: &BodyTransfer,
     ) -> ProgramResult {
-        info!("create wrapped");
-        let account_info_iter = &mut accounts.iter();
-        next_account_info(account_info_iter)?; // System program
+        info!("refunding transfer");
+        if bridge.paused_inbound {
+            return Err(Error::BridgePaused.into());
+        }
+
+        let proposal_info = next_account_info(account_info_iter)?;
         next_account_info(account_info_iter)?; // Token program
-        next_account_info(account_info_iter)?; // Rent sysvar
-        let bridge_info = next_account_info(account_info_iter)?;
-        let payer_info = next_account_info(account_info_iter)?;
         let mint_info = next_account_info(account_info_iter)?;
-        let wrapped_meta_info = next_account_info(account_info_iter)?;
-
-        let bridge_data = bridge_info.data.try_borrow().map_err(|_| ProgramError::AccountBorrowFailed)?;
-        let bridge: &Bridge = Self::unpack_immutable(&bridge_data)?;
+        let destination_info = next_account_info(account_info_iter)?;
+        // Custody account or wrapped asset meta
+        let custody_info = next_account_info(account_info_iter)?;
 
-        // Foreign chain asset, mint wrapped asset
-        let expected_mint_address = Bridge::derive_wrapped_asset_id(
+        // Check whether the proposal was derived correctly
+        let expected_proposal = Bridge::derive_transfer_id(
             program_id,
             bridge_info.key,
-            a.chain,
-            a.decimals,
-            a.address,
+            b.asset.chain,
+            b.asset.address,
+            b.target_chain,
+            b.target_address,
+            b.source_address,
+            b.nonce,
         )?;
-        if expected_mint_address != *mint_info.key {
+        if expected_proposal != *proposal_info.key {
             return Err(Error::InvalidDerivedAccount.into());
         }
 
-        // Create wrapped mint
-        Self::create_wrapped_mint(
-            program_id,
-            accounts,
-            &bridge.config.token_program,
-            mint_info.key,
-            bridge_info.key,
-            payer_info,
-            &a,
-            a.decimals,
-            None,
-        )?;
+        let mut transfer_data = proposal_info.try_borrow_mut_data()?;
+        let mut proposal: &mut TransferOutProposal = Self::unpack(&mut transfer_data)?;
+        if !proposal.matches_refund(b) {
+            return Err(Error::VAAProposalMismatch.into());
+        }
+        if proposal.refunded {
+            return Err(Error::TransferRefunded.into());
+        }
+        if proposal.vaa_time != 0 {
+            return Err(Error::VAAAlreadySubmitted.into());
+        }
+        Self::check_vaa_expired(proposal.lockup_time, &bridge.config, clock)?;
 
-        // Check and create wrapped asset meta to allow reverse resolution of info
-        let wrapped_meta_seeds = Bridge::derive_wrapped_meta_seeds(bridge_info.key, mint_info.key);
-        Bridge::check_and_create_account::<WrappedAssetMeta>(
-            program_id,
-            accounts,
-            wrapped_meta_info.key,
-            payer_info,
-            program_id,
-            &wrapped_meta_seeds,
-            None,
-        )?;
+        // The tokens go back to the account the transfer was initiated from
+        if destination_info.key.to_bytes() != proposal.source_address {
+            return Err(Error::InvalidDerivedAccount.into());
+        }
+        let destination = Self::token_account_deserialize(destination_info)?;
+        if destination.mint != *mint_info.key {
+            return Err(Error::TokenMintMismatch.into());
+        }
+        let mint = Self::mint_deserialize(mint_info)?;
+        let (amount, _) =
+            Self::scale_amount(proposal.amount, proposal.asset.decimals, mint.decimals)?;
 
-        let mut wrapped_meta_data = wrapped_meta_info.try_borrow_mut_data()?;
-        let wrapped_meta: &mut WrappedAssetMeta = Bridge::unpack_unchecked(&mut wrapped_meta_data)?;
+        if b.asset.chain == CHAIN_ID_SOLANA {
+            if Pubkey::new(&b.asset.address) != *mint_info.key {
+                return Err(Error::TokenMintMismatch.into());
+            }
+            let expected_custody_id =
+                Bridge::derive_custody_id(program_id, bridge_info.key, mint_info.key)?;
+            if expected_custody_id != *custody_info.key {
+                return Err(Error::InvalidDerivedAccount.into());
+            }
 
-        wrapped_meta.is_initialized = true;
-        wrapped_meta.address = a.address;
-        wrapped_meta.chain = a.chain;
+            // Native Solana asset, release the locked tokens from custody
+            Bridge::token_transfer_custody(
+                program_id,
+                accounts,
+                &bridge.config.token_program,
+                custody_info.key,
+                destination_info.key,
+                amount,
+            )?;
+        } else {
+            let expected_mint_address = Bridge::derive_wrapped_asset_id(
+                program_id,
+                bridge_info.key,
+                b.asset.chain,
+                b.asset.address,
+            )?;
+            if expected_mint_address != *mint_info.key {
+                return Err(Error::InvalidDerivedAccount.into());
+            }
+
+            // Foreign chain asset, mint the burned wrapped tokens again
+            Bridge::wrapped_mint_to(
+                program_id,
+                accounts,
+                &bridge.config.token_program,
+                mint_info.key,
+                destination_info.key,
+                amount,
+            )?;
+        }
+
+        proposal.refunded = true;
+        proposal.vaa_time = vaa.timestamp;
 
         Ok(())
     }
-}
 
-/// Implementation of actions
-impl Bridge {
-    /// Burn a wrapped asset from account
-    pub fn wrapped_burn(
+    /// Processes a VAA post for data availability (for messages published on Solana). Like transfer
+    /// posts they are not subject to an outbound pause, the message was published before it.
+    pub fn process_vaa_message_post(
         program_id: &Pubkey,
-        accounts: &[AccountInfo],
-        token_program_id: &Pubkey,
-        token_account: &Pubkey,
-        mint_account: &Pubkey,
-        amount: U256,
-    ) -> Result<(), ProgramError> {
-        let ix = spl_token::instruction::burn(
-            token_program_id,
-            token_account,
-            mint_account,
-            &Self::derive_bridge_id(program_id)?,
-            &[],
-            amount.as_u64(),
-        )?;
-        Self::invoke_as_bridge(program_id, &ix, accounts)
+        account_info_iter: &mut Iter<AccountInfo>,
+        bridge_info: &AccountInfo,
+        vaa: &VAA,
+        b: &BodyMessage,
+        posted_vaa: &Pubkey,
+    ) -> ProgramResult {
+        info!("posting message VAA");
+        let message_info = next_account_info(account_info_iter)?;
+
+        // Check whether the message was derived correctly
+        let emitter = Pubkey::new(&b.emitter_address);
+        let expected_message =
+            Bridge::derive_message_id(program_id, bridge_info.key, &emitter, b.nonce)?;
+        if expected_message != *message_info.key {
+            return Err(Error::InvalidDerivedAccount.into());
+        }
+
+        let mut message_data = message_info.try_borrow_mut_data()?;
+        let (header, payload) = message_data.split_at_mut(size_of::<PublishedMessage>());
+        let message: &mut PublishedMessage = Self::unpack(header)?;
+        if message.sequence != b.sequence || payload[..] != b.payload[..] {
+            return Err(Error::VAAProposalMismatch.into());
+        }
+        if message.vaa_time != 0 {
+            return Err(Error::VAAAlreadySubmitted.into());
+        }
+
+        // Reference the posted vaa
+        message.posted_vaa = *posted_vaa;
+        message.vaa_time = vaa.timestamp;
+
+        Ok(())
     }
 
-    /// Mint a wrapped asset to account
-    pub fn wrapped_mint_to(
+    /// Attaches the signatures of a signature state to a VAA, ordered by guardian index
+    pub fn signed_vaa(vaa: &VAA, signatures: &[GuardianSignature]) -> VAA {
+        let signatures = signatures
+            .iter()
+            .enumerate()
+            .filter(|(_, signature)| signature.iter().any(|b| *b != 0))
+            .map(|(index, signature)| {
+                let mut r = [0u8; 32];
+                let mut s = [0u8; 32];
+                r.copy_from_slice(&signature[..32]);
+                s.copy_from_slice(&signature[32..64]);
+                Signature {
+                    index: index as u8,
+                    r,
+                    s,
+                    v: signature[64],
+                }
+            })
+            .collect();
+
+        VAA {
+            signatures,
+            ..vaa.clone()
+        }
+    }
+
+    /// Stores a signed VAA in a new PostedVAA account derived from its body hash
+    fn create_posted_vaa(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        bridge_info: &AccountInfo,
+        posted_vaa_info: &AccountInfo,
+        payer_info: &AccountInfo,
+        hash: &[u8; 32],
+        vaa_time: u32,
+        vaa_data: &[u8],
+    ) -> ProgramResult {
+        if vaa_data.len() > MAX_VAA_SIZE {
+            return Err(Error::VAATooLong.into());
+        }
+
+        Bridge::check_and_create_account_sized(
+            program_id,
+            accounts,
+            posted_vaa_info.key,
+            payer_info,
+            program_id,
+            &Bridge::derive_posted_vaa_seeds(bridge_info.key, hash),
+            size_of::<PostedVAA>() + vaa_data.len(),
+            Some(bridge_info),
+        )?;
+
+        let mut posted_vaa_data = posted_vaa_info.try_borrow_mut_data()?;
+        let (header, vaa) = posted_vaa_data.split_at_mut(size_of::<PostedVAA>());
+        let posted_vaa: &mut PostedVAA = Bridge::unpack_unchecked(header)?;
+        posted_vaa.is_initialized = true;
+        posted_vaa.hash = *hash;
+        posted_vaa.vaa_time = vaa_time;
+        posted_vaa.len = vaa_data.len() as u32;
+        vaa.copy_from_slice(vaa_data);
+
+        Ok(())
+    }
+
+    /// Processes an asset attestation VAA by creating the wrapped mint of the foreign asset and its
+    /// meta account, which stores the attested metadata. The wrapped mint uses the attested
+    /// decimals and can only be created once per origin token.
+    pub fn process_vaa_asset_metadata(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        account_info_iter: &mut Iter<AccountInfo>,
+        bridge_info: &AccountInfo,
+        payer_info: &AccountInfo,
+        bridge: &Bridge,
+        b: &BodyAssetMetadata,
+    ) -> ProgramResult {
+        if bridge.paused_inbound {
+            return Err(Error::BridgePaused.into());
+        }
+
+        next_account_info(account_info_iter)?; // Token program
+        let mint_info = next_account_info(account_info_iter)?;
+        let wrapped_meta_info = next_account_info(account_info_iter)?;
+
+        // Foreign chain asset, mint wrapped asset
+        let expected_mint_address = Bridge::derive_wrapped_asset_id(
+            program_id,
+            bridge_info.key,
+            b.asset.chain,
+            b.asset.address,
+        )?;
+        if expected_mint_address != *mint_info.key {
+            return Err(Error::InvalidDerivedAccount.into());
+        }
+
+        // Create wrapped mint
+        Bridge::create_wrapped_mint(
+            program_id,
+            accounts,
+            &bridge.config.token_program,
+            mint_info.key,
+            bridge_info.key,
+            payer_info,
+            &b.asset,
+            b.asset.decimals.min(MAX_WRAPPED_DECIMALS),
+            None,
+        )?;
+
+        // Check and create wrapped asset meta to allow reverse resolution of info
+        let wrapped_meta_seeds = Bridge::derive_wrapped_meta_seeds(bridge_info.key, mint_info.key);
+        Bridge::check_and_create_account::<WrappedAssetMeta>(
+            program_id,
+            accounts,
+            wrapped_meta_info.key,
+            payer_info,
+            program_id,
+            &wrapped_meta_seeds,
+            None,
+        )?;
+
+        let mut wrapped_meta_data = wrapped_meta_info.try_borrow_mut_data()?;
+        let wrapped_meta: &mut WrappedAssetMeta = Bridge::unpack_unchecked(&mut wrapped_meta_data)?;
+
+        wrapped_meta.is_initialized = true;
+        wrapped_meta.address = b.asset.address;
+        wrapped_meta.chain = b.asset.chain;
+        wrapped_meta.decimals = b.asset.decimals;
+        wrapped_meta.symbol = b.symbol;
+        wrapped_meta.name = b.name;
+
+        Ok(())
+    }
+}
+
+/// Implementation of actions
+impl Bridge {
+    /// Scales `amount` from `from_decimals` to `to_decimals`. Scaling down truncates the amount,
+    /// the truncated dust is returned along with it.
+    pub fn scale_amount(
+        amount: U256,
+        from_decimals: u8,
+        to_decimals: u8,
+    ) -> Result<(U256, U256), Error> {
+        if from_decimals > to_decimals {
+            let factor = Self::decimals_factor(from_decimals - to_decimals)?;
+            Ok((amount / factor, amount % factor))
+        } else {
+            let factor = Self::decimals_factor(to_decimals - from_decimals)?;
+            let amount = amount.checked_mul(factor).ok_or(Error::AmountOverflow)?;
+            Ok((amount, U256::zero()))
+        }
+    }
+
+    /// Factor between two amounts whose decimals differ by `decimals`
+    fn decimals_factor(decimals: u8) -> Result<U256, Error> {
+        U256::from(10)
+            .checked_pow(U256::from(decimals))
+            .ok_or(Error::AmountOverflow)
+    }
+
+    /// Converts the amount of a token transfer to the amount of an SPL token instruction. Amounts
+    /// that do not fit are rejected instead of being truncated, as are zero amounts.
+    pub fn token_amount(amount: U256) -> Result<u64, Error> {
+        if amount.is_zero() {
+            return Err(Error::ZeroAmount);
+        }
+        if amount > U256::from(u64::MAX) {
+            return Err(Error::AmountTooLarge);
+        }
+        Ok(amount.as_u64())
+    }
+
+    /// Burn a wrapped asset from account
+    pub fn wrapped_burn(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        token_program_id: &Pubkey,
+        token_account: &Pubkey,
+        mint_account: &Pubkey,
+        amount: U256,
+    ) -> Result<(), ProgramError> {
+        let ix = spl_token::instruction::burn(
+            token_program_id,
+            token_account,
+            mint_account,
+            &Self::derive_bridge_id(program_id)?,
+            &[],
+            Self::token_amount(amount)?,
+        )?;
+        Self::invoke_as_bridge(program_id, &ix, accounts)
+    }
+
+    /// Mint a wrapped asset to account
+    pub fn wrapped_mint_to(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         token_program_id: &Pubkey,
@@ -1092,7 +2053,7 @@ impl Bridge {
             destination,
             &Self::derive_bridge_id(program_id)?,
             &[],
-            amount.as_u64(),
+            Self::token_amount(amount)?,
         )?;
         Self::invoke_as_bridge(program_id, &ix, accounts)
     }
@@ -1113,7 +2074,7 @@ impl Bridge {
             destination,
             authority,
             &[],
-            amount.as_u64(),
+            Self::token_amount(amount)?,
         )?;
         Self::invoke_as_bridge(program_id, &ix, accounts)
     }
@@ -1133,7 +2094,7 @@ impl Bridge {
             destination,
             &Self::derive_bridge_id(program_id)?,
             &[],
-            amount.as_u64(),
+            Self::token_amount(amount)?,
         )?;
         Self::invoke_as_bridge(program_id, &ix, accounts)
     }
@@ -1186,7 +2147,7 @@ impl Bridge {
             mint,
             payer,
             token_program,
-            &Self::derive_wrapped_asset_seeds(bridge, asset.chain, asset.decimals, asset.address),
+            &Self::derive_wrapped_asset_seeds(bridge, asset.chain, asset.address),
             subsidizer,
         )?;
         let ix = spl_token::instruction::initialize_mint(
@@ -1199,6 +2160,40 @@ impl Bridge {
         invoke_signed(&ix, accounts, &[])
     }
 
+    /// Creates the associated token account of `wallet` for `mint`. The payer funds the account
+    /// and is refunded by the bridge if it holds enough fees.
+    pub fn create_associated_token_account(
+        accounts: &[AccountInfo],
+        token_program: &Pubkey,
+        bridge_info: &AccountInfo,
+        payer: &AccountInfo,
+        wallet: &Pubkey,
+        account: &Pubkey,
+        mint: &Pubkey,
+    ) -> ProgramResult {
+        let ix = Instruction {
+            program_id: associated_token_program::id(),
+            accounts: vec![
+                AccountMeta::new(*payer.key, true),
+                AccountMeta::new(*account, false),
+                AccountMeta::new_readonly(*wallet, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                AccountMeta::new_readonly(*token_program, false),
+                AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            ],
+            data: vec![],
+        };
+        invoke_signed(&ix, accounts, &[])?;
+
+        let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+        if Self::fee_surplus(bridge_info) >= rent {
+            Self::transfer_sol(bridge_info, payer, rent)?;
+        }
+
+        Ok(())
+    }
+
     pub fn invoke_as_bridge<'a>(
         program_id: &Pubkey,
         instruction: &Instruction,
@@ -1219,11 +2214,21 @@ impl Bridge {
         invoke_signed(instruction, account_infos, &[s.as_slice()])
     }
 
-    /// The amount of sol that needs to be held in the BridgeConfig account in order to make it
-    /// exempt of rent payments.
-    const MIN_BRIDGE_BALANCE: u64 = (((solana_program::rent::ACCOUNT_STORAGE_OVERHEAD + size_of::<BridgeConfig>() as u64) *
-        solana_program::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR) as f64
-        * solana_program::rent::DEFAULT_EXEMPTION_THRESHOLD) as u64;
+    /// Fees collected by the bridge account that exceed `min_bridge_balance`. The bridge account
+    /// is the fee vault: transfer fees are paid into it and VAA refunds, rent subsidies and fee
+    /// withdrawals are paid out of this surplus.
+    fn fee_surplus(bridge_info: &AccountInfo) -> u64 {
+        bridge_info
+            .lamports()
+            .checked_sub(Self::min_bridge_balance())
+            .unwrap_or(0)
+    }
+
+    /// The amount of sol that needs to be held in the bridge account in order to make it exempt
+    /// of rent payments.
+    fn min_bridge_balance() -> u64 {
+        Rent::default().minimum_balance(size_of::<Bridge>())
+    }
 
     /// Check that a key was derived correctly and create account
     pub fn check_and_create_account<T: Sized>(
@@ -1234,6 +2239,29 @@ impl Bridge {
         owner: &Pubkey,
         seeds: &Vec<Vec<u8>>,
         subsidizer: Option<&AccountInfo>,
+    ) -> Result<Vec<Vec<u8>>, ProgramError> {
+        Self::check_and_create_account_sized(
+            program_id,
+            accounts,
+            new_account,
+            payer,
+            owner,
+            seeds,
+            size_of::<T>(),
+            subsidizer,
+        )
+    }
+
+    /// Checks the derivation of an account and creates it with `size` bytes of data
+    pub fn check_and_create_account_sized(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_account: &Pubkey,
+        payer: &AccountInfo,
+        owner: &Pubkey,
+        seeds: &Vec<Vec<u8>>,
+        size: usize,
+        subsidizer: Option<&AccountInfo>,
     ) -> Result<Vec<Vec<u8>>, ProgramError> {
         info!("deriving key");
         let (expected_key, full_seeds) = Bridge::derive_key(program_id, seeds)?;
@@ -1242,25 +2270,26 @@ impl Bridge {
         }
 
         info!("deploying contract");
-        Self::create_account_raw::<T>(
+        Self::create_account_raw(
             program_id,
             accounts,
             new_account,
             payer.key,
             owner,
             &full_seeds,
+            size,
         )?;
 
         // The subsidizer refunds the rent that needs to be paid to create the account.
         // This mechanism is intended to reduce the cost of operating a guardian.
         // The subsidizer account should be of the type BridgeConfig and will only pay out
-        // the subsidy if the account holds at least MIN_BRIDGE_BALANCE+rent
+        // the subsidy if the account holds at least min_bridge_balance+rent
         match subsidizer {
             None => {}
             Some(v) => {
                 let bal = v.try_lamports()?;
-                let rent = Rent::default().minimum_balance(size_of::<T>());
-                if bal.checked_sub(Self::MIN_BRIDGE_BALANCE).ok_or(ProgramError::InsufficientFunds)? >= rent {
+                let rent = Rent::default().minimum_balance(size);
+                if bal.checked_sub(Self::min_bridge_balance()).ok_or(ProgramError::InsufficientFunds)? >= rent {
                     // Refund rent to payer
                     Self::transfer_sol(v, payer, rent)?;
                 }
@@ -1271,15 +2300,15 @@ impl Bridge {
     }
 
     /// Create a new account
-    fn create_account_raw<T: Sized>(
+    fn create_account_raw(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         new_account: &Pubkey,
         payer: &Pubkey,
         owner: &Pubkey,
         seeds: &Vec<Vec<u8>>,
+        size: usize,
     ) -> Result<(), ProgramError> {
-        let size = size_of::<T>();
         let ix = create_account(
             payer,
             new_account,
@@ -1291,3 +2320,2090 @@ impl Bridge {
         invoke_signed(&ix, accounts, &[s.as_slice()])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+
+    use solana_program::{
+        account_info::AccountInfo,
+        clock::Epoch,
+        message::Message,
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+        sysvar,
+    };
+    use spl_token::instruction::TokenInstruction;
+
+    use crate::instruction::{
+        evict_claimed_vaa, evict_transfer_out, initialize, post_vaa, InitializePayload,
+    };
+
+    use super::*;
+
+    const VAA_TIME: u32 = 1_600_000_000;
+    const VAA_EXPIRATION_TIME: u32 = 7 * 24 * 60 * 60;
+
+    struct TestAccount {
+        key: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        owner: Pubkey,
+    }
+
+    impl TestAccount {
+        fn new(key: Pubkey, lamports: u64, size: usize, owner: Pubkey) -> TestAccount {
+            TestAccount {
+                key,
+                lamports,
+                data: vec![0; size],
+                owner,
+            }
+        }
+
+        fn info(&mut self, is_signer: bool) -> AccountInfo {
+            AccountInfo::new(
+                &self.key,
+                is_signer,
+                true,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                Epoch::default(),
+            )
+        }
+    }
+
+    /// Accounts passed to a VAA processor, along with the bridge and the payer
+    struct ProcessorAccounts {
+        accounts: Vec<TestAccount>,
+        bridge: TestAccount,
+        payer: TestAccount,
+    }
+
+    impl ProcessorAccounts {
+        fn new(program_id: &Pubkey, accounts: Vec<TestAccount>) -> ProcessorAccounts {
+            ProcessorAccounts {
+                accounts,
+                bridge: TestAccount::new(
+                    Bridge::derive_bridge_id(program_id).unwrap(),
+                    0,
+                    0,
+                    *program_id,
+                ),
+                payer: TestAccount::new(Pubkey::new(&[10; 32]), 0, 0, Pubkey::default()),
+            }
+        }
+
+        /// Returns the infos of the accounts, the bridge and the payer and starts recording the
+        /// cross-program invocations made with them
+        fn infos(&mut self) -> (Vec<AccountInfo>, AccountInfo, AccountInfo) {
+            record_invocations();
+            (
+                self.accounts
+                    .iter_mut()
+                    .map(|account| account.info(false))
+                    .collect(),
+                self.bridge.info(false),
+                self.payer.info(true),
+            )
+        }
+
+        /// Accounts of an inbound transfer of `mint` to `wallet`, paid into `destination`
+        fn transfer_to_wallet(
+            program_id: &Pubkey,
+            mint: Pubkey,
+            wallet: Pubkey,
+            destination: TestAccount,
+        ) -> ProcessorAccounts {
+            let mut accounts = transfer_accounts(program_id, mint, destination);
+            accounts.push(TestAccount::new(wallet, 0, 0, Pubkey::default()));
+            accounts.push(TestAccount::new(
+                associated_token_program::id(),
+                0,
+                0,
+                Pubkey::default(),
+            ));
+            ProcessorAccounts::new(program_id, accounts)
+        }
+
+        /// Accounts of an inbound transfer of `mint` to the token account [7; 32] of `owner`,
+        /// followed by those of the `receiver` invoked with the payload
+        fn transfer_with_payload(
+            program_id: &Pubkey,
+            mint: Pubkey,
+            receiver: Pubkey,
+            owner: Pubkey,
+        ) -> ProcessorAccounts {
+            let bridge_key = Bridge::derive_bridge_id(program_id).unwrap();
+            let authority = Bridge::derive_payload_authority_id(program_id, &bridge_key).unwrap();
+            let destination = token_account(Pubkey::new(&[7; 32]), mint, owner);
+            let mut accounts = transfer_accounts(program_id, mint, destination);
+            accounts.push(TestAccount::new(receiver, 0, 0, Pubkey::default()));
+            accounts.push(TestAccount::new(authority, 0, 0, Pubkey::default()));
+            // Passed on to the receiver
+            accounts.push(TestAccount::new(Pubkey::new(&[11; 32]), 0, 0, *program_id));
+            ProcessorAccounts::new(program_id, accounts)
+        }
+
+        /// Accounts of the creation of the wrapped `mint`, the wrapped asset meta is sized as it
+        /// would be by the system program
+        fn asset_metadata(program_id: &Pubkey, mint: Pubkey) -> ProcessorAccounts {
+            let bridge_key = Bridge::derive_bridge_id(program_id).unwrap();
+            let meta = Bridge::derive_wrapped_meta_id(program_id, &bridge_key, &mint).unwrap();
+            let accounts = vec![
+                TestAccount::new(spl_token::id(), 0, 0, Pubkey::default()),
+                TestAccount::new(mint, 0, 0, spl_token::id()),
+                TestAccount::new(meta, 0, size_of::<WrappedAssetMeta>(), *program_id),
+            ];
+            ProcessorAccounts::new(program_id, accounts)
+        }
+
+        /// Accounts of the refund of a lockup of `amount` wrapped `mint` tokens by `source` at
+        /// `VAA_TIME`, paid into the token account `destination`
+        fn refund(
+            program_id: &Pubkey,
+            vaa_time: u32,
+            amount: U256,
+            mint: Pubkey,
+            source: Pubkey,
+            destination: Pubkey,
+        ) -> ProcessorAccounts {
+            let mut proposal = proposal_account(program_id, vaa_time);
+            let locked: &mut TransferOutProposal = Bridge::unpack(&mut proposal.data).unwrap();
+            locked.amount = amount;
+            locked.lockup_time = VAA_TIME;
+            let mut accounts = vec![proposal];
+            accounts.extend(transfer_accounts(
+                program_id,
+                mint,
+                token_account(destination, mint, source),
+            ));
+            ProcessorAccounts::new(program_id, accounts)
+        }
+
+        fn process_transfer(
+            &mut self,
+            program_id: &Pubkey,
+            bridge: &Bridge,
+            body: &BodyTransfer,
+        ) -> ProgramResult {
+            let (accounts, bridge_info, payer) = self.infos();
+            Bridge::process_vaa_transfer(
+                program_id,
+                &accounts,
+                &mut accounts.iter(),
+                &bridge_info,
+                &payer,
+                bridge,
+                body,
+            )
+        }
+
+        fn process_transfer_to_wallet(
+            &mut self,
+            program_id: &Pubkey,
+            bridge: &Bridge,
+            body: &BodyTransfer,
+        ) -> ProgramResult {
+            let (accounts, bridge_info, payer) = self.infos();
+            Bridge::process_vaa_transfer_to_wallet(
+                program_id,
+                &accounts,
+                &mut accounts.iter(),
+                &bridge_info,
+                &payer,
+                bridge,
+                body,
+            )
+        }
+
+        /// Processes the transfer with the account passed on to the receiver signed, as the
+        /// signatures of the submitter must not be passed on
+        fn process_transfer_with_payload(
+            &mut self,
+            program_id: &Pubkey,
+            bridge: &Bridge,
+            body: &BodyTransferWithPayload,
+        ) -> ProgramResult {
+            let (mut accounts, bridge_info, payer) = self.infos();
+            accounts.last_mut().unwrap().is_signer = true;
+            Bridge::process_vaa_transfer_with_payload(
+                program_id,
+                &accounts,
+                &mut accounts.iter(),
+                &bridge_info,
+                &payer,
+                bridge,
+                body,
+            )
+        }
+
+        fn process_asset_metadata(
+            &mut self,
+            program_id: &Pubkey,
+            bridge: &Bridge,
+            body: &BodyAssetMetadata,
+        ) -> ProgramResult {
+            let (accounts, bridge_info, payer) = self.infos();
+            Bridge::process_vaa_asset_metadata(
+                program_id,
+                &accounts,
+                &mut accounts.iter(),
+                &bridge_info,
+                &payer,
+                bridge,
+                body,
+            )
+        }
+
+        fn process_refund(
+            &mut self,
+            program_id: &Pubkey,
+            clock: &Clock,
+            bridge: &Bridge,
+            vaa: &VAA,
+            body: &BodyTransfer,
+        ) -> ProgramResult {
+            let (accounts, bridge_info, _) = self.infos();
+            Bridge::process_vaa_refund(
+                program_id,
+                &accounts,
+                &mut accounts.iter(),
+                clock,
+                &bridge_info,
+                bridge,
+                vaa,
+                body,
+            )
+        }
+
+        /// Posts the transfer VAA of the proposal, which is the first account
+        fn process_transfer_post(
+            &mut self,
+            program_id: &Pubkey,
+            body: &BodyTransfer,
+        ) -> ProgramResult {
+            let (accounts, bridge_info, _) = self.infos();
+            Bridge::process_vaa_transfer_post(
+                program_id,
+                &mut accounts.iter(),
+                &bridge_info,
+                &VAA::default(),
+                body,
+                &Pubkey::new(&[9; 32]),
+                &Pubkey::new(&[10; 32]),
+            )
+        }
+    }
+
+    /// Bridge state that moves tokens with the SPL token program
+    fn token_bridge() -> Bridge {
+        let mut bridge_data = vec![0u8; size_of::<Bridge>()];
+        let bridge: &mut Bridge = Bridge::unpack_unchecked(&mut bridge_data).unwrap();
+        bridge.config.token_program = spl_token::id();
+        *bridge
+    }
+
+    /// Inbound transfer of 100 tokens of the asset [6; 32] of chain 2 to `target_address`
+    fn transfer_in(target_address: [u8; 32]) -> BodyTransfer {
+        BodyTransfer {
+            nonce: 1,
+            source_chain: 2,
+            target_chain: CHAIN_ID_SOLANA,
+            source_address: [0; 32],
+            target_address,
+            asset: AssetMeta {
+                address: [6; 32],
+                chain: 2,
+                decimals: 8,
+            },
+            amount: U256::from(100),
+            fee: U256::zero(),
+        }
+    }
+
+    thread_local! {
+        static INVOKED: RefCell<Vec<Instruction>> = RefCell::new(Vec::new());
+    }
+
+    /// Records cross-program invocations instead of executing them. Associated token accounts
+    /// are created though, as the transfer that follows their creation depends on them.
+    struct RecordingStubs;
+
+    impl SyscallStubs for RecordingStubs {
+        fn sol_invoke_signed(
+            &self,
+            instruction: &Instruction,
+            account_infos: &[AccountInfo],
+            _signers_seeds: &[&[&[u8]]],
+        ) -> ProgramResult {
+            INVOKED.with(|invoked| invoked.borrow_mut().push(instruction.clone()));
+            if instruction.program_id == associated_token_program::id() {
+                let account = &instruction.accounts[1].pubkey;
+                let wallet = &instruction.accounts[2].pubkey;
+                let mint = &instruction.accounts[3].pubkey;
+                let info = account_infos
+                    .iter()
+                    .find(|info| info.key == account)
+                    .unwrap();
+                let created = token_account(*account, *mint, *wallet).data;
+                *info.data.borrow_mut() = Box::leak(created.into_boxed_slice());
+            }
+            Ok(())
+        }
+    }
+
+    fn record_invocations() {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(RecordingStubs));
+        });
+        INVOKED.with(|invoked| invoked.borrow_mut().clear());
+    }
+
+    /// Instructions invoked on this thread since the recording was started
+    fn invoked() -> Vec<Instruction> {
+        INVOKED.with(|invoked| invoked.borrow().clone())
+    }
+
+    fn mint_account(key: Pubkey, decimals: u8) -> TestAccount {
+        let mut account = TestAccount::new(key, 0, Mint::LEN, spl_token::id());
+        Mint::pack(
+            Mint {
+                decimals,
+                is_initialized: true,
+                ..Default::default()
+            },
+            &mut account.data,
+        )
+        .unwrap();
+        account
+    }
+
+    fn token_account(key: Pubkey, mint: Pubkey, owner: Pubkey) -> TestAccount {
+        let mut account = TestAccount::new(key, 0, spl_token::state::Account::LEN, spl_token::id());
+        spl_token::state::Account::pack(
+            spl_token::state::Account {
+                mint,
+                owner,
+                state: spl_token::state::AccountState::Initialized,
+                ..Default::default()
+            },
+            &mut account.data,
+        )
+        .unwrap();
+        account
+    }
+
+    /// Token program, mint, destination and wrapped asset meta of an inbound transfer of a
+    /// wrapped asset with 8 decimals
+    fn transfer_accounts(
+        program_id: &Pubkey,
+        mint: Pubkey,
+        destination: TestAccount,
+    ) -> Vec<TestAccount> {
+        vec![
+            TestAccount::new(spl_token::id(), 0, 0, Pubkey::default()),
+            mint_account(mint, 8),
+            destination,
+            TestAccount::new(Pubkey::new(&[8; 32]), 0, 0, *program_id),
+        ]
+    }
+
+    fn clock_account(unix_timestamp: i64) -> TestAccount {
+        let mut account =
+            TestAccount::new(sysvar::clock::id(), 1, Clock::size_of(), sysvar::id());
+        let clock = Clock {
+            unix_timestamp,
+            ..Clock::default()
+        };
+        clock.to_account_info(&mut account.info(false)).unwrap();
+        account
+    }
+
+    fn bridge_account(program_id: &Pubkey) -> TestAccount {
+        let key = Bridge::derive_bridge_id(program_id).unwrap();
+        let mut account = TestAccount::new(key, 0, size_of::<Bridge>(), *program_id);
+        let bridge: &mut Bridge = Bridge::unpack_unchecked(&mut account.data).unwrap();
+        bridge.is_initialized = true;
+        bridge.config.vaa_expiration_time = VAA_EXPIRATION_TIME;
+        account
+    }
+
+    fn proposal_account(program_id: &Pubkey, vaa_time: u32) -> TestAccount {
+        let bridge_key = Bridge::derive_bridge_id(program_id).unwrap();
+        let asset = AssetMeta {
+            address: [2; 32],
+            chain: 2,
+            decimals: 8,
+        };
+        let key = Bridge::derive_transfer_id(
+            program_id,
+            &bridge_key,
+            asset.chain,
+            asset.address,
+            2,
+            [3; 32],
+            [4; 32],
+            5,
+        )
+        .unwrap();
+
+        let mut account =
+            TestAccount::new(key, 1000, size_of::<TransferOutProposal>(), *program_id);
+        let proposal: &mut TransferOutProposal =
+            Bridge::unpack_unchecked(&mut account.data).unwrap();
+        proposal.is_initialized = true;
+        proposal.asset = asset;
+        proposal.to_chain_id = 2;
+        proposal.foreign_address = [3; 32];
+        proposal.source_address = [4; 32];
+        proposal.nonce = 5;
+        proposal.vaa_time = vaa_time;
+        account
+    }
+
+    fn claim_account(program_id: &Pubkey, vaa_time: u32) -> TestAccount {
+        let bridge_key = Bridge::derive_bridge_id(program_id).unwrap();
+        let key = Bridge::derive_claim_id(program_id, &bridge_key, &[9; 32]).unwrap();
+        let mut account = TestAccount::new(key, 1000, size_of::<ClaimedVAA>(), *program_id);
+        let claim: &mut ClaimedVAA = Bridge::unpack_unchecked(&mut account.data).unwrap();
+        claim.is_initialized = true;
+        claim.hash = [9; 32];
+        claim.vaa_time = vaa_time;
+        account
+    }
+
+    fn posted_vaa_account(program_id: &Pubkey, vaa: &[u8]) -> TestAccount {
+        let bridge_key = Bridge::derive_bridge_id(program_id).unwrap();
+        let key = Bridge::derive_posted_vaa_id(program_id, &bridge_key, &[9; 32]).unwrap();
+        let mut account =
+            TestAccount::new(key, 1000, size_of::<PostedVAA>() + vaa.len(), *program_id);
+        let (header, data) = account.data.split_at_mut(size_of::<PostedVAA>());
+        let posted_vaa: &mut PostedVAA = Bridge::unpack_unchecked(header).unwrap();
+        posted_vaa.is_initialized = true;
+        posted_vaa.hash = [9; 32];
+        posted_vaa.len = vaa.len() as u32;
+        data.copy_from_slice(vaa);
+        account
+    }
+
+    fn evict(
+        program_id: &Pubkey,
+        ix: &Instruction,
+        targets: &mut [&mut TestAccount],
+        guardian: &mut TestAccount,
+        bridge: &mut TestAccount,
+        now: i64,
+    ) -> ProgramResult {
+        let mut program = TestAccount::new(*program_id, 0, 0, Pubkey::default());
+        let mut clock = clock_account(now);
+
+        let mut accounts = vec![
+            program.info(false),
+            guardian.info(true),
+            clock.info(false),
+            bridge.info(false),
+        ];
+        accounts.extend(targets.iter_mut().map(|target| target.info(false)));
+        Bridge::process(program_id, &accounts, &ix.data)
+    }
+
+    #[test]
+    fn next_sequence_increments() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut bridge = TestAccount::new(
+            Bridge::derive_bridge_id(&program_id).unwrap(),
+            0,
+            size_of::<Bridge>(),
+            program_id,
+        );
+        assert!(Bridge::next_sequence(&bridge.info(false)).is_err());
+
+        let state: &mut Bridge = Bridge::unpack_unchecked(&mut bridge.data).unwrap();
+        state.is_initialized = true;
+        for expected in 0..3 {
+            assert_eq!(Bridge::next_sequence(&bridge.info(false)).unwrap(), expected);
+        }
+        let state: &mut Bridge = Bridge::unpack(&mut bridge.data).unwrap();
+        assert_eq!(state.sequence, 3);
+    }
+
+    fn governance(
+        bridge_account: &mut TestAccount,
+        recipient: &mut TestAccount,
+        change: GovernanceChange,
+        target_chain: u8,
+    ) -> (ProgramResult, Bridge) {
+        governance_paused(bridge_account, recipient, change, target_chain, false)
+    }
+
+    /// Applies a governance change to a bridge that has both directions paused or not
+    fn governance_paused(
+        bridge_account: &mut TestAccount,
+        recipient: &mut TestAccount,
+        change: GovernanceChange,
+        target_chain: u8,
+        paused: bool,
+    ) -> (ProgramResult, Bridge) {
+        let mut bridge_data = vec![0u8; size_of::<Bridge>()];
+        let bridge: &mut Bridge = Bridge::unpack_unchecked(&mut bridge_data).unwrap();
+        bridge.is_initialized = true;
+        bridge.paused_inbound = paused;
+        bridge.paused_outbound = paused;
+        let mut guardian_data = vec![0u8; size_of::<GuardianSet>()];
+        let guardian_set: &mut GuardianSet = Bridge::unpack_unchecked(&mut guardian_data).unwrap();
+
+        let accounts = vec![recipient.info(false)];
+        let result = Bridge::process_vaa_governance(
+            &mut accounts.iter(),
+            &bridge_account.info(false),
+            bridge,
+            guardian_set,
+            VAA_TIME,
+            &BodyGovernance {
+                target_chain,
+                change,
+            },
+        );
+        (result, *bridge)
+    }
+
+    #[test]
+    fn governance_fee_config() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut bridge = TestAccount::new(Pubkey::new(&[2; 32]), 0, 0, program_id);
+        let mut recipient = TestAccount::new(Pubkey::new(&[3; 32]), 0, 0, Pubkey::default());
+
+        let (result, state) = governance(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::TransferFee(5000),
+            CHAIN_ID_SOLANA,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(state.config.transfer_fee, 5000);
+
+        let (result, state) = governance(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::VAARefund(1000),
+            CHAIN_ID_SOLANA,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(state.config.vaa_tx_refund, 1000);
+
+        let (result, state) = governance(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::VAAExpiration(30 * 24 * 60 * 60),
+            CHAIN_ID_SOLANA,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(state.config.vaa_expiration_time, 30 * 24 * 60 * 60);
+
+        let (result, _) = governance(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::VAAExpiration(u64::from(u32::MAX) + 1),
+            CHAIN_ID_SOLANA,
+        );
+        assert_eq!(result, Err(Error::InvalidVAAFormat.into()));
+
+        let (result, _) = governance(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::TransferFee(5000),
+            3,
+        );
+        assert_eq!(result, Err(Error::WrongTargetChain.into()));
+
+        let (result, _) = governance(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::WrappedAssetCodeId(7),
+            CHAIN_ID_SOLANA,
+        );
+        assert_eq!(result, Err(Error::InvalidVAAAction.into()));
+
+        let (result, _) = governance(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::Unknown {
+                kind: 0x7f,
+                bytes: vec![1, 2, 3],
+            },
+            CHAIN_ID_SOLANA,
+        );
+        assert_eq!(result, Err(Error::InvalidVAAAction.into()));
+    }
+
+    #[test]
+    fn governance_pause() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut bridge = TestAccount::new(Pubkey::new(&[2; 32]), 0, 0, program_id);
+        let mut recipient = TestAccount::new(Pubkey::new(&[3; 32]), 0, 0, Pubkey::default());
+
+        let (result, state) = governance(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::Active(false),
+            CHAIN_ID_SOLANA,
+        );
+        assert_eq!(result, Ok(()));
+        assert!(state.paused_inbound && state.paused_outbound);
+
+        let (result, state) = governance(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::Pause {
+                inbound: true,
+                outbound: false,
+            },
+            CHAIN_ID_SOLANA,
+        );
+        assert_eq!(result, Ok(()));
+        assert!(state.paused_inbound);
+        assert!(!state.paused_outbound);
+
+        // Inbound transfers are rejected before any account is touched
+        let mut payer = TestAccount::new(Pubkey::new(&[4; 32]), 0, 0, Pubkey::default());
+        let transfer = BodyTransfer {
+            nonce: 1,
+            source_chain: 2,
+            target_chain: CHAIN_ID_SOLANA,
+            source_address: [0; 32],
+            target_address: [5; 32],
+            asset: AssetMeta {
+                address: [6; 32],
+                chain: 2,
+                decimals: 8,
+            },
+            amount: U256::from(100),
+            fee: U256::zero(),
+        };
+        let accounts: Vec<AccountInfo> = vec![];
+        let result = Bridge::process_vaa_transfer(
+            &program_id,
+            &accounts,
+            &mut accounts.iter(),
+            &bridge.info(false),
+            &payer.info(false),
+            &state,
+            &transfer,
+        );
+        assert_eq!(result, Err(Error::BridgePaused.into()));
+    }
+
+    #[test]
+    fn paused_bridge_accepts_governance() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut bridge = TestAccount::new(Pubkey::new(&[2; 32]), 0, 0, program_id);
+        let mut recipient = TestAccount::new(Pubkey::new(&[3; 32]), 0, 0, Pubkey::default());
+
+        let (result, state) = governance_paused(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::TransferFee(5000),
+            CHAIN_ID_SOLANA,
+            true,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(state.config.transfer_fee, 5000);
+
+        let (result, state) = governance_paused(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::Active(true),
+            CHAIN_ID_SOLANA,
+            true,
+        );
+        assert_eq!(result, Ok(()));
+        assert!(!state.paused_inbound && !state.paused_outbound);
+    }
+
+    #[test]
+    fn paused_bridge_accepts_set_update() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let bridge_key = Bridge::derive_bridge_id(&program_id).unwrap();
+        let mut bridge_data = vec![0u8; size_of::<Bridge>()];
+        let bridge: &mut Bridge = Bridge::unpack_unchecked(&mut bridge_data).unwrap();
+        bridge.paused_inbound = true;
+        bridge.paused_outbound = true;
+        let mut guardian_set_data = vec![0u8; size_of::<GuardianSet>()];
+        let old_guardian_set: &mut GuardianSet =
+            Bridge::unpack_unchecked(&mut guardian_set_data).unwrap();
+        let new_guardian_set = TestAccount::new(
+            Bridge::derive_guardian_set_id(&program_id, &bridge_key, 1).unwrap(),
+            0,
+            GuardianSet::size(1),
+            program_id,
+        );
+        let mut fixture = ProcessorAccounts::new(&program_id, vec![new_guardian_set]);
+        let (accounts, bridge_info, payer_info) = fixture.infos();
+        let clock = Clock {
+            unix_timestamp: VAA_TIME as i64,
+            ..Clock::default()
+        };
+
+        let result = Bridge::process_vaa_set_update(
+            &program_id,
+            &accounts,
+            &mut accounts.iter(),
+            &clock,
+            &bridge_info,
+            &payer_info,
+            bridge,
+            old_guardian_set,
+            &BodyUpdateGuardianSet {
+                new_index: 1,
+                new_keys: vec![[1; 20]],
+                threshold: None,
+            },
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(bridge.guardian_set_index, 1);
+    }
+
+    #[test]
+    fn paused_bridge_posts_transfer_vaa() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut bridge = bridge_account(&program_id);
+        let state: &mut Bridge = Bridge::unpack(&mut bridge.data).unwrap();
+        state.paused_outbound = true;
+        // Matches the proposal created by `proposal_account`, locked before the pause
+        let mut proposal = proposal_account(&program_id, 0);
+        let body = BodyTransfer {
+            nonce: 5,
+            source_chain: CHAIN_ID_SOLANA,
+            target_chain: 2,
+            source_address: [4; 32],
+            target_address: [3; 32],
+            asset: AssetMeta {
+                address: [2; 32],
+                chain: 2,
+                decimals: 8,
+            },
+            amount: U256::zero(),
+            fee: U256::zero(),
+        };
+        let vaa = VAA {
+            timestamp: VAA_TIME,
+            ..VAA::default()
+        };
+
+        let accounts = vec![proposal.info(false)];
+        let result = Bridge::process_vaa_transfer_post(
+            &program_id,
+            &mut accounts.iter(),
+            &bridge.info(false),
+            &vaa,
+            &body,
+            &Pubkey::new(&[9; 32]),
+            &Pubkey::new(&[10; 32]),
+        );
+        assert_eq!(result, Ok(()));
+        drop(accounts);
+        let posted: &TransferOutProposal = Bridge::unpack_immutable(&proposal.data).unwrap();
+        assert_eq!(posted.vaa_time, VAA_TIME);
+    }
+
+    #[test]
+    fn paused_bridge_posts_message_vaa() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut bridge = bridge_account(&program_id);
+        let state: &mut Bridge = Bridge::unpack(&mut bridge.data).unwrap();
+        state.paused_outbound = true;
+        let emitter = Pubkey::new(&[2; 32]);
+        // Published before the pause
+        let mut message_account = message_account(&program_id, &emitter, b"hello");
+        let vaa = VAA {
+            timestamp: VAA_TIME,
+            ..VAA::default()
+        };
+
+        let accounts = vec![message_account.info(false)];
+        let result = Bridge::process_vaa_message_post(
+            &program_id,
+            &mut accounts.iter(),
+            &bridge.info(false),
+            &vaa,
+            &BodyMessage {
+                emitter_chain: CHAIN_ID_SOLANA,
+                emitter_address: emitter.to_bytes(),
+                nonce: 5,
+                sequence: 3,
+                payload: b"hello".to_vec(),
+            },
+            &Pubkey::new(&[3; 32]),
+        );
+        assert_eq!(result, Ok(()));
+        drop(accounts);
+        let (message, _) = Bridge::unpack_message(&message_account.data).unwrap();
+        assert_eq!(message.vaa_time, VAA_TIME);
+    }
+
+    #[test]
+    fn governance_withdraw_fees() {
+        let program_id = Pubkey::new(&[1; 32]);
+        // The bridge account has to stay rent exempt for its full size
+        let min_balance = Rent::default().minimum_balance(size_of::<Bridge>());
+        assert_eq!(Bridge::min_bridge_balance(), min_balance);
+        let mut bridge = TestAccount::new(
+            Pubkey::new(&[2; 32]),
+            min_balance + 1000,
+            size_of::<Bridge>(),
+            program_id,
+        );
+        let mut recipient = TestAccount::new(Pubkey::new(&[3; 32]), 0, 0, Pubkey::default());
+        let withdraw = GovernanceChange::WithdrawFees {
+            recipient: recipient.key.to_bytes(),
+            amount: 600,
+        };
+
+        let (result, _) = governance(
+            &mut bridge,
+            &mut recipient,
+            withdraw.clone(),
+            CHAIN_ID_SOLANA,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(bridge.lamports, min_balance + 400);
+        assert_eq!(recipient.lamports, 600);
+
+        // The rent exempt balance of the bridge cannot be withdrawn
+        let (result, _) = governance(&mut bridge, &mut recipient, withdraw, CHAIN_ID_SOLANA);
+        assert_eq!(result, Err(ProgramError::InsufficientFunds));
+        assert_eq!(bridge.lamports, min_balance + 400);
+
+        // Fees are only sent to the recipient of the VAA
+        let (result, _) = governance(
+            &mut bridge,
+            &mut recipient,
+            GovernanceChange::WithdrawFees {
+                recipient: [4; 32],
+                amount: 100,
+            },
+            CHAIN_ID_SOLANA,
+        );
+        assert_eq!(result, Err(ProgramError::InvalidArgument));
+    }
+
+    #[test]
+    fn governance_replay() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut bridge_account = TestAccount::new(Pubkey::new(&[2; 32]), 0, 0, program_id);
+        let mut bridge_data = vec![0u8; size_of::<Bridge>()];
+        let bridge: &mut Bridge = Bridge::unpack_unchecked(&mut bridge_data).unwrap();
+        let mut guardian_data = vec![0u8; size_of::<GuardianSet>()];
+        let guardian_set: &mut GuardianSet = Bridge::unpack_unchecked(&mut guardian_data).unwrap();
+        let body = BodyGovernance {
+            target_chain: CHAIN_ID_SOLANA,
+            change: GovernanceChange::TransferFee(5000),
+        };
+
+        let mut process = |bridge: &mut Bridge, vaa_time: u32| {
+            let accounts: Vec<AccountInfo> = vec![];
+            Bridge::process_vaa_governance(
+                &mut accounts.iter(),
+                &bridge_account.info(false),
+                bridge,
+                guardian_set,
+                vaa_time,
+                &body,
+            )
+        };
+
+        assert_eq!(process(bridge, VAA_TIME), Ok(()));
+        assert_eq!(bridge.governance_vaa_time, VAA_TIME);
+
+        // Governance VAAs do not expire, a VAA whose claim was evicted must not apply again
+        assert_eq!(
+            process(bridge, VAA_TIME),
+            Err(Error::OldGovernanceVAA.into())
+        );
+        assert_eq!(
+            process(bridge, VAA_TIME - 1),
+            Err(Error::OldGovernanceVAA.into())
+        );
+        assert_eq!(process(bridge, VAA_TIME + 1), Ok(()));
+    }
+
+    #[test]
+    fn vaa_expiration() {
+        let mut bridge_data = vec![0u8; size_of::<Bridge>()];
+        let bridge: &mut Bridge = Bridge::unpack_unchecked(&mut bridge_data).unwrap();
+        bridge.config.vaa_expiration_time = VAA_EXPIRATION_TIME;
+        let clock = Clock {
+            unix_timestamp: VAA_TIME as i64 + VAA_EXPIRATION_TIME as i64 + 1,
+            ..Clock::default()
+        };
+        let vaa = |timestamp: u32, payload: VAABody| VAA {
+            timestamp,
+            payload: Some(payload),
+            ..VAA::default()
+        };
+        let transfer = VAABody::Transfer(BodyTransfer {
+            nonce: 1,
+            source_chain: 2,
+            target_chain: CHAIN_ID_SOLANA,
+            source_address: [0; 32],
+            target_address: [4; 32],
+            asset: AssetMeta {
+                address: [6; 32],
+                chain: 2,
+                decimals: 8,
+            },
+            amount: U256::from(100),
+            fee: U256::zero(),
+        });
+        let set_update = VAABody::UpdateGuardianSet(BodyUpdateGuardianSet {
+            new_index: 1,
+            new_keys: vec![[1; 20]],
+            threshold: None,
+        });
+        let governance = VAABody::Governance(BodyGovernance {
+            target_chain: CHAIN_ID_SOLANA,
+            change: GovernanceChange::TransferFee(5000),
+        });
+
+        // Inbound transfers can only be redeemed within the expiration time
+        assert_eq!(
+            Bridge::check_vaa_expiration(&vaa(VAA_TIME + 1, transfer.clone()), bridge, &clock),
+            Ok(())
+        );
+        assert_eq!(
+            Bridge::check_vaa_expiration(&vaa(VAA_TIME, transfer.clone()), bridge, &clock),
+            Err(Error::VAAExpired.into())
+        );
+
+        // Guardian set updates and governance VAAs can be applied late
+        assert_eq!(
+            Bridge::check_vaa_expiration(&vaa(VAA_TIME, set_update.clone()), bridge, &clock),
+            Ok(())
+        );
+        assert_eq!(
+            Bridge::check_vaa_expiration(&vaa(VAA_TIME, governance.clone()), bridge, &clock),
+            Ok(())
+        );
+
+        // VAAs up to the newest evicted claim are rejected even if they have not expired
+        bridge.evicted_vaa_time = VAA_TIME + 1;
+        assert_eq!(
+            Bridge::check_vaa_expiration(&vaa(VAA_TIME + 1, transfer), bridge, &clock),
+            Err(Error::VAAExpired.into())
+        );
+        assert_eq!(
+            Bridge::check_vaa_expiration(&vaa(VAA_TIME + 1, set_update), bridge, &clock),
+            Ok(())
+        );
+        assert_eq!(
+            Bridge::check_vaa_expiration(&vaa(VAA_TIME + 1, governance), bridge, &clock),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn dynamic_transfer_fee() {
+        let mut fee_state = FeeState::default();
+        let min_fee = 1000;
+        assert_eq!(fee_state.transfer_fee(100, min_fee), min_fee);
+
+        // Half of the capacity costs (1/2)^6 SOL
+        let max_transfers = FEE_MAX_TPS * 30;
+        for i in 0..max_transfers / 2 {
+            fee_state.record_transfer(100 + i % 10);
+        }
+        assert_eq!(fee_state.transfers_in_window(110), max_transfers / 2);
+        assert_eq!(fee_state.transfer_fee(110, min_fee), 1_000_000_000 / 64);
+
+        // Buckets leave the window after 30 seconds
+        let window_end = 100 + FEE_BUCKET_SLOTS * FEE_BUCKETS as u64;
+        assert_eq!(fee_state.transfers_in_window(window_end), max_transfers / 4);
+        assert_eq!(fee_state.transfers_in_window(window_end + 5), 0);
+        assert_eq!(fee_state.transfer_fee(window_end + 5, min_fee), min_fee);
+
+        // Reused buckets are reset
+        fee_state.record_transfer(window_end + 5);
+        assert_eq!(fee_state.transfers_in_window(window_end + 5), 1);
+    }
+
+    #[test]
+    fn evict_transfer_out_after_expiration() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge = bridge_account(&program_id);
+        let mut proposal = proposal_account(&program_id, VAA_TIME);
+
+        let ix = evict_transfer_out(&program_id, &guardian.key, &proposal.key).unwrap();
+        let now = VAA_TIME as i64 + VAA_EXPIRATION_TIME as i64 + 1;
+        evict(
+            &program_id,
+            &ix,
+            &mut [&mut proposal],
+            &mut guardian,
+            &mut bridge,
+            now,
+        )
+        .unwrap();
+
+        assert_eq!(proposal.lamports, 0);
+        assert_eq!(guardian.lamports, 1010);
+        assert!(proposal.data.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn evict_transfer_out_before_expiration() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge = bridge_account(&program_id);
+        let mut proposal = proposal_account(&program_id, VAA_TIME);
+
+        let ix = evict_transfer_out(&program_id, &guardian.key, &proposal.key).unwrap();
+        let now = VAA_TIME as i64 + VAA_EXPIRATION_TIME as i64;
+        let result = evict(
+            &program_id,
+            &ix,
+            &mut [&mut proposal],
+            &mut guardian,
+            &mut bridge,
+            now,
+        );
+
+        assert_eq!(result, Err(Error::VAANotExpired.into()));
+        assert_eq!(proposal.lamports, 1000);
+        assert_eq!(guardian.lamports, 10);
+    }
+
+    #[test]
+    fn evict_transfer_out_without_vaa() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge = bridge_account(&program_id);
+        let mut proposal = proposal_account(&program_id, 0);
+
+        let ix = evict_transfer_out(&program_id, &guardian.key, &proposal.key).unwrap();
+        let result = evict(
+            &program_id,
+            &ix,
+            &mut [&mut proposal],
+            &mut guardian,
+            &mut bridge,
+            i64::MAX,
+        );
+
+        assert_eq!(result, Err(Error::VAANotExpired.into()));
+    }
+
+    #[test]
+    fn evict_transfer_out_wrong_derivation() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge = bridge_account(&program_id);
+        let mut proposal = proposal_account(&program_id, VAA_TIME);
+        proposal.key = Pubkey::new(&[8; 32]);
+
+        let ix = evict_transfer_out(&program_id, &guardian.key, &proposal.key).unwrap();
+        let result = evict(
+            &program_id,
+            &ix,
+            &mut [&mut proposal],
+            &mut guardian,
+            &mut bridge,
+            i64::MAX,
+        );
+
+        assert_eq!(result, Err(Error::InvalidDerivedAccount.into()));
+    }
+
+    #[test]
+    fn evict_claimed_vaa_after_expiration() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge = bridge_account(&program_id);
+        let mut claim = claim_account(&program_id, VAA_TIME);
+        let mut posted_vaa = posted_vaa_account(&program_id, &[1, 2, 3]);
+
+        let ix = evict_claimed_vaa(&program_id, &guardian.key, &[9; 32]).unwrap();
+        let now = VAA_TIME as i64 + VAA_EXPIRATION_TIME as i64 + 1;
+        evict(
+            &program_id,
+            &ix,
+            &mut [&mut claim, &mut posted_vaa],
+            &mut guardian,
+            &mut bridge,
+            now,
+        )
+        .unwrap();
+
+        assert_eq!(claim.lamports, 0);
+        assert_eq!(posted_vaa.lamports, 0);
+        assert_eq!(guardian.lamports, 2010);
+        assert!(claim.data.iter().all(|b| *b == 0));
+        assert!(posted_vaa.data.iter().all(|b| *b == 0));
+
+        // The VAA stays rejected even if governance extends the expiration
+        let state: &Bridge = Bridge::unpack_immutable(&bridge.data).unwrap();
+        assert_eq!(state.evicted_vaa_time, VAA_TIME);
+    }
+
+    #[test]
+    fn evict_with_governance_expiration() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge = bridge_account(&program_id);
+        let state: &mut Bridge = Bridge::unpack(&mut bridge.data).unwrap();
+        state.config.vaa_expiration_time = 30 * 24 * 60 * 60;
+        let mut proposal = proposal_account(&program_id, VAA_TIME);
+
+        // The expiration is taken from the bridge config
+        let ix = evict_transfer_out(&program_id, &guardian.key, &proposal.key).unwrap();
+        let now = VAA_TIME as i64 + VAA_EXPIRATION_TIME as i64 + 1;
+        let result = evict(
+            &program_id,
+            &ix,
+            &mut [&mut proposal],
+            &mut guardian,
+            &mut bridge,
+            now,
+        );
+        assert_eq!(result, Err(Error::VAANotExpired.into()));
+
+        let now = VAA_TIME as i64 + 30 * 24 * 60 * 60 + 1;
+        evict(
+            &program_id,
+            &ix,
+            &mut [&mut proposal],
+            &mut guardian,
+            &mut bridge,
+            now,
+        )
+        .unwrap();
+        assert_eq!(proposal.lamports, 0);
+    }
+
+    #[test]
+    fn evict_claimed_vaa_before_expiration() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge = bridge_account(&program_id);
+        let mut claim = claim_account(&program_id, VAA_TIME);
+        let mut posted_vaa = posted_vaa_account(&program_id, &[1, 2, 3]);
+
+        let ix = evict_claimed_vaa(&program_id, &guardian.key, &[9; 32]).unwrap();
+        let result = evict(
+            &program_id,
+            &ix,
+            &mut [&mut claim, &mut posted_vaa],
+            &mut guardian,
+            &mut bridge,
+            VAA_TIME as i64,
+        );
+
+        assert_eq!(result, Err(Error::VAANotExpired.into()));
+        assert_eq!(claim.lamports, 1000);
+    }
+
+    #[test]
+    fn evict_claimed_vaa_wrong_owner() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge = bridge_account(&program_id);
+        let mut claim = claim_account(&program_id, VAA_TIME);
+        let mut posted_vaa = posted_vaa_account(&program_id, &[1, 2, 3]);
+        claim.owner = Pubkey::new(&[6; 32]);
+
+        let ix = evict_claimed_vaa(&program_id, &guardian.key, &[9; 32]).unwrap();
+        let result = evict(
+            &program_id,
+            &ix,
+            &mut [&mut claim, &mut posted_vaa],
+            &mut guardian,
+            &mut bridge,
+            i64::MAX,
+        );
+
+        assert_eq!(result, Err(ProgramError::IncorrectProgramId));
+    }
+
+    #[test]
+    fn evict_claimed_vaa_wrong_derivation() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge = bridge_account(&program_id);
+        let mut claim = claim_account(&program_id, VAA_TIME);
+        let mut posted_vaa = posted_vaa_account(&program_id, &[1, 2, 3]);
+        claim.key = Pubkey::new(&[9; 32]);
+
+        let ix = evict_claimed_vaa(&program_id, &guardian.key, &[9; 32]).unwrap();
+        let result = evict(
+            &program_id,
+            &ix,
+            &mut [&mut claim, &mut posted_vaa],
+            &mut guardian,
+            &mut bridge,
+            i64::MAX,
+        );
+
+        assert_eq!(result, Err(Error::InvalidDerivedAccount.into()));
+        assert_eq!(claim.lamports, 1000);
+    }
+
+    #[test]
+    fn evict_claimed_vaa_wrong_posted_vaa() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge = bridge_account(&program_id);
+        let mut claim = claim_account(&program_id, VAA_TIME);
+        let mut posted_vaa = posted_vaa_account(&program_id, &[1, 2, 3]);
+        posted_vaa.key = Pubkey::new(&[9; 32]);
+
+        let ix = evict_claimed_vaa(&program_id, &guardian.key, &[9; 32]).unwrap();
+        let result = evict(
+            &program_id,
+            &ix,
+            &mut [&mut claim, &mut posted_vaa],
+            &mut guardian,
+            &mut bridge,
+            i64::MAX,
+        );
+
+        assert_eq!(result, Err(Error::InvalidDerivedAccount.into()));
+        assert_eq!(posted_vaa.lamports, 1000);
+    }
+
+    #[test]
+    fn unpack_posted_vaa() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let mut posted_vaa = posted_vaa_account(&program_id, &[1, 2, 3]);
+
+        let (header, vaa) = Bridge::unpack_posted_vaa(&posted_vaa.data).unwrap();
+        assert_eq!(header.hash, [9; 32]);
+        assert_eq!(vaa, &[1, 2, 3]);
+
+        // The length of the VAA must match the header
+        posted_vaa.data.push(4);
+        assert_eq!(
+            Bridge::unpack_posted_vaa(&posted_vaa.data).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn posted_vaa_holds_signatures() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let vaa = VAA {
+            version: 1,
+            guardian_set_index: 3,
+            signatures: Vec::new(),
+            timestamp: VAA_TIME,
+            payload: Some(VAABody::Governance(BodyGovernance {
+                target_chain: CHAIN_ID_SOLANA,
+                change: GovernanceChange::TransferFee(5000),
+            })),
+        };
+
+        // Slots of guardians that did not sign are empty
+        let mut signatures = [[0u8; 65]; 4];
+        signatures[1] = [1; 65];
+        signatures[1][64] = 0;
+        signatures[3] = [2; 65];
+        signatures[3][64] = 1;
+
+        let signed = Bridge::signed_vaa(&vaa, &signatures).serialize().unwrap();
+        let posted_vaa = posted_vaa_account(&program_id, &signed);
+        let (_, data) = Bridge::unpack_posted_vaa(&posted_vaa.data).unwrap();
+
+        let posted = VAA::deserialize(data).unwrap();
+        assert_eq!(
+            posted.signatures,
+            vec![
+                Signature {
+                    index: 1,
+                    r: [1; 32],
+                    s: [1; 32],
+                    v: 0,
+                },
+                Signature {
+                    index: 3,
+                    r: [2; 32],
+                    s: [2; 32],
+                    v: 1,
+                },
+            ]
+        );
+        assert_eq!(posted.body_hash(), vaa.body_hash());
+        assert_eq!(posted.payload, vaa.payload);
+    }
+
+    #[test]
+    fn unpack_guardian_set() {
+        let mut data = vec![0u8; GuardianSet::size(30)];
+        {
+            let (guardian_set, keys) = Bridge::unpack_guardian_set_unchecked(&mut data).unwrap();
+            assert_eq!(keys.len(), 30);
+            guardian_set.is_initialized = true;
+            guardian_set.len_keys = 30;
+            guardian_set.threshold = 12;
+            keys[29] = [7; 20];
+        }
+
+        let (guardian_set, keys) = Bridge::unpack_guardian_set_immutable(&data).unwrap();
+        assert_eq!(guardian_set.len_keys, 30);
+        assert_eq!(guardian_set.threshold, 12);
+        assert_eq!(keys[29], [7; 20]);
+
+        // The number of keys must match the header
+        data.extend_from_slice(&[0; 20]);
+        assert_eq!(
+            Bridge::unpack_guardian_set(&mut data).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+
+        // Accounts must hold whole keys
+        data.push(0);
+        assert_eq!(
+            Bridge::unpack_guardian_set_unchecked(&mut data).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn guardian_set_instructions_round_trip() {
+        let mut payload = VerifySigPayload::default();
+        for index in &[0, 9, 21, 200] {
+            payload.set_signer(*index);
+        }
+        assert_eq!(payload.signers.len(), 26);
+        assert_eq!(payload.signer_indices(), vec![0, 9, 21, 200]);
+
+        let data = BridgeInstruction::VerifySignatures(payload)
+            .serialize()
+            .unwrap();
+        match BridgeInstruction::deserialize(&data).unwrap() {
+            VerifySignatures(p) => assert_eq!(p.signer_indices(), vec![0, 9, 21, 200]),
+            _ => panic!("expected VerifySignatures"),
+        }
+
+        let initial_guardian: Vec<GuardianAddress> = (0..25).map(|i| [i; 20]).collect();
+        let data = BridgeInstruction::Initialize(InitializePayload {
+            initial_guardian: initial_guardian.clone(),
+            config: BridgeConfig::default(),
+        })
+        .serialize()
+        .unwrap();
+        match BridgeInstruction::deserialize(&data).unwrap() {
+            Initialize(p) => assert_eq!(p.initial_guardian, initial_guardian),
+            _ => panic!("expected Initialize"),
+        }
+    }
+
+    /// maximum size of a transaction
+    const PACKET_DATA_SIZE: usize = 1280 - 40 - 8;
+
+    /// Size of a transaction that carries `ix` and is signed by the payer
+    fn transaction_size(ix: &Instruction, payer: &Pubkey) -> usize {
+        // Number of signatures and the signature of the payer, followed by the message
+        1 + 64 + Message::new(&[ix.clone()], Some(payer)).serialize().len()
+    }
+
+    #[test]
+    fn guardian_set_max_size() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let bridge_key = Bridge::derive_bridge_id(&program_id).unwrap();
+        let payer = Pubkey::new(&[10; 32]);
+        let keys: Vec<GuardianAddress> = (0..MAX_LEN_GUARDIAN_KEYS)
+            .map(|i| [i as u8 + 1; 20])
+            .collect();
+        let config = BridgeConfig {
+            vaa_expiration_time: VAA_EXPIRATION_TIME,
+            token_program: spl_token::id(),
+            ..BridgeConfig::default()
+        };
+
+        let process_initialize = |keys: Vec<GuardianAddress>, config: BridgeConfig| {
+            let data = BridgeInstruction::Initialize(InitializePayload {
+                initial_guardian: keys.clone(),
+                config,
+            })
+            .serialize()
+            .unwrap();
+            let mut guardian_set = TestAccount::new(
+                Bridge::derive_guardian_set_id(&program_id, &bridge_key, 0).unwrap(),
+                0,
+                GuardianSet::size(keys.len()),
+                program_id,
+            );
+            let mut system = TestAccount::new(
+                solana_program::system_program::id(),
+                0,
+                0,
+                Pubkey::default(),
+            );
+            let mut clock = clock_account(VAA_TIME as i64);
+            let mut bridge = TestAccount::new(bridge_key, 0, size_of::<Bridge>(), program_id);
+            let mut payer = TestAccount::new(payer, 0, 0, Pubkey::default());
+            record_invocations();
+            let result = {
+                let accounts = vec![
+                    system.info(false),
+                    clock.info(false),
+                    bridge.info(false),
+                    guardian_set.info(false),
+                    payer.info(true),
+                ];
+                Bridge::process(&program_id, &accounts, &data)
+            };
+            (result, guardian_set.data)
+        };
+
+        // The bridge is initialized with the largest guardian set in a single transaction
+        let ix = initialize(&program_id, &payer, keys.clone(), &config).unwrap();
+        assert!(transaction_size(&ix, &payer) <= PACKET_DATA_SIZE);
+        let (result, mut data) = process_initialize(keys.clone(), config);
+        assert_eq!(result, Ok(()));
+        let (guardian_set, set_keys) = Bridge::unpack_guardian_set(&mut data).unwrap();
+        assert_eq!(guardian_set.len_keys as usize, MAX_LEN_GUARDIAN_KEYS);
+        assert_eq!(set_keys.to_vec(), keys);
+
+        // Empty and larger guardian sets are rejected
+        let mut too_many = keys.clone();
+        too_many.push([0xff; 20]);
+        for keys in vec![vec![], too_many] {
+            assert_eq!(
+                initialize(&program_id, &payer, keys.clone(), &config).err(),
+                Some(ProgramError::InvalidArgument)
+            );
+            assert_eq!(
+                process_initialize(keys, config).0,
+                Err(ProgramError::InvalidInstructionData)
+            );
+        }
+
+        // Associated token accounts are derived with the SPL token program, the bridge must use it
+        let other_token_program = BridgeConfig {
+            token_program: Pubkey::new(&[11; 32]),
+            ..config
+        };
+        assert_eq!(
+            initialize(&program_id, &payer, keys.clone(), &other_token_program).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            process_initialize(keys.clone(), other_token_program).0,
+            Err(Error::InvalidTokenProgram.into())
+        );
+
+        // The largest guardian set update is posted in a single transaction
+        let body = BodyUpdateGuardianSet {
+            new_index: 1,
+            new_keys: keys.clone(),
+            threshold: Some(MAX_LEN_GUARDIAN_KEYS as u8),
+        };
+        let vaa = VAA {
+            version: 1,
+            guardian_set_index: 0,
+            signatures: Vec::new(),
+            timestamp: VAA_TIME,
+            payload: Some(VAABody::UpdateGuardianSet(body.clone())),
+        };
+        let ix = post_vaa(&program_id, &payer, vaa.serialize().unwrap()).unwrap();
+        assert!(transaction_size(&ix, &payer) <= PACKET_DATA_SIZE);
+
+        // Its posted VAA fits the signatures of all guardians
+        let signatures = vec![[1; 65]; MAX_LEN_GUARDIAN_KEYS];
+        let signed = Bridge::signed_vaa(&vaa, &signatures).serialize().unwrap();
+        assert!(signed.len() <= MAX_VAA_SIZE);
+
+        // The new guardian set account fits all keys
+        let mut bridge_data = vec![0u8; size_of::<Bridge>()];
+        let bridge: &mut Bridge = Bridge::unpack_unchecked(&mut bridge_data).unwrap();
+        let mut guardian_set_data = vec![0u8; size_of::<GuardianSet>()];
+        let old_guardian_set: &mut GuardianSet =
+            Bridge::unpack_unchecked(&mut guardian_set_data).unwrap();
+        let new_guardian_set = TestAccount::new(
+            Bridge::derive_guardian_set_id(&program_id, &bridge_key, 1).unwrap(),
+            0,
+            GuardianSet::size(MAX_LEN_GUARDIAN_KEYS),
+            program_id,
+        );
+        let mut fixture = ProcessorAccounts::new(&program_id, vec![new_guardian_set]);
+        // The bridge only subsidizes the new account from fees above its rent exempt balance
+        fixture.bridge.lamports = Rent::default().minimum_balance(size_of::<Bridge>());
+        {
+            let (accounts, bridge_info, payer_info) = fixture.infos();
+            let clock = Clock {
+                unix_timestamp: VAA_TIME as i64,
+                ..Clock::default()
+            };
+            let result = Bridge::process_vaa_set_update(
+                &program_id,
+                &accounts,
+                &mut accounts.iter(),
+                &clock,
+                &bridge_info,
+                &payer_info,
+                bridge,
+                old_guardian_set,
+                &body,
+            );
+            assert_eq!(result, Ok(()));
+        }
+        let (guardian_set, set_keys) =
+            Bridge::unpack_guardian_set(&mut fixture.accounts[0].data).unwrap();
+        assert_eq!(guardian_set.index, 1);
+        assert_eq!(set_keys.to_vec(), keys);
+        assert_eq!(bridge.guardian_set_index, 1);
+    }
+
+    fn message_account(program_id: &Pubkey, emitter: &Pubkey, payload: &[u8]) -> TestAccount {
+        let bridge_key = Bridge::derive_bridge_id(program_id).unwrap();
+        let key = Bridge::derive_message_id(program_id, &bridge_key, emitter, 5).unwrap();
+        let mut account = TestAccount::new(
+            key,
+            1000,
+            size_of::<PublishedMessage>() + payload.len(),
+            *program_id,
+        );
+        let (header, data) = account.data.split_at_mut(size_of::<PublishedMessage>());
+        let message: &mut PublishedMessage = Bridge::unpack_unchecked(header).unwrap();
+        message.is_initialized = true;
+        message.emitter = *emitter;
+        message.nonce = 5;
+        message.sequence = 3;
+        message.len = payload.len() as u32;
+        data.copy_from_slice(payload);
+        account
+    }
+
+    #[test]
+    fn message_vaa_post() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let bridge = Bridge::derive_bridge_id(&program_id).unwrap();
+        let emitter = Pubkey::new(&[2; 32]);
+        let posted_vaa = Pubkey::new(&[3; 32]);
+        let mut message_account = message_account(&program_id, &emitter, b"hello");
+
+        let mut vaa = VAA::new();
+        vaa.timestamp = VAA_TIME;
+        let mut body = BodyMessage {
+            emitter_chain: CHAIN_ID_SOLANA,
+            emitter_address: emitter.to_bytes(),
+            nonce: 5,
+            sequence: 3,
+            payload: b"hello".to_vec(),
+        };
+        let mut bridge_account = TestAccount::new(bridge, 0, 0, program_id);
+        let mut post = |body: &BodyMessage, message_account: &mut TestAccount| {
+            let accounts = vec![message_account.info(false)];
+            Bridge::process_vaa_message_post(
+                &program_id,
+                &mut accounts.iter(),
+                &bridge_account.info(false),
+                &vaa,
+                body,
+                &posted_vaa,
+            )
+        };
+
+        // The VAA must match the published message
+        body.payload = b"world".to_vec();
+        assert_eq!(
+            post(&body, &mut message_account),
+            Err(Error::VAAProposalMismatch.into())
+        );
+
+        body.payload = b"hello".to_vec();
+        assert_eq!(post(&body, &mut message_account), Ok(()));
+        assert_eq!(
+            post(&body, &mut message_account),
+            Err(Error::VAAAlreadySubmitted.into())
+        );
+
+        let (message, payload) = Bridge::unpack_message(&message_account.data).unwrap();
+        assert_eq!(message.posted_vaa, posted_vaa);
+        assert_eq!(message.vaa_time, VAA_TIME);
+        assert_eq!(payload, b"hello");
+    }
+
+    #[test]
+    fn transfer_with_payload_receiver() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let bridge_key = Bridge::derive_bridge_id(&program_id).unwrap();
+        let receiver = Pubkey::new(&[4; 32]);
+        let mint = Bridge::derive_wrapped_asset_id(&program_id, &bridge_key, 2, [6; 32]).unwrap();
+        let authority = Bridge::derive_payload_authority_id(&program_id, &bridge_key).unwrap();
+        let owner = Bridge::derive_receiver_id(&receiver, &bridge_key).unwrap();
+        let bridge = token_bridge();
+        let mut body = BodyTransferWithPayload {
+            transfer: transfer_in(receiver.to_bytes()),
+            payload: b"swap".to_vec(),
+        };
+
+        // The tokens are minted to the receiver, which is then invoked with the payload by the
+        // payload authority
+        let mut received =
+            ProcessorAccounts::transfer_with_payload(&program_id, mint, receiver, owner);
+        assert_eq!(
+            received.process_transfer_with_payload(&program_id, &bridge, &body),
+            Ok(())
+        );
+        let invoked = invoked();
+        assert_eq!(invoked.len(), 2);
+        match TokenInstruction::unpack(&invoked[0].data).unwrap() {
+            TokenInstruction::MintTo { amount } => assert_eq!(amount, 100),
+            _ => panic!("expected MintTo"),
+        }
+        assert_eq!(invoked[0].accounts[1].pubkey, Pubkey::new(&[7; 32]));
+        assert_eq!(invoked[1].program_id, receiver);
+        assert_eq!(invoked[1].data, b"swap".to_vec());
+        assert_eq!(
+            invoked[1].accounts,
+            vec![
+                AccountMeta::new_readonly(authority, true),
+                AccountMeta::new(Pubkey::new(&[7; 32]), false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(Pubkey::new(&[11; 32]), false),
+            ]
+        );
+
+        // The tokens must be owned by the receiver
+        let mut other = ProcessorAccounts::transfer_with_payload(
+            &program_id,
+            mint,
+            receiver,
+            Pubkey::new(&[9; 32]),
+        );
+        assert_eq!(
+            other.process_transfer_with_payload(&program_id, &bridge, &body),
+            Err(Error::InvalidReceiver.into())
+        );
+
+        // The receiver must be the target of the transfer
+        body.transfer.target_address = [9; 32];
+        assert_eq!(
+            received.process_transfer_with_payload(&program_id, &bridge, &body),
+            Err(Error::InvalidReceiver.into())
+        );
+    }
+
+    #[test]
+    fn transfer_to_wallet_recipient() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let bridge_key = Bridge::derive_bridge_id(&program_id).unwrap();
+        let wallet = Pubkey::new(&[4; 32]);
+        let mint = Bridge::derive_wrapped_asset_id(&program_id, &bridge_key, 2, [6; 32]).unwrap();
+        let associated =
+            Bridge::derive_associated_token_id(&wallet, &spl_token::id(), &mint).unwrap();
+        let bridge = token_bridge();
+        let mut body = transfer_in(wallet.to_bytes());
+
+        // The tokens are only delivered to the associated token account of the wallet
+        let mut other = ProcessorAccounts::transfer_to_wallet(
+            &program_id,
+            mint,
+            wallet,
+            token_account(Pubkey::new(&[9; 32]), mint, wallet),
+        );
+        assert_eq!(
+            other.process_transfer_to_wallet(&program_id, &bridge, &body),
+            Err(Error::InvalidRecipient.into())
+        );
+
+        // A missing associated token account is created, its rent is refunded to the payer out
+        // of the fees collected by the bridge
+        let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+        let mut missing = ProcessorAccounts::transfer_to_wallet(
+            &program_id,
+            mint,
+            wallet,
+            TestAccount::new(associated, 0, 0, Pubkey::default()),
+        );
+        missing.bridge.lamports = Bridge::min_bridge_balance() + rent;
+        assert_eq!(
+            missing.process_transfer_to_wallet(&program_id, &bridge, &body),
+            Ok(())
+        );
+        let invoked = invoked();
+        assert_eq!(invoked.len(), 2);
+        assert_eq!(invoked[0].program_id, associated_token_program::id());
+        assert_eq!(
+            invoked[0].accounts,
+            vec![
+                AccountMeta::new(missing.payer.key, true),
+                AccountMeta::new(associated, false),
+                AccountMeta::new_readonly(wallet, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+            ]
+        );
+        match TokenInstruction::unpack(&invoked[1].data) {
+            Ok(TokenInstruction::MintTo { amount }) => assert_eq!(amount, 100),
+            _ => panic!("expected a mint"),
+        }
+        assert_eq!(invoked[1].accounts[1].pubkey, associated);
+        assert_eq!(missing.payer.lamports, rent);
+        assert_eq!(missing.bridge.lamports, Bridge::min_bridge_balance());
+
+        // An existing associated token account is used as is
+        let mut existing = ProcessorAccounts::transfer_to_wallet(
+            &program_id,
+            mint,
+            wallet,
+            token_account(associated, mint, wallet),
+        );
+        assert_eq!(
+            existing.process_transfer_to_wallet(&program_id, &bridge, &body),
+            Ok(())
+        );
+        assert_eq!(invoked().len(), 1);
+        assert_eq!(invoked()[0].program_id, spl_token::id());
+
+        // The wallet must be the target of the transfer
+        body.target_address = [9; 32];
+        assert_eq!(
+            existing.process_transfer_to_wallet(&program_id, &bridge, &body),
+            Err(Error::InvalidRecipient.into())
+        );
+    }
+
+    #[test]
+    fn scale_amount_round_trip() {
+        // An 18 decimal token is minted with 8 decimals, the dust is not representable
+        let amount = U256::from(1_234_567_891_234_567_891u64);
+        let (wrapped, dust) = Bridge::scale_amount(amount, 18, MAX_WRAPPED_DECIMALS).unwrap();
+        assert_eq!(wrapped, U256::from(123_456_789u64));
+        assert_eq!(dust, U256::from(1_234_567_891u64));
+        let (sent, dust) = Bridge::scale_amount(wrapped, MAX_WRAPPED_DECIMALS, 18).unwrap();
+        assert_eq!(dust, U256::zero());
+        assert_eq!(sent + U256::from(1_234_567_891u64), amount);
+
+        // A lockup of a 9 decimal token keeps the dust with the sender, the locked amount
+        // is restored exactly when it is transferred back in
+        let amount = U256::from(5_000_000_123u64);
+        let (sent, dust) = Bridge::scale_amount(amount, 9, MAX_WRAPPED_DECIMALS).unwrap();
+        assert_eq!(sent, U256::from(500_000_012u64));
+        assert_eq!(dust, U256::from(3));
+        assert_eq!(
+            Bridge::scale_amount(sent, MAX_WRAPPED_DECIMALS, 9),
+            Ok((amount - dust, U256::zero()))
+        );
+
+        // Tokens with at most 8 decimals are not scaled
+        assert_eq!(
+            Bridge::scale_amount(amount, 6, 6),
+            Ok((amount, U256::zero()))
+        );
+
+        // Scaling up must not overflow
+        assert_eq!(
+            Bridge::scale_amount(U256::max_value(), 8, 9),
+            Err(Error::AmountOverflow)
+        );
+        assert_eq!(
+            Bridge::scale_amount(U256::one(), 0, 255),
+            Err(Error::AmountOverflow)
+        );
+        assert_eq!(
+            Bridge::scale_amount(U256::one(), 255, 0),
+            Err(Error::AmountOverflow)
+        );
+    }
+
+    #[test]
+    fn token_amount_boundaries() {
+        assert_eq!(Bridge::token_amount(U256::zero()), Err(Error::ZeroAmount));
+        assert_eq!(Bridge::token_amount(U256::one()), Ok(1));
+        assert_eq!(Bridge::token_amount(U256::from(u64::MAX)), Ok(u64::MAX));
+        assert_eq!(
+            Bridge::token_amount(U256::from(u64::MAX) + 1),
+            Err(Error::AmountTooLarge)
+        );
+        assert_eq!(
+            Bridge::token_amount(U256::max_value()),
+            Err(Error::AmountTooLarge)
+        );
+    }
+
+    #[test]
+    fn transfer_in_amount_boundaries() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let bridge_key = Bridge::derive_bridge_id(&program_id).unwrap();
+        let mint = Bridge::derive_wrapped_asset_id(&program_id, &bridge_key, 2, [6; 32]).unwrap();
+        let bridge = token_bridge();
+        let mut body = transfer_in([4; 32]);
+        body.amount = U256::from(u64::MAX) + 1;
+        let destination = token_account(Pubkey::new(&[7; 32]), mint, Pubkey::new(&[4; 32]));
+        let mut fixture = ProcessorAccounts::new(
+            &program_id,
+            transfer_accounts(&program_id, mint, destination),
+        );
+
+        // Amounts that do not fit into a token amount are rejected instead of being truncated
+        assert_eq!(
+            fixture.process_transfer(&program_id, &bridge, &body),
+            Err(Error::AmountTooLarge.into())
+        );
+        body.amount = U256::max_value();
+        assert_eq!(
+            fixture.process_transfer(&program_id, &bridge, &body),
+            Err(Error::AmountTooLarge.into())
+        );
+
+        // This also applies to amounts that only exceed it once they are scaled to the mint
+        body.amount = U256::from(u64::MAX);
+        body.asset.decimals = 7;
+        assert_eq!(
+            fixture.process_transfer(&program_id, &bridge, &body),
+            Err(Error::AmountTooLarge.into())
+        );
+
+        body.amount = U256::zero();
+        body.asset.decimals = 8;
+        assert_eq!(
+            fixture.process_transfer(&program_id, &bridge, &body),
+            Err(Error::ZeroAmount.into())
+        );
+
+        // Dust that the mint cannot represent is no transfer either
+        body.amount = U256::from(9);
+        body.asset.decimals = 9;
+        assert_eq!(
+            fixture.process_transfer(&program_id, &bridge, &body),
+            Err(Error::ZeroAmount.into())
+        );
+
+        // Amounts are scaled to the 8 decimals of the wrapped mint, the dust is not minted
+        body.amount = U256::from(1_234_567_891_234_567_891u64);
+        body.asset.decimals = 18;
+        assert_eq!(
+            fixture.process_transfer(&program_id, &bridge, &body),
+            Ok(())
+        );
+        match TokenInstruction::unpack(&invoked()[0].data) {
+            Ok(TokenInstruction::MintTo { amount }) => assert_eq!(amount, 123_456_789),
+            _ => panic!("expected a mint"),
+        }
+    }
+
+    #[test]
+    fn asset_metadata_wrapped_mint() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let bridge_key = Bridge::derive_bridge_id(&program_id).unwrap();
+        let mint = Bridge::derive_wrapped_asset_id(&program_id, &bridge_key, 2, [6; 32]).unwrap();
+        let mut bridge = token_bridge();
+        let body = BodyAssetMetadata {
+            asset: AssetMeta {
+                address: [6; 32],
+                chain: 2,
+                decimals: 18,
+            },
+            symbol: [0; 32],
+            name: [0; 32],
+        };
+
+        // There is a single wrapped mint per origin token, derived without the decimals
+        let mut other = ProcessorAccounts::asset_metadata(&program_id, Pubkey::new(&[5; 32]));
+        assert_eq!(
+            other.process_asset_metadata(&program_id, &bridge, &body),
+            Err(Error::InvalidDerivedAccount.into())
+        );
+
+        // The mint is created with at most 8 decimals, the meta keeps those of the origin token
+        let mut created = ProcessorAccounts::asset_metadata(&program_id, mint);
+        assert_eq!(
+            created.process_asset_metadata(&program_id, &bridge, &body),
+            Ok(())
+        );
+        let initialize_mint = invoked()
+            .into_iter()
+            .find(|ix| ix.program_id == spl_token::id())
+            .unwrap();
+        match TokenInstruction::unpack(&initialize_mint.data) {
+            Ok(TokenInstruction::InitializeMint {
+                decimals,
+                mint_authority,
+                ..
+            }) => {
+                assert_eq!(decimals, MAX_WRAPPED_DECIMALS);
+                assert_eq!(mint_authority, bridge_key);
+            }
+            _ => panic!("expected a mint initialization"),
+        }
+        assert_eq!(initialize_mint.accounts[0].pubkey, mint);
+        let meta: &mut WrappedAssetMeta = Bridge::unpack(&mut created.accounts[2].data).unwrap();
+        assert_eq!(meta.chain, 2);
+        assert_eq!(meta.address, [6; 32]);
+        assert_eq!(meta.decimals, 18);
+
+        bridge.paused_inbound = true;
+        let mut paused = ProcessorAccounts::asset_metadata(&program_id, mint);
+        assert_eq!(
+            paused.process_asset_metadata(&program_id, &bridge, &body),
+            Err(Error::BridgePaused.into())
+        );
+
+        // Wrapped assets can no longer be created from caller supplied metadata
+        assert_eq!(
+            BridgeInstruction::deserialize(&[7; 43]).err(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+    }
+
+    #[test]
+    fn refund_transfer_out() {
+        let program_id = Pubkey::new(&[1; 32]);
+        let bridge_key = Bridge::derive_bridge_id(&program_id).unwrap();
+        let source = Pubkey::new(&[4; 32]);
+        let mint = Bridge::derive_wrapped_asset_id(&program_id, &bridge_key, 2, [2; 32]).unwrap();
+        let mut bridge = token_bridge();
+        bridge.config.vaa_expiration_time = VAA_EXPIRATION_TIME;
+        let vaa = VAA {
+            timestamp: VAA_TIME,
+            ..VAA::default()
+        };
+        let clock = Clock {
+            unix_timestamp: VAA_TIME as i64 + VAA_EXPIRATION_TIME as i64 + 1,
+            ..Clock::default()
+        };
+
+        // Matches the proposal created by `proposal_account` for a transfer of 100 tokens
+        let body = BodyTransfer {
+            nonce: 5,
+            source_chain: CHAIN_ID_SOLANA,
+            target_chain: 2,
+            source_address: [4; 32],
+            target_address: [3; 32],
+            asset: AssetMeta {
+                address: [2; 32],
+                chain: 2,
+                decimals: 8,
+            },
+            amount: U256::from(100),
+            fee: U256::zero(),
+        };
+
+        // Transfers that already have a VAA cannot be refunded
+        let mut posted =
+            ProcessorAccounts::refund(&program_id, VAA_TIME, body.amount, mint, source, source);
+        assert_eq!(
+            posted.process_refund(&program_id, &clock, &bridge, &vaa, &body),
+            Err(Error::VAAAlreadySubmitted.into())
+        );
+
+        // A transfer VAA that was signed but not posted back, e.g. because it was rejected on the
+        // target chain, can still be redeemed there until it expires
+        let mut signed =
+            ProcessorAccounts::refund(&program_id, 0, body.amount, mint, source, source);
+        let unexpired = Clock {
+            unix_timestamp: VAA_TIME as i64 + VAA_EXPIRATION_TIME as i64,
+            ..Clock::default()
+        };
+        assert_eq!(
+            signed.process_refund(&program_id, &unexpired, &bridge, &vaa, &body),
+            Err(Error::VAANotExpired.into())
+        );
+        assert!(invoked().is_empty());
+        let proposal: &mut TransferOutProposal =
+            Bridge::unpack(&mut signed.accounts[0].data).unwrap();
+        assert!(!proposal.refunded);
+
+        // The tokens can only be returned to the source of the transfer
+        let mut other = ProcessorAccounts::refund(
+            &program_id,
+            0,
+            body.amount,
+            mint,
+            source,
+            Pubkey::new(&[7; 32]),
+        );
+        assert_eq!(
+            other.process_refund(&program_id, &clock, &bridge, &vaa, &body),
+            Err(Error::InvalidDerivedAccount.into())
+        );
+
+        // The burned wrapped tokens are minted again to the source, the proposal expires with
+        // the refund VAA
+        let mut refunded =
+            ProcessorAccounts::refund(&program_id, 0, body.amount, mint, source, source);
+        assert_eq!(
+            refunded.process_refund(&program_id, &clock, &bridge, &vaa, &body),
+            Ok(())
+        );
+        let invoked = invoked();
+        assert_eq!(invoked.len(), 1);
+        match TokenInstruction::unpack(&invoked[0].data) {
+            Ok(TokenInstruction::MintTo { amount }) => assert_eq!(amount, 100),
+            _ => panic!("expected a mint"),
+        }
+        assert_eq!(invoked[0].accounts[1].pubkey, source);
+        let proposal: &mut TransferOutProposal =
+            Bridge::unpack(&mut refunded.accounts[0].data).unwrap();
+        assert!(proposal.refunded);
+        assert_eq!(proposal.vaa_time, VAA_TIME);
+
+        // Refunded transfers can neither be refunded again nor completed
+        assert_eq!(
+            refunded.process_refund(&program_id, &clock, &bridge, &vaa, &body),
+            Err(Error::TransferRefunded.into())
+        );
+        assert_eq!(
+            refunded.process_transfer_post(&program_id, &body),
+            Err(Error::TransferRefunded.into())
+        );
+
+        // The relayer fee of a proposal must be signed with the transfer, but refunds return the
+        // whole amount without it
+        let mut with_fee =
+            ProcessorAccounts::refund(&program_id, 0, body.amount, mint, source, source);
+        let locked: &mut TransferOutProposal =
+            Bridge::unpack(&mut with_fee.accounts[0].data).unwrap();
+        locked.fee = U256::from(10);
+        assert_eq!(
+            with_fee.process_transfer_post(&program_id, &body),
+            Err(Error::VAAProposalMismatch.into())
+        );
+        assert_eq!(
+            with_fee.process_refund(&program_id, &clock, &bridge, &vaa, &body),
+            Ok(())
+        );
+
+        // Refunded proposals are evicted once the refund VAA has expired
+        let mut guardian = TestAccount::new(Pubkey::new(&[7; 32]), 10, 0, Pubkey::default());
+        let mut bridge_state = bridge_account(&program_id);
+        let proposal = &mut refunded.accounts[0];
+        let ix = evict_transfer_out(&program_id, &guardian.key, &proposal.key).unwrap();
+        let now = VAA_TIME as i64 + VAA_EXPIRATION_TIME as i64;
+        let result = evict(
+            &program_id,
+            &ix,
+            &mut [&mut *proposal],
+            &mut guardian,
+            &mut bridge_state,
+            now,
+        );
+        assert_eq!(result, Err(Error::VAANotExpired.into()));
+        let result = evict(
+            &program_id,
+            &ix,
+            &mut [&mut *proposal],
+            &mut guardian,
+            &mut bridge_state,
+            now + 1,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(proposal.lamports, 0);
+        assert_eq!(guardian.lamports, 1010);
+
+        bridge.paused_inbound = true;
+        let mut paused =
+            ProcessorAccounts::refund(&program_id, 0, body.amount, mint, source, source);
+        assert_eq!(
+            paused.process_refund(&program_id, &clock, &bridge, &vaa, &body),
+            Err(Error::BridgePaused.into())
+        );
+    }
+}
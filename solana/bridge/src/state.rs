@@ -3,16 +3,31 @@
 use std::mem::size_of;
 
 use primitive_types::U256;
-use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use solana_program::{
+    account_info::AccountInfo, native_token::LAMPORTS_PER_SOL, program_error::ProgramError,
+    pubkey::Pubkey,
+};
 use zerocopy::AsBytes;
 
-use crate::{
-    error::Error,
-    instruction::{ForeignAddress, MAX_LEN_GUARDIAN_KEYS, MAX_VAA_SIZE},
-    vaa::BodyTransfer,
-};
+use crate::{error::Error, instruction::ForeignAddress, vaa::BodyTransfer};
 use solana_program::program_pack::Pack;
-use solana_program::rent::Rent;
+
+/// Metadata about an asset, shared with the VAA encoding
+pub use wormhole_vaa::AssetMeta;
+
+/// Program that creates the associated token accounts of wallets
+pub mod associated_token_program {
+    declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+}
+
+/// number of slots counted in one bucket of the fee state
+pub const FEE_BUCKET_SLOTS: u64 = 5;
+/// number of buckets of the fee state, covering the 30 second window (75 slots of 400ms)
+pub const FEE_BUCKETS: usize = 15;
+/// transfers out per second at which a transfer costs 1 SOL
+pub const FEE_MAX_TPS: u64 = 10;
+/// length of the window in which transfers out are counted in seconds
+const FEE_WINDOW_SECONDS: u64 = 30;
 
 /// fee rate as a ratio
 #[repr(C)]
@@ -24,7 +39,7 @@ pub struct Fee {
     pub numerator: u64,
 }
 
-/// guardian set
+/// guardian set, the `len_keys` addresses of the guardians follow it in the account data
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct GuardianSet {
@@ -32,8 +47,8 @@ pub struct GuardianSet {
     pub index: u32,
     /// number of keys stored
     pub len_keys: u8,
-    /// public key of the threshold schnorr set
-    pub keys: [[u8; 20]; MAX_LEN_GUARDIAN_KEYS],
+    /// number of signatures required for a VAA of this set to be valid
+    pub threshold: u8,
     /// creation time
     pub creation_time: u32,
     /// expiration time when VAAs issued by this set are no longer valid
@@ -49,11 +64,25 @@ impl IsInitialized for GuardianSet {
     }
 }
 
+impl GuardianSet {
+    /// Size of a guardian set account with `len_keys` guardians
+    pub fn size(len_keys: usize) -> usize {
+        size_of::<GuardianSet>() + len_keys * size_of::<GuardianAddress>()
+    }
+}
+
+/// address of a guardian
+pub type GuardianAddress = [u8; 20];
+/// signature of a guardian (r, s, v)
+pub type GuardianSignature = [u8; 65];
+
 /// proposal to transfer tokens to a foreign chain
 #[repr(C)]
 pub struct TransferOutProposal {
     /// amount to transfer
     pub amount: U256,
+    /// part of `amount` paid to the relayer of the VAA on the target chain
+    pub fee: U256,
     /// chain id to transfer to
     pub to_chain_id: u8,
     /// address the transfer was initiated from
@@ -64,10 +93,11 @@ pub struct TransferOutProposal {
     pub asset: AssetMeta,
     /// nonce of the transfer
     pub nonce: u32,
-    /// vaa to unlock the tokens on the foreign chain
-    /// it is +1 byte long to make space for the termination byte
-    pub vaa: [u8; MAX_VAA_SIZE + 1],
-    /// time the vaa was submitted
+    /// sequence number assigned to the lockup by the bridge
+    pub sequence: u64,
+    /// PostedVAA account holding the vaa to unlock the tokens on the foreign chain
+    pub posted_vaa: Pubkey,
+    /// time the vaa was submitted, or the time of the refund vaa if the transfer was refunded
     pub vaa_time: u32,
     /// time the lockup was created
     pub lockup_time: u32,
@@ -75,6 +105,8 @@ pub struct TransferOutProposal {
     pub poke_counter: u8,
     /// Account where signatures are stored
     pub signature_account: Pubkey,
+    /// Is `true` if the tokens were refunded to `source_address`
+    pub refunded: bool,
 
     /// Is `true` if this structure has been initialized.
     pub is_initialized: bool,
@@ -88,10 +120,68 @@ impl IsInitialized for TransferOutProposal {
 
 impl TransferOutProposal {
     pub fn matches_vaa(&self, b: &BodyTransfer) -> bool {
+        return self.matches_refund(b) && b.fee == self.fee;
+    }
+
+    /// Refunds return the whole amount to the sender, they never carry the relayer fee
+    pub fn matches_refund(&self, b: &BodyTransfer) -> bool {
         return b.amount == self.amount
             && b.target_address == self.foreign_address
             && b.target_chain == self.to_chain_id
-            && b.asset == self.asset;
+            && b.asset == self.asset
+            && b.fee.is_zero();
+    }
+}
+
+/// header of a posted VAA, the signed VAA of `len` bytes follows it in the account data
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PostedVAA {
+    /// hash of the vaa
+    pub hash: [u8; 32],
+    /// timestamp of the vaa
+    pub vaa_time: u32,
+    /// length of the signed vaa
+    pub len: u32,
+
+    /// Is `true` if this structure has been initialized.
+    pub is_initialized: bool,
+}
+
+impl IsInitialized for PostedVAA {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+/// header of a message published on Solana, the payload of `len` bytes follows it in the account
+/// data
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PublishedMessage {
+    /// emitter of the message
+    pub emitter: Pubkey,
+    /// nonce of the message
+    pub nonce: u32,
+    /// sequence number assigned to the message by the bridge, shared with lockups and the
+    /// messages of other emitters
+    pub sequence: u64,
+    /// time the message was published
+    pub submission_time: u32,
+    /// PostedVAA account holding the signed message
+    pub posted_vaa: Pubkey,
+    /// time the vaa was submitted
+    pub vaa_time: u32,
+    /// length of the payload
+    pub len: u32,
+
+    /// Is `true` if this structure has been initialized.
+    pub is_initialized: bool,
+}
+
+impl IsInitialized for PublishedMessage {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
     }
 }
 
@@ -101,7 +191,7 @@ impl TransferOutProposal {
 pub struct ClaimedVAA {
     /// hash of the vaa
     pub hash: [u8; 32],
-    /// time the vaa was submitted
+    /// timestamp of the vaa, used to determine when the claim can be evicted
     pub vaa_time: u32,
 
     /// Is `true` if this structure has been initialized.
@@ -122,6 +212,12 @@ pub struct WrappedAssetMeta {
     pub chain: u8,
     /// address of the asset on the native chain
     pub address: ForeignAddress,
+    /// decimals of the asset on the native chain, as attested by the guardians
+    pub decimals: u8,
+    /// symbol of the asset, UTF-8 and right zero-padded
+    pub symbol: [u8; 32],
+    /// name of the asset, UTF-8 and right zero-padded
+    pub name: [u8; 32],
 
     /// Is `true` if this structure has been initialized.
     pub is_initialized: bool,
@@ -133,18 +229,57 @@ impl IsInitialized for WrappedAssetMeta {
     }
 }
 
-/// Metadata about an asset
+/// Recent transfers out of Solana, used to calculate the dynamic transfer fee
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
-pub struct AssetMeta {
-    /// Address of the token
-    pub address: ForeignAddress,
+pub struct FeeState {
+    /// bucket (slot / FEE_BUCKET_SLOTS) in which each count was recorded
+    pub buckets: [u64; FEE_BUCKETS],
+    /// number of transfers out in each bucket
+    pub counts: [u32; FEE_BUCKETS],
 
-    /// Chain of the token
-    pub chain: u8,
+    /// Is `true` if this structure has been initialized.
+    pub is_initialized: bool,
+}
 
-    /// Number of decimals of the token
-    pub decimals: u8,
+impl IsInitialized for FeeState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl FeeState {
+    /// Number of transfers out in the window that ends at `slot`
+    pub fn transfers_in_window(&self, slot: u64) -> u64 {
+        let current = slot / FEE_BUCKET_SLOTS;
+        self.buckets
+            .iter()
+            .zip(self.counts.iter())
+            .filter(|(bucket, _)| **bucket <= current && **bucket + FEE_BUCKETS as u64 > current)
+            .map(|(_, count)| *count as u64)
+            .sum()
+    }
+
+    /// Records a transfer out at `slot`
+    pub fn record_transfer(&mut self, slot: u64) {
+        let bucket = slot / FEE_BUCKET_SLOTS;
+        let i = (bucket % FEE_BUCKETS as u64) as usize;
+        if self.buckets[i] != bucket {
+            self.buckets[i] = bucket;
+            self.counts[i] = 0;
+        }
+        self.counts[i] = self.counts[i].saturating_add(1);
+    }
+
+    /// Fee of a transfer out at `slot`: `(tps/tps_max)^6` SOL but at least `min_fee`
+    pub fn transfer_fee(&self, slot: u64, min_fee: u64) -> u64 {
+        let max_transfers = (FEE_MAX_TPS * FEE_WINDOW_SECONDS) as u128;
+        // Capped to prevent overflows, the fee is 10^6 SOL at this point
+        let transfers = (self.transfers_in_window(slot) as u128).min(max_transfers * 10);
+
+        let fee = LAMPORTS_PER_SOL as u128 * transfers.pow(6) / max_transfers.pow(6);
+        (fee as u64).max(min_fee)
+    }
 }
 
 /// Config for a bridge.
@@ -158,6 +293,16 @@ pub struct BridgeConfig {
 
     /// Token program that is used for this bridge
     pub token_program: Pubkey,
+
+    /// Lamports that need to be paid to the bridge for a transfer out
+    pub transfer_fee: u64,
+
+    /// Lamports refunded from the collected fees to the submitter of a VAA to cover the tx fee
+    pub vaa_tx_refund: u64,
+
+    /// Period after which a VAA is no longer accepted and its ClaimedVAA / TransferOutProposal
+    /// can be evicted to reclaim rent
+    pub vaa_expiration_time: u32,
 }
 
 /// Bridge state.
@@ -167,9 +312,27 @@ pub struct Bridge {
     /// the currently active guardian set
     pub guardian_set_index: u32,
 
-    /// read-only config parameters for a bridge instance.
+    /// config parameters for a bridge instance, updated by governance VAAs.
     pub config: BridgeConfig,
 
+    /// sequence number of the next lockup or message, incremented on every transfer out and
+    /// published message
+    pub sequence: u64,
+
+    /// timestamp of the newest VAA whose claim was evicted. VAAs up to this time are rejected
+    /// even if governance extends the `vaa_expiration_time`, so they cannot be replayed.
+    pub evicted_vaa_time: u32,
+
+    /// timestamp of the last applied governance VAA. Governance VAAs do not expire, older ones are
+    /// rejected so they cannot be replayed once their claims are evicted.
+    pub governance_vaa_time: u32,
+
+    /// Is `true` if transfers into Solana are paused by governance
+    pub paused_inbound: bool,
+
+    /// Is `true` if transfers out of Solana are paused by governance
+    pub paused_outbound: bool,
+
     /// Is `true` if this structure has been initialized.
     pub is_initialized: bool,
 }
@@ -180,13 +343,11 @@ impl IsInitialized for Bridge {
     }
 }
 
-/// Signature state
+/// Signature state, a signature slot for every guardian of the set follows it in the account
+/// data. Slots of guardians that did not sign are zero.
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct SignatureState {
-    /// signatures of validators
-    pub signatures: [[u8; 65]; MAX_LEN_GUARDIAN_KEYS],
-
     /// hash of the data
     pub hash: [u8; 32],
 
@@ -203,6 +364,13 @@ impl IsInitialized for SignatureState {
     }
 }
 
+impl SignatureState {
+    /// Size of a signature state account for a guardian set with `len_keys` guardians
+    pub fn size(len_keys: usize) -> usize {
+        size_of::<SignatureState>() + len_keys * size_of::<GuardianSignature>()
+    }
+}
+
 /// Implementation of serialization functions
 impl Bridge {
     /// Deserializes a spl_token `Account`.
@@ -254,6 +422,121 @@ impl Bridge {
         #[allow(clippy::cast_ptr_alignment)]
             Ok(unsafe { &*(&input[0] as *const u8 as *const T) })
     }
+
+    /// Unpacks a message account into its header and the payload.
+    pub fn unpack_message(input: &[u8]) -> Result<(&PublishedMessage, &[u8]), ProgramError> {
+        if input.len() < size_of::<PublishedMessage>() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (header, payload) = input.split_at(size_of::<PublishedMessage>());
+        let message: &PublishedMessage = Self::unpack_immutable(header)?;
+        if payload.len() != message.len as usize {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok((message, payload))
+    }
+
+    /// Unpacks a PostedVAA account into its header and the signed VAA.
+    pub fn unpack_posted_vaa(input: &[u8]) -> Result<(&PostedVAA, &[u8]), ProgramError> {
+        if input.len() < size_of::<PostedVAA>() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (header, vaa) = input.split_at(size_of::<PostedVAA>());
+        let posted_vaa: &PostedVAA = Self::unpack_immutable(header)?;
+        if vaa.len() != posted_vaa.len as usize {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok((posted_vaa, vaa))
+    }
+
+    /// Unpacks a guardian set account into its header and the guardian addresses without checking
+    /// that the state is initialized.
+    pub fn unpack_guardian_set_unchecked(
+        input: &mut [u8],
+    ) -> Result<(&mut GuardianSet, &mut [GuardianAddress]), ProgramError> {
+        let (header, keys) = Self::split_items::<GuardianSet>(input, size_of::<GuardianAddress>())?;
+        let guardian_set: &mut GuardianSet = Self::unpack_unchecked(header)?;
+        let len = keys.len() / size_of::<GuardianAddress>();
+        #[allow(clippy::cast_ptr_alignment)]
+        let keys = unsafe {
+            std::slice::from_raw_parts_mut(keys.as_mut_ptr() as *mut GuardianAddress, len)
+        };
+        Ok((guardian_set, keys))
+    }
+
+    /// Unpacks a guardian set account into its header and the guardian addresses.
+    pub fn unpack_guardian_set(
+        input: &mut [u8],
+    ) -> Result<(&mut GuardianSet, &mut [GuardianAddress]), ProgramError> {
+        let (guardian_set, keys) = Self::unpack_guardian_set_unchecked(input)?;
+        if !guardian_set.is_initialized() {
+            return Err(Error::UninitializedState.into());
+        }
+        if keys.len() != guardian_set.len_keys as usize {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok((guardian_set, keys))
+    }
+
+    /// Unpacks a guardian set account into its header and the guardian addresses.
+    pub fn unpack_guardian_set_immutable(
+        input: &[u8],
+    ) -> Result<(&GuardianSet, &[GuardianAddress]), ProgramError> {
+        if input.len() < size_of::<GuardianSet>() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (header, keys) = input.split_at(size_of::<GuardianSet>());
+        let guardian_set: &GuardianSet = Self::unpack_immutable(header)?;
+        if keys.len() != guardian_set.len_keys as usize * size_of::<GuardianAddress>() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        #[allow(clippy::cast_ptr_alignment)]
+        let keys = unsafe {
+            std::slice::from_raw_parts(
+                keys.as_ptr() as *const GuardianAddress,
+                guardian_set.len_keys as usize,
+            )
+        };
+        Ok((guardian_set, keys))
+    }
+
+    /// Unpacks a signature state account into its header and the signature slots without checking
+    /// that the state is initialized.
+    pub fn unpack_signature_state_unchecked(
+        input: &mut [u8],
+    ) -> Result<(&mut SignatureState, &mut [GuardianSignature]), ProgramError> {
+        let (header, signatures) =
+            Self::split_items::<SignatureState>(input, size_of::<GuardianSignature>())?;
+        let sig_state: &mut SignatureState = Self::unpack_unchecked(header)?;
+        let len = signatures.len() / size_of::<GuardianSignature>();
+        #[allow(clippy::cast_ptr_alignment)]
+        let signatures = unsafe {
+            std::slice::from_raw_parts_mut(signatures.as_mut_ptr() as *mut GuardianSignature, len)
+        };
+        Ok((sig_state, signatures))
+    }
+
+    /// Unpacks a signature state account into its header and the signature slots.
+    pub fn unpack_signature_state(
+        input: &mut [u8],
+    ) -> Result<(&mut SignatureState, &mut [GuardianSignature]), ProgramError> {
+        let (sig_state, signatures) = Self::unpack_signature_state_unchecked(input)?;
+        if !sig_state.is_initialized() {
+            return Err(Error::UninitializedState.into());
+        }
+        Ok((sig_state, signatures))
+    }
+
+    /// Splits an account into the header `T` and the items of `item_size` bytes that follow it.
+    fn split_items<T>(
+        input: &mut [u8],
+        item_size: usize,
+    ) -> Result<(&mut [u8], &mut [u8]), ProgramError> {
+        if input.len() < size_of::<T>() || (input.len() - size_of::<T>()) % item_size != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(input.split_at_mut(size_of::<T>()))
+    }
 }
 
 /// Implementation of derivations
@@ -267,18 +550,17 @@ impl Bridge {
         ]
     }
 
-    /// Calculates derived seeds for a wrapped asset
+    /// Calculates derived seeds for a wrapped asset. There is a single wrapped asset per origin
+    /// token, so the decimals are not part of the seeds.
     pub fn derive_wrapped_asset_seeds(
         bridge_key: &Pubkey,
         asset_chain: u8,
-        asset_decimal: u8,
         asset: ForeignAddress,
     ) -> Vec<Vec<u8>> {
         vec![
             "wrapped".as_bytes().to_vec(),
             bridge_key.to_bytes().to_vec(),
             asset_chain.as_bytes().to_vec(),
-            asset_decimal.as_bytes().to_vec(),
             asset.as_bytes().to_vec(),
         ]
     }
@@ -319,13 +601,62 @@ impl Bridge {
         ]
     }
 
-    /// Calculates derived seeds for a claim
-    pub fn derive_claim_seeds<'a>(bridge: &Pubkey, body: Vec<u8>) -> Vec<Vec<u8>> {
-        [
-            vec!["claim".as_bytes().to_vec(), bridge.to_bytes().to_vec()],
-            body.chunks(32).map(|v| v.to_vec()).collect(),
+    /// Calculates derived seeds for a claim from the body hash of the VAA
+    pub fn derive_claim_seeds<'a>(bridge: &Pubkey, hash: &[u8; 32]) -> Vec<Vec<u8>> {
+        vec![
+            "claim".as_bytes().to_vec(),
+            bridge.to_bytes().to_vec(),
+            hash.to_vec(),
+        ]
+    }
+
+    /// Calculates derived seeds for a posted VAA from the body hash of the VAA
+    pub fn derive_posted_vaa_seeds(bridge: &Pubkey, hash: &[u8; 32]) -> Vec<Vec<u8>> {
+        vec![
+            "vaa".as_bytes().to_vec(),
+            bridge.to_bytes().to_vec(),
+            hash.to_vec(),
+        ]
+    }
+
+    /// Calculates derived seeds for a message
+    pub fn derive_message_seeds(bridge: &Pubkey, emitter: &Pubkey, nonce: u32) -> Vec<Vec<u8>> {
+        vec![
+            "message".as_bytes().to_vec(),
+            bridge.to_bytes().to_vec(),
+            emitter.to_bytes().to_vec(),
+            nonce.as_bytes().to_vec(),
+        ]
+    }
+
+    /// Calculates derived seeds for the authority that signs invocations of payload receivers
+    pub fn derive_payload_authority_seeds(bridge: &Pubkey) -> Vec<Vec<u8>> {
+        vec!["payload".as_bytes().to_vec(), bridge.to_bytes().to_vec()]
+    }
+
+    /// Calculates derived seeds for the token owner of a payload receiver, derived by the
+    /// receiving program
+    pub fn derive_receiver_seeds(bridge: &Pubkey) -> Vec<Vec<u8>> {
+        vec!["receiver".as_bytes().to_vec(), bridge.to_bytes().to_vec()]
+    }
+
+    /// Calculates derived seeds for the associated token account of `wallet`, derived by the
+    /// associated token program
+    pub fn derive_associated_token_seeds(
+        wallet: &Pubkey,
+        token_program: &Pubkey,
+        mint: &Pubkey,
+    ) -> Vec<Vec<u8>> {
+        vec![
+            wallet.to_bytes().to_vec(),
+            token_program.to_bytes().to_vec(),
+            mint.to_bytes().to_vec(),
         ]
-            .concat()
+    }
+
+    /// Calculates derived seeds for the fee state
+    pub fn derive_fee_state_seeds(bridge: &Pubkey) -> Vec<Vec<u8>> {
+        vec!["fee".as_bytes().to_vec(), bridge.to_bytes().to_vec()]
     }
 
     /// Calculates derived seeds for a wrapped asset meta entry
@@ -369,9 +700,63 @@ impl Bridge {
     pub fn derive_claim_id(
         program_id: &Pubkey,
         bridge: &Pubkey,
-        body: Vec<u8>,
+        hash: &[u8; 32],
+    ) -> Result<Pubkey, Error> {
+        Ok(Self::derive_key(program_id, &Self::derive_claim_seeds(bridge, hash))?.0)
+    }
+
+    /// Calculates a derived address for a posted VAA
+    pub fn derive_posted_vaa_id(
+        program_id: &Pubkey,
+        bridge: &Pubkey,
+        hash: &[u8; 32],
+    ) -> Result<Pubkey, Error> {
+        Ok(Self::derive_key(program_id, &Self::derive_posted_vaa_seeds(bridge, hash))?.0)
+    }
+
+    /// Calculates a derived address for a message
+    pub fn derive_message_id(
+        program_id: &Pubkey,
+        bridge: &Pubkey,
+        emitter: &Pubkey,
+        nonce: u32,
+    ) -> Result<Pubkey, Error> {
+        Ok(Self::derive_key(
+            program_id,
+            &Self::derive_message_seeds(bridge, emitter, nonce),
+        )?
+        .0)
+    }
+
+    /// Calculates a derived address for the authority that signs invocations of payload receivers
+    pub fn derive_payload_authority_id(
+        program_id: &Pubkey,
+        bridge: &Pubkey,
     ) -> Result<Pubkey, Error> {
-        Ok(Self::derive_key(program_id, &Self::derive_claim_seeds(bridge, body))?.0)
+        Ok(Self::derive_key(program_id, &Self::derive_payload_authority_seeds(bridge))?.0)
+    }
+
+    /// Calculates the address that has to own the tokens delivered to `receiver_program`
+    pub fn derive_receiver_id(receiver_program: &Pubkey, bridge: &Pubkey) -> Result<Pubkey, Error> {
+        Ok(Self::derive_key(receiver_program, &Self::derive_receiver_seeds(bridge))?.0)
+    }
+
+    /// Calculates the address of the associated token account of `wallet` for `mint`
+    pub fn derive_associated_token_id(
+        wallet: &Pubkey,
+        token_program: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Pubkey, Error> {
+        Ok(Self::derive_key(
+            &associated_token_program::id(),
+            &Self::derive_associated_token_seeds(wallet, token_program, mint),
+        )?
+        .0)
+    }
+
+    /// Calculates a derived address for the fee state
+    pub fn derive_fee_state_id(program_id: &Pubkey, bridge: &Pubkey) -> Result<Pubkey, Error> {
+        Ok(Self::derive_key(program_id, &Self::derive_fee_state_seeds(bridge))?.0)
     }
 
     /// Calculates a derived address for a wrapped asset meta entry
@@ -401,12 +786,11 @@ impl Bridge {
         program_id: &Pubkey,
         bridge_key: &Pubkey,
         asset_chain: u8,
-        asset_decimal: u8,
         asset: ForeignAddress,
     ) -> Result<Pubkey, Error> {
         Ok(Self::derive_key(
             program_id,
-            &Self::derive_wrapped_asset_seeds(bridge_key, asset_chain, asset_decimal, asset),
+            &Self::derive_wrapped_asset_seeds(bridge_key, asset_chain, asset),
         )?
             .0)
     }
@@ -479,15 +863,6 @@ impl Bridge {
         }
         panic!("Unable to find a viable program address nonce");
     }
-
-    /// Tx fee of Signature checks and PostVAA (see docs for calculation)
-    pub const VAA_TX_FEE: u64 = 18 * 10000;
-
-    pub fn transfer_fee() -> u64 {
-        // Pay for 2 signature state and Claimed VAA rents + 2 * guardian tx fees
-        // This will pay for this transfer and ~10 inbound ones
-        Rent::default().minimum_balance((size_of::<SignatureState>() + size_of::<ClaimedVAA>()) * 2) + Self::VAA_TX_FEE * 2
-    }
 }
 
 /// Check is a token state is initialized
@@ -34,7 +34,11 @@ use spl_token::{
     state::{Account, Mint},
 };
 
-use spl_bridge::{instruction::*, state::*};
+use spl_bridge::{
+    instruction::*,
+    state::*,
+    vaa::{HEADER_LEN, SIGNATURE_LEN},
+};
 
 use crate::faucet::request_and_confirm_airdrop;
 use solana_sdk::program_pack::Pack;
@@ -51,6 +55,11 @@ struct Config {
 type Error = Box<dyn std::error::Error>;
 type CommmandResult = Result<Option<Transaction>, Error>;
 
+/// Tx fee of Signature checks and PostVAA that is refunded to guardians (see docs for calculation)
+const VAA_TX_REFUND: u64 = 18 * 10000;
+/// VAAs can be submitted for a week, after which their claims can be evicted
+const VAA_EXPIRATION_TIME: u32 = 7 * 24 * 60 * 60;
+
 fn command_deploy_bridge(
     config: &Config,
     bridge: &Pubkey,
@@ -62,6 +71,18 @@ fn command_deploy_bridge(
         .rpc_client
         .get_minimum_balance_for_rent_exemption(size_of::<Mint>())?;
 
+    // Pay for 2 signature state, Claimed VAA and Posted VAA rents + 2 * guardian tx fees
+    // This will pay for this transfer and ~10 inbound ones
+    let transfer_fee = config.rpc_client.get_minimum_balance_for_rent_exemption(
+        (SignatureState::size(initial_guardian.len())
+            + size_of::<ClaimedVAA>()
+            + size_of::<PostedVAA>()
+            + HEADER_LEN
+            + SIGNATURE_LEN * initial_guardian.len()
+            + MAX_VAA_BODY_SIZE)
+            * 2,
+    )? + VAA_TX_REFUND * 2;
+
     let ix = initialize(
         bridge,
         &config.owner.pubkey(),
@@ -69,6 +90,9 @@ fn command_deploy_bridge(
         &BridgeConfig {
             guardian_set_expiration_time: 200000000,
             token_program: spl_token::id(),
+            transfer_fee,
+            vaa_tx_refund: VAA_TX_REFUND,
+            vaa_expiration_time: VAA_EXPIRATION_TIME,
         },
     )?;
     println!("bridge: {}, ", ix.accounts[2].pubkey.to_string());
@@ -85,44 +109,6 @@ fn command_deploy_bridge(
     Ok(Some(transaction))
 }
 
-fn command_create_wrapped(
-    config: &Config,
-    bridge: &Pubkey,
-    address: ForeignAddress,
-    chain: u8,
-    decimals: u8,
-) -> CommmandResult {
-    println!("Creating wrapped asset");
-
-    let minimum_balance_for_rent_exemption = config
-        .rpc_client
-        .get_minimum_balance_for_rent_exemption(size_of::<Mint>())?;
-
-    let ix = create_wrapped(
-        bridge,
-        &config.owner.pubkey(),
-        AssetMeta {
-            address,
-            chain,
-            decimals,
-        },
-    )?;
-    println!(
-        "Wrapped Mint address: {}",
-        ix.accounts[5].pubkey.to_string()
-    );
-    let mut transaction = Transaction::new_with_payer(&[ix], Some(&config.fee_payer.pubkey()));
-
-    let (recent_blockhash, fee_calculator) = config.rpc_client.get_recent_blockhash()?;
-    check_fee_payer_balance(
-        config,
-        minimum_balance_for_rent_exemption
-            + fee_calculator.calculate_fee(&transaction.message()),
-    )?;
-    transaction.sign(&[&config.fee_payer, &config.owner], recent_blockhash);
-    Ok(Some(transaction))
-}
-
 fn command_poke_proposal(config: &Config, bridge: &Pubkey, proposal: &Pubkey) -> CommmandResult {
     println!("Poking lockup");
 
@@ -144,6 +130,7 @@ fn command_lock_tokens(
     account: Pubkey,
     token: Pubkey,
     amount: u64,
+    fee: u64,
     to_chain: u8,
     target: ForeignAddress,
     nonce: u32,
@@ -156,6 +143,25 @@ fn command_lock_tokens(
 
     let bridge_key = Bridge::derive_bridge_id(bridge)?;
 
+    // Fetch the current transfer fee
+    let bridge_account = config.rpc_client.get_account(&bridge_key)?;
+    let bridge_state: &Bridge = Bridge::unpack_immutable(bridge_account.data.as_slice())?;
+    if bridge_state.paused_outbound {
+        return Err("transfers out of Solana are paused".into());
+    }
+    let fee_state_key = Bridge::derive_fee_state_id(bridge, &bridge_key)?;
+    let transfer_fee = match config.rpc_client.get_account(&fee_state_key) {
+        Ok(v) => {
+            let fee_state: &FeeState = Bridge::unpack_immutable(v.data.as_slice())?;
+            fee_state.transfer_fee(
+                config.rpc_client.get_slot()?,
+                bridge_state.config.transfer_fee,
+            )
+        }
+        // The fee state is created by the first transfer out
+        Err(_e) => bridge_state.config.transfer_fee,
+    };
+
     // Fetch token balance to get decimals.
     let balance = config
         .rpc_client
@@ -190,7 +196,7 @@ fn command_lock_tokens(
             &[],
             amount,
         )?,
-        system_instruction::transfer(&config.owner.pubkey(), &bridge_key, Bridge::transfer_fee()),
+        system_instruction::transfer(&config.owner.pubkey(), &bridge_key, transfer_fee),
         transfer_out(
             bridge,
             &config.owner.pubkey(),
@@ -202,6 +208,7 @@ fn command_lock_tokens(
                 asset: asset_meta,
                 target,
                 nonce,
+                fee: U256::from(fee),
             },
         )?,
     ];
@@ -254,6 +261,22 @@ fn check_owner_balance(config: &Config, required_balance: u64) -> Result<(), Err
     }
 }
 
+fn command_bridge_status(config: &Config, bridge: &Pubkey) -> CommmandResult {
+    let bridge_key = Bridge::derive_bridge_id(bridge)?;
+    let bridge_account = config.rpc_client.get_account(&bridge_key)?;
+    let bridge_state: &Bridge = Bridge::unpack_immutable(bridge_account.data.as_slice())?;
+
+    println!("bridge: {}", bridge_key);
+    println!("guardian set index: {}", bridge_state.guardian_set_index);
+    println!("sequence: {}", bridge_state.sequence);
+    println!("transfer fee: {}", bridge_state.config.transfer_fee);
+    println!("vaa tx refund: {}", bridge_state.config.vaa_tx_refund);
+    println!("inbound paused: {}", bridge_state.paused_inbound);
+    println!("outbound paused: {}", bridge_state.paused_outbound);
+
+    Ok(None)
+}
+
 fn command_create_token(config: &Config, decimals: u8, token: Keypair) -> CommmandResult {
     println!("Creating token {}", token.pubkey());
 
@@ -1016,6 +1039,15 @@ fn main() {
                         .required(true)
                         .help("Address of the recipient (hex)"),
                 )
+                .arg(
+                    Arg::with_name("fee")
+                        .long("fee")
+                        .validator(is_amount)
+                        .value_name("FEE")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("Part of the amount paid to the relayer on the target chain"),
+                )
         )
         .subcommand(
             SubCommand::with_name("postvaa")
@@ -1094,28 +1126,19 @@ fn main() {
                         .required(true)
                         .help("Chain ID of the asset"),
                 )
-                .arg(
-                    Arg::with_name("decimals")
-                        .validator(is_u8)
-                        .value_name("DECIMALS")
-                        .takes_value(true)
-                        .index(3)
-                        .required(true)
-                        .help("Decimals of the asset"),
-                )
                 .arg(
                     Arg::with_name("token")
                         .validator(is_hex)
                         .value_name("TOKEN_ADDRESS")
                         .takes_value(true)
-                        .index(4)
+                        .index(3)
                         .required(true)
                         .help("Token address of the asset"),
                 )
         )
         .subcommand(
-            SubCommand::with_name("create-wrapped")
-                .about("Create a new wrapped asset Mint")
+            SubCommand::with_name("bridge-status")
+                .about("Show the state of a bridge, including whether it is paused")
                 .arg(
                     Arg::with_name("bridge")
                         .long("bridge")
@@ -1128,33 +1151,6 @@ fn main() {
                             "Specify the bridge program public key"
                         ),
                 )
-                .arg(
-                    Arg::with_name("chain")
-                        .validator(is_u8)
-                        .value_name("CHAIN")
-                        .takes_value(true)
-                        .index(2)
-                        .required(true)
-                        .help("Chain ID of the asset"),
-                )
-                .arg(
-                    Arg::with_name("decimals")
-                        .validator(is_u8)
-                        .value_name("DECIMALS")
-                        .takes_value(true)
-                        .index(3)
-                        .required(true)
-                        .help("Decimals of the asset"),
-                )
-                .arg(
-                    Arg::with_name("token")
-                        .validator(is_hex)
-                        .value_name("TOKEN_ADDRESS")
-                        .takes_value(true)
-                        .index(4)
-                        .required(true)
-                        .help("Token address of the asset"),
-                )
         )
         .get_matches();
 
@@ -1262,6 +1258,7 @@ fn main() {
             let bridge = pubkey_of(arg_matches, "bridge").unwrap();
             let account = pubkey_of(arg_matches, "sender").unwrap();
             let amount = value_t_or_exit!(arg_matches, "amount", u64);
+            let fee = value_t_or_exit!(arg_matches, "fee", u64);
             let nonce = value_t_or_exit!(arg_matches, "nonce", u32);
             let chain = value_t_or_exit!(arg_matches, "chain", u8);
             let token = pubkey_of(arg_matches, "token").unwrap();
@@ -1275,7 +1272,7 @@ fn main() {
             let mut recipient = [0u8; 32];
             recipient.copy_from_slice(&recipient_data);
             command_lock_tokens(
-                &config, &bridge, account, token, amount, chain, recipient, nonce,
+                &config, &bridge, account, token, amount, fee, chain, recipient, nonce,
             )
         }
         ("poke", Some(arg_matches)) => {
@@ -1283,22 +1280,13 @@ fn main() {
             let proposal = pubkey_of(arg_matches, "proposal").unwrap();
             command_poke_proposal(&config, &bridge, &proposal)
         }
-        ("create-wrapped", Some(arg_matches)) => {
+        ("bridge-status", Some(arg_matches)) => {
             let bridge = pubkey_of(arg_matches, "bridge").unwrap();
-            let chain = value_t_or_exit!(arg_matches, "chain", u8);
-            let decimals = value_t_or_exit!(arg_matches, "decimals", u8);
-            let addr_string: String = value_of(arg_matches, "token").unwrap();
-            let addr_data = hex::decode(addr_string).unwrap();
-
-            let mut token_addr = [0u8; 32];
-            token_addr.copy_from_slice(addr_data.as_slice());
-
-            command_create_wrapped(&config, &bridge, token_addr, chain, decimals)
+            command_bridge_status(&config, &bridge)
         }
         ("wrapped-address", Some(arg_matches)) => {
             let bridge = pubkey_of(arg_matches, "bridge").unwrap();
             let chain = value_t_or_exit!(arg_matches, "chain", u8);
-            let decimals = value_t_or_exit!(arg_matches, "decimals", u8);
             let addr_string: String = value_of(arg_matches, "token").unwrap();
             let addr_data = hex::decode(addr_string).unwrap();
 
@@ -1307,8 +1295,7 @@ fn main() {
 
             let bridge_key = Bridge::derive_bridge_id(&bridge).unwrap();
             let wrapped_key =
-                Bridge::derive_wrapped_asset_id(&bridge, &bridge_key, chain, decimals, token_addr)
-                    .unwrap();
+                Bridge::derive_wrapped_asset_id(&bridge, &bridge_key, chain, token_addr).unwrap();
             println!("Wrapped address: {}", wrapped_key);
             return;
         }
@@ -41,19 +41,16 @@ echo "Created token account $account"
 # Mint new tokens owned by our CLI account
 cli mint "$token" 10000000000 "$account"
 
-# Create wrapped asset for the token we mint in send-lockups.js (2 = Ethereum, 9 decimals)
-wrapped_token=$(cli create-wrapped "$bridge_address" 2 9 000000000000000000000000CfEB869F69431e42cdB54A4F4f105C19C080A601 | grep 'Wrapped Mint address' | awk '{ print $4 }')
-echo "Created wrapped token $wrapped_token"
-
-# Create token account to receive wrapped assets from send-lockups.js
-wrapped_account=$(cli create-account --seed=934893 "$wrapped_token" | grep 'Creating account' | awk '{ print $3 }')
-echo "Created wrapped token account $wrapped_account"
-
-# Create wrapped asset and token account for Terra tokens (3 for Terra, 8 for precision)
-wrapped_terra_token=$(cli create-wrapped "$bridge_address" 3 8 0000000000000000000000003b1a7485c6162c5883ee45fb2d7477a87d8a4ce5 | grep 'Wrapped Mint address' | awk '{ print $4 }')
-echo "Created wrapped token for Terra $wrapped_terra_token"
-wrapped_terra_account=$(cli create-account --seed=736251 "$wrapped_terra_token" | grep 'Creating account' | awk '{ print $3 }')
-echo "Created wrapped token account for Terra $wrapped_terra_account"
+# Wrapped assets are created once their asset metadata is attested by the guardians and transfers
+# in are delivered to the associated token account of the recipient, so only derive the addresses.
+
+# Wrapped asset for the token we mint in send-lockups.js (2 = Ethereum)
+wrapped_token=$(cli wrapped-address "$bridge_address" 2 000000000000000000000000CfEB869F69431e42cdB54A4F4f105C19C080A601 | awk '{ print $3 }')
+echo "Wrapped token $wrapped_token"
+
+# Wrapped asset for Terra tokens (3 for Terra)
+wrapped_terra_token=$(cli wrapped-address "$bridge_address" 3 0000000000000000000000003b1a7485c6162c5883ee45fb2d7477a87d8a4ce5 | awk '{ print $3 }')
+echo "Wrapped token for Terra $wrapped_terra_token"
 
 # Let k8s startup probe succeed
 nc -l -p 2000
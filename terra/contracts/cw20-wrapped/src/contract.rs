@@ -10,11 +10,10 @@ use cw20_base::allowances::{
 use cw20_base::contract::{
     handle_mint, handle_send, handle_transfer, query_balance, query_token_info,
 };
-use cw20_base::state::{token_info, MinterData, TokenInfo};
+use cw20_base::state::{token_info, token_info_read, MinterData, TokenInfo};
 
 use crate::msg::{HandleMsg, InitMsg, QueryMsg, WrappedAssetInfoResponse};
 use crate::state::{wrapped_asset_info, wrapped_asset_info_read, WrappedAssetInfo};
-use std::string::String;
 
 pub fn init<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
@@ -23,8 +22,8 @@ pub fn init<S: Storage, A: Api, Q: Querier>(
 ) -> StdResult<InitResponse> {
     // store token info using cw20-base format
     let data = TokenInfo {
-        name: String::from("Wormhole Wrapped"),
-        symbol: String::from("WWT"),
+        name: msg.name,
+        symbol: msg.symbol,
         decimals: msg.decimals,
         total_supply: Uint128(0),
         // set creator as minter
@@ -104,6 +103,9 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
             amount,
             msg,
         } => Ok(handle_send_from(deps, env, owner, contract, amount, msg)?),
+        HandleMsg::UpdateMetadata { name, symbol } => {
+            handle_update_metadata(deps, env, name, symbol)
+        }
     }
 }
 
@@ -122,6 +124,26 @@ fn handle_mint_wrapped<S: Storage, A: Api, Q: Querier>(
     Ok(handle_mint(deps, env, recipient, amount)?)
 }
 
+fn handle_update_metadata<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    name: String,
+    symbol: String,
+) -> StdResult<HandleResponse> {
+    // Only bridge can update metadata
+    let wrapped_info = wrapped_asset_info_read(&deps.storage).load()?;
+    if wrapped_info.bridge != deps.api.canonical_address(&env.message.sender)? {
+        return Err(StdError::unauthorized());
+    }
+
+    let mut info = token_info_read(&deps.storage).load()?;
+    info.name = name;
+    info.symbol = symbol;
+    token_info(&mut deps.storage).save(&info)?;
+
+    Ok(HandleResponse::default())
+}
+
 pub fn query<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
     msg: QueryMsg,
@@ -167,6 +189,8 @@ mod tests {
 
     fn do_init<S: Storage, A: Api, Q: Querier>(deps: &mut Extern<S, A, Q>, creator: &HumanAddr) {
         let init_msg = InitMsg {
+            name: "Wormhole Wrapped".to_string(),
+            symbol: "WWT".to_string(),
             asset_chain: 1,
             asset_address: vec![1; 32].into(),
             decimals: 10,
@@ -299,4 +323,50 @@ mod tests {
         let env = mock_env(&owner, &[]);
         let _ = handle(&mut deps, env, msg.clone()).unwrap_err(); // Will panic if no error
     }
+
+    #[test]
+    fn bridge_can_update_metadata() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let minter = HumanAddr::from("minter");
+        do_init(&mut deps, &minter);
+
+        let msg = HandleMsg::UpdateMetadata {
+            name: "Wrapped Ether".to_string(),
+            symbol: "WETH".to_string(),
+        };
+
+        let env = mock_env(&minter, &[]);
+        let res = handle(&mut deps, env, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        assert_eq!(
+            query_token_info(&deps).unwrap(),
+            TokenInfoResponse {
+                name: "Wrapped Ether".to_string(),
+                symbol: "WETH".to_string(),
+                decimals: 10,
+                total_supply: Uint128::from(0u128),
+            }
+        );
+    }
+
+    #[test]
+    fn others_cannot_update_metadata() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let minter = HumanAddr::from("minter");
+        do_init(&mut deps, &minter);
+
+        let msg = HandleMsg::UpdateMetadata {
+            name: "Wrapped Ether".to_string(),
+            symbol: "WETH".to_string(),
+        };
+
+        let other_address = HumanAddr::from("other");
+        let env = mock_env(&other_address, &[]);
+        let res = handle(&mut deps, env, msg);
+        assert_eq!(
+            format!("{}", res.unwrap_err()),
+            format!("{}", crate::error::ContractError::Unauthorized {})
+        );
+    }
 }
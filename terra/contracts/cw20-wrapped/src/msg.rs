@@ -7,6 +7,8 @@ use cw20::Expiration;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InitMsg {
+    pub name: String,
+    pub symbol: String,
     pub asset_chain: u8,
     pub asset_address: Binary,
     pub decimals: u8,
@@ -82,6 +84,9 @@ pub enum HandleMsg {
     },
     /// Implements CW20 "approval" extension. Destroys tokens forever
     BurnFrom { owner: HumanAddr, amount: Uint128 },
+    /// Replaces the token name and symbol with the ones attested by the guardians. Only the bridge
+    /// can update the metadata.
+    UpdateMetadata { name: String, symbol: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
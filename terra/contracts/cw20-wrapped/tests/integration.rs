@@ -50,6 +50,8 @@ fn get_wrapped_asset_info<S: Storage>(storage: &S) -> WrappedAssetInfo {
 fn do_init(height: u64) -> Instance<MockStorage, MockApi, MockQuerier> {
     let mut deps = mock_instance(WASM, &[]);
     let init_msg = InitMsg {
+        name: "Wormhole Wrapped".to_string(),
+        symbol: "WWT".to_string(),
         asset_chain: 1,
         asset_address: vec![1; 32].into(),
         decimals: 10,
@@ -1,43 +1,35 @@
 use cosmwasm_std::CanonicalAddr;
 
-pub trait ByteUtils {
-    fn get_u8(&self, index: usize) -> u8;
-    fn get_u32(&self, index: usize) -> u32;
-    fn get_u128_be(&self, index: usize) -> u128;
-    /// High 128 then low 128
-    fn get_u256(&self, index: usize) -> (u128, u128);
-    fn get_address(&self, index: usize) -> CanonicalAddr;
-    fn get_bytes32(&self, index: usize) -> &[u8];
+pub fn extend_address_to_32(addr: &CanonicalAddr) -> Vec<u8> {
+    let mut result: Vec<u8> = vec![0; 12];
+    result.extend(addr.as_slice());
+    result
 }
 
-impl ByteUtils for &[u8] {
-    fn get_u8(&self, index: usize) -> u8 {
-        self[index]
-    }
-    fn get_u32(&self, index: usize) -> u32 {
-        let mut bytes: [u8; 32 / 8] = [0; 32 / 8];
-        bytes.copy_from_slice(&self[index..index + 4]);
-        u32::from_be_bytes(bytes)
-    }
-    fn get_u128_be(&self, index: usize) -> u128 {
-        let mut bytes: [u8; 128 / 8] = [0; 128 / 8];
-        bytes.copy_from_slice(&self[index..index + 128 / 8]);
-        u128::from_be_bytes(bytes)
-    }
-    fn get_u256(&self, index: usize) -> (u128, u128) {
-        (self.get_u128_be(index), self.get_u128_be(index + 128 / 8))
-    }
-    fn get_address(&self, index: usize) -> CanonicalAddr {
-        // 32 bytes are reserved for addresses, but only the last 20 bytes are taken by the actual address
-        CanonicalAddr::from(&self[index + 32 - 20..index + 32])
-    }
-    fn get_bytes32(&self, index: usize) -> &[u8] {
-        &self[index..index + 32]
+pub fn address_from_32(addr: &[u8; 32]) -> CanonicalAddr {
+    // 32 bytes are reserved for addresses, but only the last 20 bytes are taken by the actual address
+    CanonicalAddr::from(&addr[32 - 20..])
+}
+
+// Marker in the first byte of a native denom address. CW20 addresses are zero-padded and never set it.
+const NATIVE_DENOM_MARKER: u8 = 1;
+
+pub fn extend_denom_to_32(denom: &str) -> Option<Vec<u8>> {
+    // Denom bytes are right-aligned behind the marker, the remaining bytes are zero
+    let denom = denom.as_bytes();
+    if denom.is_empty() || denom.len() > 31 || denom.contains(&0) {
+        return None;
     }
+    let mut result: Vec<u8> = vec![0; 32 - denom.len()];
+    result[0] = NATIVE_DENOM_MARKER;
+    result.extend(denom);
+    Some(result)
 }
 
-pub fn extend_address_to_32(addr: &CanonicalAddr) -> Vec<u8> {
-    let mut result: Vec<u8> = vec![0; 12];
-    result.extend(addr.as_slice());
-    result
+pub fn denom_from_32(addr: &[u8; 32]) -> Option<String> {
+    if addr[0] != NATIVE_DENOM_MARKER {
+        return None;
+    }
+    let start = addr[1..].iter().position(|b| *b != 0)? + 1;
+    String::from_utf8(addr[start..].to_vec()).ok()
 }
@@ -1,17 +1,23 @@
 use crate::msg::WrappedRegistryResponse;
 use cosmwasm_std::{
-    log, to_binary, Api, Binary, CanonicalAddr, CosmosMsg, Env, Extern, HandleResponse, HumanAddr,
-    InitResponse, Querier, QueryRequest, StdResult, Storage, Uint128, WasmMsg, WasmQuery,
+    log, to_binary, Api, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Env, Extern,
+    HandleResponse, HumanAddr, InitResponse, MigrateResponse, Querier, QueryRequest, StdResult,
+    Storage, Uint128, WasmMsg, WasmQuery,
 };
 
-use crate::byte_utils::extend_address_to_32;
-use crate::byte_utils::ByteUtils;
+use crate::byte_utils::{address_from_32, denom_from_32, extend_address_to_32, extend_denom_to_32};
 use crate::error::ContractError;
-use crate::msg::{GuardianSetInfoResponse, HandleMsg, InitMsg, QueryMsg};
+use crate::msg::{
+    GuardianSetInfoResponse, HandleMsg, InitMsg, MigrateMsg, PendingTransferResponse, QueryMsg,
+    ReceiverHandleMsg, TransferCapacityResponse, VaaStatusResponse, WrappedTransfer,
+};
 use crate::state::{
-    config, config_read, guardian_set_get, guardian_set_set, vaa_archive_add, vaa_archive_check,
-    wrapped_asset, wrapped_asset_address, wrapped_asset_address_read, wrapped_asset_read,
-    ConfigInfo, GuardianAddress, GuardianSetInfo,
+    config, config_read, guardian_set_get, guardian_set_set, pending_transfer,
+    pending_transfer_read, transfer_limit, transfer_limit_read, transfer_window,
+    transfer_window_read, vaa_archive_add, vaa_archive_check, vaa_archive_get, vaa_archive_migrate,
+    vaa_archive_remove, wrapped_asset, wrapped_asset_address, wrapped_asset_address_read,
+    wrapped_asset_meta, wrapped_asset_meta_read, wrapped_asset_read, ConfigInfo, GuardianAddress,
+    GuardianSetInfo, ParsedVAA, PendingTransferInfo, TransferLimitInfo, WrappedAssetMetaInfo,
 };
 
 use cw20_base::msg::HandleMsg as TokenMsg;
@@ -22,25 +28,39 @@ use cw20::TokenInfoResponse;
 use cw20_wrapped::msg::HandleMsg as WrappedMsg;
 use cw20_wrapped::msg::InitMsg as WrappedInit;
 use cw20_wrapped::msg::QueryMsg as WrappedQuery;
-use cw20_wrapped::msg::{InitHook, InitMint, WrappedAssetInfoResponse};
+use cw20_wrapped::msg::{InitHook, WrappedAssetInfoResponse};
+
+use wormhole_vaa::{
+    BodyAssetMetadata, BodyGovernance, BodyTransfer, BodyUpdateGuardianSet, Error as VAAError,
+    GovernanceChange, Reader, VAABody, VAAPayload, U256,
+};
 
-use k256::ecdsa::recoverable::Id as RecoverableId;
-use k256::ecdsa::recoverable::Signature as RecoverableSignature;
-use k256::ecdsa::Signature;
-use k256::ecdsa::VerifyKey;
-use k256::EncodedPoint;
 use sha3::{Digest, Keccak256};
 
-use std::convert::TryFrom;
+use terra_cosmwasm::TerraQuerier;
 
 // Chain ID of Terra
 const CHAIN_ID: u8 = 3;
 
+// Decimals of native Terra denoms (uluna, uusd, ...)
+const NATIVE_DENOM_DECIMALS: u8 = 6;
+
+// Metadata of wrapped assets deployed before the guardians attested their metadata
+const WRAPPED_ASSET_DEFAULT_NAME: &str = "Wormhole Wrapped";
+const WRAPPED_ASSET_DEFAULT_SYMBOL: &str = "WWT";
+
 pub fn init<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     msg: InitMsg,
 ) -> StdResult<InitResponse> {
+    if !msg.initial_guardian_set.is_valid_size() {
+        return ContractError::InvalidGuardianSetSize.std_err();
+    }
+    if !msg.initial_guardian_set.is_valid_threshold() {
+        return ContractError::InvalidGuardianSetThreshold.std_err();
+    }
+
     // Save general wormhole info
     let state = ConfigInfo {
         guardian_set_index: 0,
@@ -48,6 +68,8 @@ pub fn init<S: Storage, A: Api, Q: Querier>(
         wrapped_asset_code_id: msg.wrapped_asset_code_id,
         owner: deps.api.canonical_address(&env.message.sender)?,
         is_active: true,
+        sequence: 0,
+        vaa_expiration_time: msg.vaa_expiration_time,
     };
     config(&mut deps.storage).save(&state)?;
 
@@ -68,8 +90,8 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
 ) -> StdResult<HandleResponse> {
     match msg {
         HandleMsg::SubmitVAA { vaa } => handle_submit_vaa(deps, env, &vaa.as_slice()),
-        HandleMsg::RegisterAssetHook { asset_id } => {
-            handle_register_asset(deps, env, &asset_id.as_slice())
+        HandleMsg::RegisterAssetHook { asset_id, transfer } => {
+            handle_register_asset(deps, env, &asset_id.as_slice(), transfer)
         }
         HandleMsg::LockAssets {
             asset,
@@ -77,6 +99,7 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
             amount,
             target_chain,
             nonce,
+            fee,
         } => handle_lock_assets(
             deps,
             env,
@@ -85,9 +108,57 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
             recipient.as_slice(),
             target_chain,
             nonce,
+            fee.unwrap_or_else(Uint128::zero),
+        ),
+        HandleMsg::LockNativeAssets {
+            recipient,
+            target_chain,
+            nonce,
+            fee,
+        } => handle_lock_native_assets(
+            deps,
+            env,
+            recipient.as_slice(),
+            target_chain,
+            nonce,
+            fee.unwrap_or_else(Uint128::zero),
         ),
         HandleMsg::SetActive { is_active } => handle_set_active(deps, env, is_active),
+        HandleMsg::SetTransferLimit {
+            asset_chain,
+            asset_address,
+            limit,
+        } => handle_set_transfer_limit(deps, env, asset_chain, asset_address.as_slice(), limit),
+        HandleMsg::ReleasePendingTransfer { hash } => {
+            handle_release_pending_transfer(deps, env, hash.as_slice())
+        }
+        HandleMsg::CancelPendingTransfer { hash } => {
+            handle_cancel_pending_transfer(deps, env, hash.as_slice())
+        }
+    }
+}
+
+/// Move executed VAA hashes out of the legacy archive that shared its namespace with guardian sets
+pub fn migrate<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    _env: Env,
+    msg: MigrateMsg,
+) -> StdResult<MigrateResponse> {
+    let mut migrated = 0u32;
+    for hash in msg.vaa_hashes.iter() {
+        if vaa_archive_migrate(&mut deps.storage, hash.as_slice())? {
+            migrated += 1;
+        }
     }
+
+    Ok(MigrateResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "migrate"),
+            log("vaa_archive.migrated", migrated),
+        ],
+        data: None,
+    })
 }
 
 /// Process VAA message signed by quardians
@@ -97,110 +168,96 @@ fn handle_submit_vaa<S: Storage, A: Api, Q: Querier>(
     data: &[u8],
 ) -> StdResult<HandleResponse> {
     let state = config_read(&deps.storage).load()?;
-    if !state.is_active {
-        return ContractError::ContractInactive.std_err();
-    }
-
-    /* VAA format:
-
-    header (length 6):
-    0   uint8   version (0x01)
-    1   uint32  guardian set index
-    5   uint8   len signatures
-
-    per signature (length 66):
-    0   uint8       index of the signer (in guardian keys)
-    1   [65]uint8   signature
 
-    body:
-    0   uint32  unix seconds
-    4   uint8   action
-    5   [payload_size]uint8 payload */
+    let vaa = ParsedVAA::deserialize(data)?;
 
-    const HEADER_LEN: usize = 6;
-    const SIGNATURE_LEN: usize = 66;
-
-    let version = data.get_u8(0);
-    if version != 1 {
-        return ContractError::InvalidVersion.std_err();
+    // Governance VAAs are accepted while the contract is inactive, they are used to reactivate it
+    let is_governance = matches!(vaa.payload, VAABody::Governance(_));
+    if !state.is_active && !is_governance {
+        return ContractError::ContractInactive.std_err();
     }
 
-    // Load 4 bytes starting from index 1
-    let vaa_guardian_set_index: u32 = data.get_u32(1);
-    let len_signers = data.get_u8(5) as usize;
-    let body_offset: usize = 6 + SIGNATURE_LEN * len_signers as usize;
-
-    // Hash the body
-    let body = &data[body_offset..];
-    let mut hasher = Keccak256::new();
-    hasher.update(body);
-    let hash = hasher.finalize();
+    // Transfer VAAs expire, so that transfers out of Solana that were not redeemed in time can be
+    // refunded without the risk of also being redeemed later
+    let is_transfer = matches!(
+        vaa.payload,
+        VAABody::Transfer(_) | VAABody::TransferWithPayload(_)
+    );
+    if is_transfer
+        && (vaa.timestamp as u64).saturating_add(state.vaa_expiration_time) < env.block.time
+    {
+        return ContractError::VaaExpired.std_err();
+    }
 
     // Check if VAA with this hash was already accepted
-    if vaa_archive_check(&deps.storage, &hash) {
+    if vaa_archive_check(&deps.storage, &vaa.hash) {
         return ContractError::VaaAlreadyExecuted.std_err();
     }
 
     // Load and check guardian set
-    let guardian_set = guardian_set_get(&deps.storage, vaa_guardian_set_index);
+    let guardian_set = guardian_set_get(&deps.storage, vaa.guardian_set_index);
     let guardian_set: GuardianSetInfo =
         guardian_set.or_else(|_| ContractError::InvalidGuardianSetIndex.std_err())?;
 
     if guardian_set.expiration_time != 0 && guardian_set.expiration_time < env.block.time {
         return ContractError::GuardianSetExpired.std_err();
     }
-    if len_signers < guardian_set.quorum() {
+    if vaa.signatures.len() < guardian_set.quorum() {
         return ContractError::NoQuorum.std_err();
     }
 
     // Verify guardian signatures
     let mut last_index: i32 = -1;
-    let mut pos = HEADER_LEN;
-    for _ in 0..len_signers {
-        let index = data.get_u8(pos) as i32;
+    for signature in vaa.signatures.iter() {
+        let index = signature.index as i32;
         if index <= last_index {
             return ContractError::WrongGuardianIndexOrder.std_err();
         }
         last_index = index;
 
-        let signature = Signature::try_from(&data[pos + 1..pos + 1 + 64])
-            .or_else(|_| ContractError::CannotDecodeSignature.std_err())?;
-        let id = RecoverableId::new(data.get_u8(pos + 1 + 64))
-            .or_else(|_| ContractError::CannotDecodeSignature.std_err())?;
-        let recoverable_signature = RecoverableSignature::new(&signature, id)
-            .or_else(|_| ContractError::CannotDecodeSignature.std_err())?;
-
-        let verify_key = recoverable_signature
-            .recover_verify_key_from_digest_bytes(&hash)
-            .or_else(|_| ContractError::CannotRecoverKey.std_err())?;
+        let address = signature.recover(&vaa.hash).or_else(|e| match e {
+            VAAError::InvalidSignature => ContractError::CannotDecodeSignature.std_err(),
+            _ => ContractError::CannotRecoverKey.std_err(),
+        })?;
 
         let index = index as usize;
         if index >= guardian_set.addresses.len() {
             return ContractError::TooManySignatures.std_err();
         }
-        if !keys_equal(&verify_key, &guardian_set.addresses[index]) {
+        if guardian_set.addresses[index].bytes.as_slice() != &address[..] {
             return ContractError::GuardianSignatureError.std_err();
         }
-        pos += SIGNATURE_LEN;
     }
 
     // Signatures valid, apply VAA
-    let action = data.get_u8(body_offset + 4);
-    let payload = &data[body_offset + 5..];
-
-    let result = match action {
-        0x01 => {
-            if vaa_guardian_set_index != state.guardian_set_index {
+    let block_time = env.block.time;
+    let result = match vaa.payload {
+        VAABody::UpdateGuardianSet(v) => {
+            if vaa.guardian_set_index != state.guardian_set_index {
+                return ContractError::NotCurrentGuardianSet.std_err();
+            }
+            vaa_update_guardian_set(deps, env, &v)
+        }
+        VAABody::Governance(v) => {
+            if vaa.guardian_set_index != state.guardian_set_index {
                 return ContractError::NotCurrentGuardianSet.std_err();
             }
-            vaa_update_guardian_set(deps, env, payload)
+            vaa_governance(deps, &v)
+        }
+        VAABody::Transfer(v) => vaa_transfer(deps, env, &vaa.hash, &v, None),
+        VAABody::TransferWithPayload(v) => {
+            vaa_transfer(deps, env, &vaa.hash, &v.transfer, Some(&v.payload))
+        }
+        VAABody::AssetMetadata(v) => vaa_asset_metadata(deps, &v),
+        // Messages are consumed by the contracts they are addressed to, not by the bridge. Refunds
+        // only exist for transfers out of Solana.
+        VAABody::Message(_) | VAABody::Refund(_) | VAABody::Unknown { .. } => {
+            ContractError::InvalidVAAAction.std_err()
         }
-        0x10 => vaa_transfer(deps, env, payload),
-        _ => ContractError::InvalidVAAAction.std_err(),
     };
 
     if result.is_ok() {
-        vaa_archive_add(&mut deps.storage, &hash)?;
+        vaa_archive_add(&mut deps.storage, &vaa.hash, block_time)?;
     }
 
     result
@@ -211,6 +268,7 @@ fn handle_register_asset<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     asset_id: &[u8],
+    transfer: Option<WrappedTransfer>,
 ) -> StdResult<HandleResponse> {
     let mut bucket = wrapped_asset(&mut deps.storage);
     let result = bucket.load(asset_id);
@@ -227,8 +285,16 @@ fn handle_register_asset<S: Storage, A: Api, Q: Querier>(
             wrapped_asset_address(&mut deps.storage)
                 .save(contract_address.as_slice(), &asset_id.to_vec())?;
 
+            // Mint the transfer that deployed the asset
+            let messages = match transfer {
+                Some(transfer) => {
+                    wrapped_transfer_messages(&env, env.message.sender.clone(), transfer)?
+                }
+                None => vec![],
+            };
+
             Ok(HandleResponse {
-                messages: vec![],
+                messages,
                 log: vec![
                     log("action", "register_asset"),
                     log("asset_id", format!("{:?}", asset_id)),
@@ -243,33 +309,32 @@ fn handle_register_asset<S: Storage, A: Api, Q: Querier>(
 fn vaa_update_guardian_set<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
-    data: &[u8],
+    v: &BodyUpdateGuardianSet,
 ) -> StdResult<HandleResponse> {
-    /* Payload format
-    0   uint32 new_index
-    4   uint8 len(keys)
-    5   [][20]uint8 guardian addresses
-    */
-
     let mut state = config_read(&deps.storage).load()?;
 
-    let new_guardian_set_index = data.get_u32(0);
+    let new_guardian_set_index = v.new_index;
 
     if new_guardian_set_index != state.guardian_set_index + 1 {
         return ContractError::GuardianSetIndexIncreaseError.std_err();
     }
-    let len = data.get_u8(4);
 
-    let mut new_guardian_set = GuardianSetInfo {
-        addresses: vec![],
+    let new_guardian_set = GuardianSetInfo {
+        addresses: v
+            .new_keys
+            .iter()
+            .map(|key| GuardianAddress {
+                bytes: key.to_vec().into(),
+            })
+            .collect(),
         expiration_time: 0,
+        threshold: v.threshold,
     };
-    let mut pos = 5;
-    for _ in 0..len {
-        new_guardian_set.addresses.push(GuardianAddress {
-            bytes: data[pos..pos + 20].to_vec().into(),
-        });
-        pos += 20;
+    if !new_guardian_set.is_valid_size() {
+        return ContractError::InvalidGuardianSetSize.std_err();
+    }
+    if !new_guardian_set.is_valid_threshold() {
+        return ContractError::InvalidGuardianSetThreshold.std_err();
     }
 
     let old_guardian_set_index = state.guardian_set_index;
@@ -298,94 +363,294 @@ fn vaa_update_guardian_set<S: Storage, A: Api, Q: Querier>(
     })
 }
 
+fn vaa_governance<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    v: &BodyGovernance,
+) -> StdResult<HandleResponse> {
+    if v.target_chain != CHAIN_ID {
+        return ContractError::WrongTargetChain.std_err();
+    }
+
+    let mut state = config_read(&deps.storage).load()?;
+
+    let change = match &v.change {
+        GovernanceChange::WrappedAssetCodeId(code_id) => {
+            state.wrapped_asset_code_id = *code_id;
+            log("wrapped_asset_code_id", code_id)
+        }
+        GovernanceChange::GuardianSetExpiration(expirity) => {
+            state.guardian_set_expirity = *expirity;
+            log("guardian_set_expirity", expirity)
+        }
+        GovernanceChange::Owner(owner) => {
+            state.owner = address_from_32(owner);
+            log(
+                "owner",
+                deps.api
+                    .human_address(&state.owner)
+                    .or_else(|_| ContractError::WrongTargetAddressFormat.std_err())?,
+            )
+        }
+        GovernanceChange::Active(is_active) => {
+            state.is_active = *is_active;
+            log("is_active", is_active)
+        }
+        GovernanceChange::VAAExpiration(expiration) => {
+            state.vaa_expiration_time = *expiration;
+            log("vaa_expiration_time", expiration)
+        }
+        // Fees and per-direction pauses only exist on Solana. Unknown changes require an upgrade
+        // of the contract.
+        GovernanceChange::TransferFee(_)
+        | GovernanceChange::VAARefund(_)
+        | GovernanceChange::WithdrawFees { .. }
+        | GovernanceChange::Pause { .. }
+        | GovernanceChange::Unknown { .. } => {
+            return ContractError::InvalidVAAAction.std_err();
+        }
+    };
+
+    config(&mut deps.storage).save(&state)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("action", "governance"), change],
+        data: None,
+    })
+}
+
+fn vaa_asset_metadata<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    v: &BodyAssetMetadata,
+) -> StdResult<HandleResponse> {
+    // Native assets keep the metadata of their own token contracts
+    if v.asset.chain == CHAIN_ID {
+        return ContractError::InvalidAssetMetadata.std_err();
+    }
+
+    let meta = WrappedAssetMetaInfo {
+        name: v
+            .name_str()
+            .or_else(|_| ContractError::InvalidAssetMetadata.std_err())?
+            .to_string(),
+        symbol: v
+            .symbol_str()
+            .or_else(|_| ContractError::InvalidAssetMetadata.std_err())?
+            .to_string(),
+    };
+
+    let asset_id = build_asset_id(v.asset.chain, &v.asset.address);
+    wrapped_asset_meta(&mut deps.storage).save(&asset_id, &meta)?;
+
+    // Refresh the metadata of the wrapped asset if it is already deployed, otherwise it is used
+    // when the asset is instantiated by the first transfer
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if let Ok(contract_addr) = wrapped_asset_read(&deps.storage).load(&asset_id) {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg: to_binary(&WrappedMsg::UpdateMetadata {
+                name: meta.name.clone(),
+                symbol: meta.symbol.clone(),
+            })?,
+            send: vec![],
+        }));
+    }
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![
+            log("action", "asset_metadata"),
+            log("asset_id", hex::encode(&asset_id)),
+            log("name", meta.name),
+            log("symbol", meta.symbol),
+        ],
+        data: None,
+    })
+}
+
 fn vaa_transfer<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
-    data: &[u8],
+    hash: &[u8],
+    v: &BodyTransfer,
+    payload: Option<&[u8]>,
 ) -> StdResult<HandleResponse> {
-    /* Payload format:
-    0   uint32 nonce
-    4   uint8 source_chain
-    5   uint8 target_chain
-    6   [32]uint8 source_address
-    38  [32]uint8 target_address
-    70  uint8 token_chain
-    71  [32]uint8 token_address
-    103 uint8 decimals
-    104 uint256 amount */
-
-    let source_chain = data.get_u8(4);
-    let target_chain = data.get_u8(5);
-
-    let target_address = data.get_address(38);
-
-    let token_chain = data.get_u8(70);
-    let (not_supported_amount, amount) = data.get_u256(104);
-
-    // Check high 128 bit of amount value to be empty
-    if not_supported_amount != 0 {
+    // Only 128 bit amounts are supported
+    if v.amount > U256::from(u128::MAX) {
         return ContractError::AmountTooHigh.std_err();
     }
+    let amount = v.amount.as_u128();
 
     // Check if source and target chains are different
-    if source_chain == target_chain {
+    if v.source_chain == v.target_chain {
         return ContractError::SameSourceAndTarget.std_err();
     }
 
     // Check if transfer is incoming
-    if target_chain != CHAIN_ID {
+    if v.target_chain != CHAIN_ID {
         return ContractError::WrongTargetChain.std_err();
     }
 
+    // Transfers over the limits of the asset are queued instead of released
+    let asset_id = build_asset_id(v.asset.chain, &v.asset.address);
+    if let Some(release_delay) =
+        inbound_transfer_delay(&mut deps.storage, &asset_id, amount, env.block.time)?
+    {
+        let release_time = env.block.time + release_delay;
+        pending_transfer(&mut deps.storage).save(
+            hash,
+            &PendingTransferInfo {
+                payload: BodyTransfer {
+                    fee: U256::zero(),
+                    ..v.clone()
+                }
+                .serialize()
+                .or_else(|_| ContractError::CannotParseVAA.std_err())?
+                .into(),
+                message: payload.map(Binary::from),
+                relayer_fee: Uint128::from(v.fee.as_u128()),
+                relayer: Some(env.message.sender.clone()),
+                release_time,
+            },
+        )?;
+
+        return Ok(HandleResponse {
+            messages: vec![],
+            log: vec![
+                log("action", "transfer_queued"),
+                log("hash", hex::encode(hash)),
+                log("release_time", release_time),
+            ],
+            data: None,
+        });
+    }
+
+    let relayer = env.message.sender.clone();
+    release_transfer(deps, env, v, amount, payload, relayer)
+}
+
+// Pays out an inbound transfer. Transfers with a payload are sent to the target contract together
+// with the payload instead of being credited to the target address. The relayer fee is carved out
+// of the amount and paid to the sender of the message that releases the transfer.
+fn release_transfer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    v: &BodyTransfer,
+    amount: u128,
+    payload: Option<&[u8]>,
+    relayer: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let target_address = address_from_32(&v.target_address);
+    let target = deps
+        .api
+        .human_address(&target_address)
+        .or_else(|_| ContractError::WrongTargetAddressFormat.std_err())?;
+
+    // The fee cannot exceed the amount, this is checked when the VAA is decoded
+    let fee = v.fee.as_u128();
+    let amount = amount - fee;
+
+    let token_chain = v.asset.chain;
+
     if token_chain != CHAIN_ID {
-        let asset_address = data.get_bytes32(71);
+        let asset_address = &v.asset.address[..];
         let asset_id = build_asset_id(token_chain, asset_address);
 
-        let mut messages: Vec<CosmosMsg> = vec![];
+        let transfer = WrappedTransfer {
+            recipient: target,
+            amount: Uint128::from(amount),
+            payload: payload.map(Binary::from),
+            relayer,
+            relayer_fee: Uint128::from(fee),
+        };
 
         // Check if this asset is already deployed
-        match wrapped_asset_read(&deps.storage).load(&asset_id) {
-            Ok(contract_addr) => {
-                // Asset already deployed, just mint
-                messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
-                    contract_addr,
-                    msg: to_binary(&WrappedMsg::Mint {
-                        recipient: deps
-                            .api
-                            .human_address(&target_address)
-                            .or_else(|_| ContractError::WrongTargetAddressFormat.std_err())?,
-                        amount: Uint128::from(amount),
-                    })?,
-                    send: vec![],
-                }));
-            }
+        let messages = match wrapped_asset_read(&deps.storage).load(&asset_id) {
+            Ok(contract_addr) => wrapped_transfer_messages(&env, contract_addr, transfer)?,
             Err(_) => {
-                // Asset is not deployed yet, deploy and mint
+                // Asset is not deployed yet, deploy it. Its address is only known once it is
+                // registered, the transfer is minted by the registration hook.
                 let state = config_read(&deps.storage).load()?;
-                messages.push(CosmosMsg::Wasm(WasmMsg::Instantiate {
+                let meta = wrapped_asset_meta_read(&deps.storage)
+                    .may_load(&asset_id)?
+                    .unwrap_or_else(|| WrappedAssetMetaInfo {
+                        name: String::from(WRAPPED_ASSET_DEFAULT_NAME),
+                        symbol: String::from(WRAPPED_ASSET_DEFAULT_SYMBOL),
+                    });
+                vec![CosmosMsg::Wasm(WasmMsg::Instantiate {
                     code_id: state.wrapped_asset_code_id,
                     msg: to_binary(&WrappedInit {
+                        name: meta.name,
+                        symbol: meta.symbol,
                         asset_chain: token_chain,
                         asset_address: asset_address.to_vec().into(),
-                        decimals: data.get_u8(103),
-                        mint: Some(InitMint {
-                            recipient: deps
-                                .api
-                                .human_address(&target_address)
-                                .or_else(|_| ContractError::WrongTargetAddressFormat.std_err())?,
-                            amount: Uint128::from(amount),
-                        }),
+                        decimals: v.asset.decimals,
+                        mint: None,
                         init_hook: Some(InitHook {
                             contract_addr: env.contract.address,
                             msg: to_binary(&HandleMsg::RegisterAssetHook {
                                 asset_id: asset_id.to_vec().into(),
+                                transfer: Some(transfer),
                             })?,
                         }),
                     })?,
                     send: vec![],
                     label: None,
-                }));
+                })]
+            }
+        };
+
+        Ok(HandleResponse {
+            messages,
+            log: vec![],
+            data: None,
+        })
+    } else if v.asset.address[0] != 0 {
+        // Native denom held by this contract, release it from the contract balance
+        let denom = match denom_from_32(&v.asset.address) {
+            Some(denom) => denom,
+            None => return ContractError::InvalidNativeDenom.std_err(),
+        };
+
+        // The stability tax is charged on top of every send, deduct it so the contract balance
+        // covers the sent coins
+        let coins = |amount: u128| -> StdResult<Vec<Coin>> {
+            Ok(vec![deduct_tax(
+                deps,
+                Coin {
+                    denom: denom.clone(),
+                    amount: Uint128::from(amount),
+                },
+            )?])
+        };
+
+        // Native denoms have no Send, the target is called with the payload wrapped in a
+        // ReceiveNative message and the coins attached
+        let mut messages = vec![match payload {
+            Some(payload) => {
+                check_payload_target(deps, &target_address, &target)?;
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: target,
+                    msg: to_binary(&ReceiverHandleMsg::ReceiveNative {
+                        source_chain: v.source_chain,
+                        sender: Binary::from(&v.source_address[..]),
+                        payload: Binary::from(payload),
+                    })?,
+                    send: coins(amount)?,
+                })
             }
+            None => CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address.clone(),
+                to_address: target,
+                amount: coins(amount)?,
+            }),
+        }];
+        if fee != 0 {
+            messages.push(CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address,
+                to_address: relayer,
+                amount: coins(fee)?,
+            }));
         }
 
         Ok(HandleResponse {
@@ -394,23 +659,157 @@ fn vaa_transfer<S: Storage, A: Api, Q: Querier>(
             data: None,
         })
     } else {
-        let token_address = data.get_address(71);
+        let token_address = deps.api.human_address(&address_from_32(&v.asset.address))?;
 
-        Ok(HandleResponse {
-            messages: vec![CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: deps.api.human_address(&token_address)?,
+        let msg = match payload {
+            Some(payload) => TokenMsg::Send {
+                contract: target,
+                amount: Uint128::from(amount),
+                msg: Some(Binary::from(payload)),
+            },
+            None => TokenMsg::Transfer {
+                recipient: target,
+                amount: Uint128::from(amount),
+            },
+        };
+        let mut messages = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: token_address.clone(),
+            msg: to_binary(&msg)?,
+            send: vec![],
+        })];
+        if fee != 0 {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: token_address,
                 msg: to_binary(&TokenMsg::Transfer {
-                    recipient: deps.api.human_address(&target_address)?,
-                    amount: Uint128::from(amount),
+                    recipient: relayer,
+                    amount: Uint128::from(fee),
                 })?,
                 send: vec![],
-            })],
+            }));
+        }
+
+        Ok(HandleResponse {
+            messages,
             log: vec![], // TODO: Add log entries
             data: None,
         })
     }
 }
 
+// The bridge is the sender of the message that delivers a native transfer with payload. Wrapped
+// assets accept mints from the bridge and it holds balances of CW20 tokens, so neither can be the
+// target of such a transfer.
+fn check_payload_target<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    target_address: &CanonicalAddr,
+    target: &HumanAddr,
+) -> StdResult<()> {
+    if wrapped_asset_address_read(&deps.storage)
+        .load(target_address.as_slice())
+        .is_ok()
+    {
+        return ContractError::InvalidPayloadTarget.std_err();
+    }
+
+    let request = QueryRequest::<()>::Wasm(WasmQuery::Smart {
+        contract_addr: target.clone(),
+        msg: to_binary(&TokenQuery::TokenInfo {})?,
+    });
+    let token_info: StdResult<TokenInfoResponse> = deps.querier.custom_query(&request);
+    if token_info.is_ok() {
+        return ContractError::InvalidPayloadTarget.std_err();
+    }
+
+    Ok(())
+}
+
+const DECIMAL_FRACTION: Uint128 = Uint128(1_000_000_000_000_000_000u128);
+
+// Tax charged by the chain when sending `coin`, min(amount * rate / (1 + rate), cap)
+fn compute_tax<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    coin: &Coin,
+) -> StdResult<Uint128> {
+    // Luna is exempt from the stability tax
+    if coin.denom == "uluna" {
+        return Ok(Uint128::zero());
+    }
+    let terra_querier = TerraQuerier::new(&deps.querier);
+    let tax_rate: Decimal = terra_querier.query_tax_rate()?.rate;
+    let tax_cap: Uint128 = terra_querier.query_tax_cap(coin.denom.to_string())?.cap;
+    Ok(std::cmp::min(
+        (coin.amount
+            - coin.amount.multiply_ratio(
+                DECIMAL_FRACTION,
+                DECIMAL_FRACTION * tax_rate + DECIMAL_FRACTION,
+            ))?,
+        tax_cap,
+    ))
+}
+
+// Reduces `coin` so that it and the tax on it add up to the original amount
+fn deduct_tax<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    coin: Coin,
+) -> StdResult<Coin> {
+    let tax_amount = compute_tax(deps, &coin)?;
+    Ok(Coin {
+        denom: coin.denom,
+        amount: (coin.amount - tax_amount)?,
+    })
+}
+
+// Mints a transfer of the wrapped asset at `contract_addr`. Transfers with a payload are minted to
+// the bridge and sent to the target contract together with the payload.
+fn wrapped_transfer_messages(
+    env: &Env,
+    contract_addr: HumanAddr,
+    transfer: WrappedTransfer,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages = match transfer.payload {
+        Some(payload) => vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.clone(),
+                msg: to_binary(&WrappedMsg::Mint {
+                    recipient: env.contract.address.clone(),
+                    amount: transfer.amount,
+                })?,
+                send: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.clone(),
+                msg: to_binary(&WrappedMsg::Send {
+                    contract: transfer.recipient,
+                    amount: transfer.amount,
+                    msg: Some(payload),
+                })?,
+                send: vec![],
+            }),
+        ],
+        None => vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.clone(),
+            msg: to_binary(&WrappedMsg::Mint {
+                recipient: transfer.recipient,
+                amount: transfer.amount,
+            })?,
+            send: vec![],
+        })],
+    };
+
+    if transfer.relayer_fee.u128() != 0 {
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg: to_binary(&WrappedMsg::Mint {
+                recipient: transfer.relayer,
+                amount: transfer.relayer_fee,
+            })?,
+            send: vec![],
+        }));
+    }
+
+    Ok(messages)
+}
+
 fn handle_lock_assets<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
@@ -419,6 +818,7 @@ fn handle_lock_assets<S: Storage, A: Api, Q: Querier>(
     recipient: &[u8],
     target_chain: u8,
     nonce: u32,
+    fee: Uint128,
 ) -> StdResult<HandleResponse> {
     if target_chain == CHAIN_ID {
         return ContractError::SameSourceAndTarget.std_err();
@@ -428,6 +828,10 @@ fn handle_lock_assets<S: Storage, A: Api, Q: Querier>(
         return ContractError::AmountTooLow.std_err();
     }
 
+    if fee > amount {
+        return ContractError::FeeTooHigh.std_err();
+    }
+
     let state = config_read(&deps.storage).load()?;
     if !state.is_active {
         return ContractError::ContractInactive.std_err();
@@ -485,6 +889,15 @@ fn handle_lock_assets<S: Storage, A: Api, Q: Querier>(
         }
     };
 
+    account_outbound_transfer(
+        &mut deps.storage,
+        &build_asset_id(asset_chain, &asset_address),
+        amount.u128(),
+        env.block.time,
+    )?;
+
+    let sequence = next_sequence(&mut deps.storage)?;
+
     Ok(HandleResponse {
         messages,
         log: vec![
@@ -500,7 +913,80 @@ fn handle_lock_assets<S: Storage, A: Api, Q: Querier>(
             ),
             log("locked.recipient", hex::encode(recipient)),
             log("locked.amount", amount),
+            log("locked.fee", fee),
+            log("locked.nonce", nonce),
+            log("locked.sequence", sequence),
+            log("locked.block_time", env.block.time),
+        ],
+        data: None,
+    })
+}
+
+/// Lock native coins sent along with the message, they are held by this contract until released
+/// by a transfer back to Terra
+fn handle_lock_native_assets<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    recipient: &[u8],
+    target_chain: u8,
+    nonce: u32,
+    fee: Uint128,
+) -> StdResult<HandleResponse> {
+    if target_chain == CHAIN_ID {
+        return ContractError::SameSourceAndTarget.std_err();
+    }
+
+    if env.message.sent_funds.len() != 1 {
+        return ContractError::InvalidNativeFunds.std_err();
+    }
+    let coin = &env.message.sent_funds[0];
+
+    if coin.amount.is_zero() {
+        return ContractError::AmountTooLow.std_err();
+    }
+
+    if fee > coin.amount {
+        return ContractError::FeeTooHigh.std_err();
+    }
+
+    let state = config_read(&deps.storage).load()?;
+    if !state.is_active {
+        return ContractError::ContractInactive.std_err();
+    }
+
+    let asset_address = match extend_denom_to_32(&coin.denom) {
+        Some(address) => address,
+        None => return ContractError::InvalidNativeDenom.std_err(),
+    };
+
+    account_outbound_transfer(
+        &mut deps.storage,
+        &build_asset_id(CHAIN_ID, &asset_address),
+        coin.amount.u128(),
+        env.block.time,
+    )?;
+
+    let sequence = next_sequence(&mut deps.storage)?;
+
+    // Funds are already transferred to the contract with the message
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("locked.target_chain", target_chain),
+            log("locked.token_chain", CHAIN_ID),
+            log("locked.token_decimals", NATIVE_DENOM_DECIMALS),
+            log("locked.token", hex::encode(asset_address)),
+            log(
+                "locked.sender",
+                hex::encode(extend_address_to_32(
+                    &deps.api.canonical_address(&env.message.sender)?,
+                )),
+            ),
+            log("locked.recipient", hex::encode(recipient)),
+            log("locked.amount", coin.amount),
+            log("locked.fee", fee),
             log("locked.nonce", nonce),
+            log("locked.sequence", sequence),
             log("locked.block_time", env.block.time),
         ],
         data: None,
@@ -529,15 +1015,196 @@ pub fn handle_set_active<S: Storage, A: Api, Q: Querier>(
     })
 }
 
-pub fn query<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
-    msg: QueryMsg,
-) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GuardianSetInfo {} => to_binary(&query_guardian_set_info(deps)?),
-        QueryMsg::WrappedRegistry { chain, address } => {
-            to_binary(&query_wrapped_registry(deps, chain, address.as_slice())?)
-        }
+pub fn handle_set_transfer_limit<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    asset_chain: u8,
+    asset_address: &[u8],
+    limit: Option<TransferLimitInfo>,
+) -> StdResult<HandleResponse> {
+    let state = config_read(&deps.storage).load()?;
+
+    if deps.api.canonical_address(&env.message.sender)? != state.owner {
+        return ContractError::PermissionDenied.std_err();
+    }
+
+    let asset_id = build_asset_id(asset_chain, asset_address);
+    match limit {
+        Some(limit) => transfer_limit(&mut deps.storage).save(&asset_id, &limit)?,
+        None => transfer_limit(&mut deps.storage).remove(&asset_id),
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "set_transfer_limit"),
+            log("asset_chain", asset_chain),
+            log("asset_address", hex::encode(asset_address)),
+        ],
+        data: None,
+    })
+}
+
+/// Release a queued inbound transfer once its delay has passed, anyone can submit this
+fn handle_release_pending_transfer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    hash: &[u8],
+) -> StdResult<HandleResponse> {
+    let state = config_read(&deps.storage).load()?;
+    if !state.is_active {
+        return ContractError::ContractInactive.std_err();
+    }
+
+    let pending = pending_transfer_read(&deps.storage)
+        .load(hash)
+        .or_else(|_| ContractError::PendingTransferNotFound.std_err())?;
+    if env.block.time < pending.release_time {
+        return ContractError::PendingTransferLocked.std_err();
+    }
+    pending_transfer(&mut deps.storage).remove(hash);
+
+    let mut v = BodyTransfer::deserialize(&mut Reader::new(pending.payload.as_slice()))
+        .or_else(|_| ContractError::CannotParseVAA.std_err())?;
+    v.fee = U256::from(pending.relayer_fee.u128());
+    let amount = v.amount.as_u128();
+
+    // Released transfers still count towards the window
+    let asset_id = build_asset_id(v.asset.chain, &v.asset.address);
+    let mut window = transfer_window_read(&deps.storage)
+        .may_load(&asset_id)?
+        .unwrap_or_default();
+    window.inbound.add(env.block.time, amount);
+    transfer_window(&mut deps.storage).save(&asset_id, &window)?;
+
+    // The fee belongs to whoever relayed the VAA, not to whoever happens to release it
+    let relayer = pending
+        .relayer
+        .unwrap_or_else(|| env.message.sender.clone());
+    release_transfer(
+        deps,
+        env,
+        &v,
+        amount,
+        pending.message.as_ref().map(|m| m.as_slice()),
+        relayer,
+    )
+}
+
+/// Drop a queued inbound transfer, e.g. when it was signed by a compromised guardian set. The VAA
+/// is removed from the archive, the transfer is not lost and its VAA can be submitted again.
+fn handle_cancel_pending_transfer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    hash: &[u8],
+) -> StdResult<HandleResponse> {
+    let state = config_read(&deps.storage).load()?;
+
+    if deps.api.canonical_address(&env.message.sender)? != state.owner {
+        return ContractError::PermissionDenied.std_err();
+    }
+
+    if pending_transfer_read(&deps.storage).load(hash).is_err() {
+        return ContractError::PendingTransferNotFound.std_err();
+    }
+    pending_transfer(&mut deps.storage).remove(hash);
+    vaa_archive_remove(&mut deps.storage, hash);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![
+            log("action", "transfer_cancelled"),
+            log("hash", hex::encode(hash)),
+        ],
+        data: None,
+    })
+}
+
+// Assigns the next lockup sequence of the bridge
+fn next_sequence<S: Storage>(storage: &mut S) -> StdResult<u64> {
+    let mut state = config_read(storage).load()?;
+    let sequence = state.sequence;
+    state.sequence += 1;
+    config(storage).save(&state)?;
+    Ok(sequence)
+}
+
+// Returns the release delay if an inbound transfer exceeds the limits of its asset,
+// otherwise accounts for the transfer in the window
+fn inbound_transfer_delay<S: Storage>(
+    storage: &mut S,
+    asset_id: &[u8],
+    amount: u128,
+    now: u64,
+) -> StdResult<Option<u64>> {
+    let limit = match transfer_limit_read(storage).may_load(asset_id)? {
+        Some(limit) => limit,
+        None => return Ok(None),
+    };
+
+    let mut window = transfer_window_read(storage)
+        .may_load(asset_id)?
+        .unwrap_or_default();
+    if amount > limit.single_transfer.u128()
+        || window.inbound.used(now).saturating_add(amount) > limit.daily.u128()
+    {
+        return Ok(Some(limit.release_delay));
+    }
+
+    window.inbound.add(now, amount);
+    transfer_window(storage).save(asset_id, &window)?;
+    Ok(None)
+}
+
+// Rejects a lock that exceeds the limits of its asset, otherwise accounts for it in the window
+fn account_outbound_transfer<S: Storage>(
+    storage: &mut S,
+    asset_id: &[u8],
+    amount: u128,
+    now: u64,
+) -> StdResult<()> {
+    let limit = match transfer_limit_read(storage).may_load(asset_id)? {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+
+    if amount > limit.single_transfer.u128() {
+        return ContractError::TransferLimitExceeded.std_err();
+    }
+    let mut window = transfer_window_read(storage)
+        .may_load(asset_id)?
+        .unwrap_or_default();
+    if window.outbound.used(now).saturating_add(amount) > limit.daily.u128() {
+        return ContractError::DailyLimitExceeded.std_err();
+    }
+
+    window.outbound.add(now, amount);
+    transfer_window(storage).save(asset_id, &window)
+}
+
+pub fn query<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    msg: QueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GuardianSetInfo {} => to_binary(&query_guardian_set_info(deps)?),
+        QueryMsg::WrappedRegistry { chain, address } => {
+            to_binary(&query_wrapped_registry(deps, chain, address.as_slice())?)
+        }
+        QueryMsg::VaaStatus { hash } => to_binary(&query_vaa_status(deps, hash.as_slice())?),
+        QueryMsg::TransferCapacity {
+            asset_chain,
+            asset_address,
+            block_time,
+        } => to_binary(&query_transfer_capacity(
+            deps,
+            asset_chain,
+            asset_address.as_slice(),
+            block_time,
+        )?),
+        QueryMsg::PendingTransfer { hash } => {
+            to_binary(&query_pending_transfer(deps, hash.as_slice())?)
+        }
     }
 }
 
@@ -566,29 +1233,70 @@ pub fn query_wrapped_registry<S: Storage, A: Api, Q: Querier>(
     }
 }
 
-fn keys_equal(a: &VerifyKey, b: &GuardianAddress) -> bool {
-    let mut hasher = Keccak256::new();
+pub fn query_vaa_status<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    hash: &[u8],
+) -> StdResult<VaaStatusResponse> {
+    Ok(match vaa_archive_get(&deps.storage, hash) {
+        Some(info) => VaaStatusResponse {
+            executed: true,
+            block_time: info.block_time,
+        },
+        None => VaaStatusResponse {
+            executed: false,
+            block_time: None,
+        },
+    })
+}
 
-    let point: EncodedPoint = EncodedPoint::from(a);
-    let point = point.decompress();
-    if bool::from(point.is_none()) {
-        return false;
-    }
-    let point = point.unwrap();
+pub fn query_transfer_capacity<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    asset_chain: u8,
+    asset_address: &[u8],
+    now: u64,
+) -> StdResult<TransferCapacityResponse> {
+    let asset_id = build_asset_id(asset_chain, asset_address);
+    let limit = match transfer_limit_read(&deps.storage).may_load(&asset_id)? {
+        Some(limit) => limit,
+        None => {
+            return Ok(TransferCapacityResponse {
+                limit: None,
+                inbound_remaining: None,
+                outbound_remaining: None,
+            })
+        }
+    };
 
-    hasher.update(&point.as_bytes()[1..]);
-    let a = &hasher.finalize()[12..];
+    let window = transfer_window_read(&deps.storage)
+        .may_load(&asset_id)?
+        .unwrap_or_default();
+    let daily = limit.daily.u128();
+
+    Ok(TransferCapacityResponse {
+        inbound_remaining: Some(Uint128::from(
+            daily.saturating_sub(window.inbound.used(now)),
+        )),
+        outbound_remaining: Some(Uint128::from(
+            daily.saturating_sub(window.outbound.used(now)),
+        )),
+        limit: Some(limit),
+    })
+}
 
-    let b = &b.bytes;
-    if a.len() != b.len() {
-        return false;
-    }
-    for (ai, bi) in a.iter().zip(b.as_slice().iter()) {
-        if ai != bi {
-            return false;
-        }
+pub fn query_pending_transfer<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    hash: &[u8],
+) -> StdResult<PendingTransferResponse> {
+    match pending_transfer_read(&deps.storage).load(hash) {
+        Ok(pending) => Ok(PendingTransferResponse {
+            payload: pending.payload,
+            message: pending.message,
+            relayer_fee: pending.relayer_fee,
+            relayer: pending.relayer,
+            release_time: pending.release_time,
+        }),
+        Err(_) => ContractError::PendingTransferNotFound.std_err(),
     }
-    true
 }
 
 fn build_asset_id(chain: u8, address: &[u8]) -> Vec<u8> {
@@ -604,10 +1312,16 @@ fn build_asset_id(chain: u8, address: &[u8]) -> Vec<u8> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::GuardianSetInfo;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, MOCK_CONTRACT_ADDR};
-    use cosmwasm_std::{HumanAddr, QuerierResult};
+    use crate::state::{
+        GuardianSetInfo, DEFAULT_VAA_EXPIRATION_TIME, GUARDIAN_SET_KEY, MAX_LEN_GUARDIAN_KEYS,
+    };
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, MockApi, MockStorage, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{coin, coins, HumanAddr, QuerierResult, SystemError};
+    use cosmwasm_storage::{bucket, bucket_read};
     use serde_json;
+    use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper};
 
     // Constants generated by bridge/cmd/vaa-test-terra/main.go
     const ADDR_1: &str = "beFA429d57cD18b7F8A4d91A2da9AB4AF05d0FBe";
@@ -631,6 +1345,46 @@ mod tests {
     const VAA_VALID_GUARDIAN_SET_CHANGE_TO_6: &str = "01000000000100a5defbd912ef327d07afff71e0da9c2e2a13e5516255c62e249a6761afe2465c7b6fc1032451559551e76eb4a029474fd791b2250c4fd40a8b3f5d4f5f58e5a30000000fa0010000000106befa429d57cd18b7f8a4d91a2da9ab4af05d0fbee06a9adfeb38a8ee4d00e89307c016d0749679bd8575df9b3c97b4e267deb92d93137844a97a01320427cda59902dc6eb0c1bd2b6d38f87c5552b348bfea822f75c42e1764c791b8fe04a7b10ddb38572f5fe0b158147e7260f14062556afc94eece55ff";
     const VAA_VALID_TRANSFER_5_SIGS_GS_1: &str = "01000000010500027eb7e87a9d0ab91ec53bb073c0f0acf189900139daa652666fd4cfe32a4ee42383c1a66e3a397c2de8ae485225357feb52f665952b1e384ef6dfcea1ba9f920001cfcacfad444ac3202f8f0d2252c69ee90d18c9105f7be3b5d361b7fcb0fbf7fa7287bac5de9cb02f86a28fdd7f24015991020431b0048aa3bbb29daed625e416000372f6c239ddeccded04a95a0cf0bfefe6e168148f1fe3b93e797eb2e74e098b890f2be341dd0f3c8172c2050154407cfdd1ea7bd6cce0b31f020ec7530ffb6109000449c025fe0630268983d57c4bd1546497788f810e427b6fd436cb1f048152375e1063422b4d1cc668a0612814c550ea7e3d1aa93404a0b6e089d210d4c937023a000548bf474fb350d5e482378c37404fb4d1421e262d13ebf6b11977214c789a246a6c278a522a9be4beba008f3d481b1ee35c5b0559bef474eb34b9e3e681947c230100000fa01000000039010302010500000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a7640000";
 
+    // Signed by a test guardian with private key 0x1111..11
+    const ADDR_TEST: &str = "19e7e376e7c213b7e7e7e46cc70a5dd086daff2a";
+    // Changes to guardian set 1 with the 25 guardians 0x0101..01 to 0x1919..19
+    const VAA_GUARDIAN_SET_CHANGE_TO_25: &str = "01000000000100d6ecd2b91cef23c53c2053d29edf6047da283731ff8cc0af6f8cb20a48f8837140a028506ababa299bf57a9b3ad848305598d7ff844c1bfd1700b32f0c47f12600000007d00100000001190101010101010101010101010101010101010101020202020202020202020202020202020202020203030303030303030303030303030303030303030404040404040404040404040404040404040404050505050505050505050505050505050505050506060606060606060606060606060606060606060707070707070707070707070707070707070707080808080808080808080808080808080808080809090909090909090909090909090909090909090a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0d0d0d0d0d0d0d0d0d0d0d0d0d0d0d0d0d0d0d0d0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0e0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f1010101010101010101010101010101010101010111111111111111111111111111111111111111112121212121212121212121212121212121212121313131313131313131313131313131313131313141414141414141414141414141414141414141415151515151515151515151515151515151515151616161616161616161616161616161616161616171717171717171717171717171717171717171718181818181818181818181818181818181818181919191919191919191919191919191919191919";
+    // Changes to guardians ADDR_1, ADDR_2 and ADDR_3 of which 2 have to sign
+    const VAA_GUARDIAN_SET_CHANGE_THRESHOLD: &str = "010000000001005e885ee4dcd4711a70e6cbd19ec0811e727bd9e233b9eeddc39ba63aa46d379d249f4c375706e840017e40bd52e90c8dba780931cc4efac346dc626ab140bedb01000007d0030000000103befa429d57cd18b7f8a4d91a2da9ab4af05d0fbee06a9adfeb38a8ee4d00e89307c016d0749679bd8575df9b3c97b4e267deb92d93137844a97a013202";
+    const VAA_GUARDIAN_SET_CHANGE_TO_EMPTY: &str = "010000000001009b67c11fdf150cb29a6d1185b74fa9ac4941fbf1654eaba18d5387d0144e873045ba60958643fca43bbe46d61fa139c8c1649474868d5fced9e8b0d037bfaabd01000007d0010000000100";
+    // Releases 1000000uusd to "recipient"
+    const VAA_VALID_NATIVE_TRANSFER: &str = "01000000000100116703f4a71007fa1d21b777fedc8e50be29763ed3a2667359623afb6229b9812392c3dc8c8fb8dc6da9eb3211304af2c3d7c3560c0dcc694e23a8a0b8e8e5eb00000007d0100000000101030000000000000000000000000102030405060708090001020304050607080900000000000000000000000000726563697069656e7400000000000000000000000301000000000000000000000000000000000000000000000000000000757573640600000000000000000000000000000000000000000000000000000000000f4240";
+    // Same transfer timestamped exactly DEFAULT_VAA_EXPIRATION_TIME before unix_timestamp()
+    const VAA_NATIVE_TRANSFER_UNEXPIRED: &str = "010000000001008984505319e04f81d781056549b8486f23a8848e8db065c16ded6ee960e5ff6a0cc1f6821ea124e2963b467c82e0cfa42c997dd5e273a36a7e5c84a6eff6add9015fdb2a1f100000000101030000000000000000000000000102030405060708090001020304050607080900000000000000000000000000726563697069656e7400000000000000000000000301000000000000000000000000000000000000000000000000000000757573640600000000000000000000000000000000000000000000000000000000000f4240";
+    // Same transfer with a relayer fee of 1000 uusd
+    const VAA_NATIVE_TRANSFER_RELAYER_FEE: &str = "01000000000100032f29abcbda487465325f5a3b6272623e8dddd9e94becf84d443f7c813b3a1f7a688728c34e22c0ca51749e88fef6fabdb5ea244fc9fcc7f34428aceeadf49600000007d0140000000101030000000000000000000000000102030405060708090001020304050607080900000000000000000000000000726563697069656e7400000000000000000000000301000000000000000000000000000000000000000000000000000000757573640600000000000000000000000000000000000000000000000000000000000f424000000000000000000000000000000000000000000000000000000000000003e8";
+    // Native transfer of 1000000uusd to the contract "recipient" with the payload {"swap":{}}
+    const VAA_NATIVE_TRANSFER_WITH_PAYLOAD: &str = "010000000001001e8f4df103a52a72675a56003862a6135ab9b4df2d499f3599aa316cd365250b266e35b9b45b9db8f0fd163df426a956d69c95a93957cf44b59b66865046b5bc00000007d0130000000101030000000000000000000000000102030405060708090001020304050607080900000000000000000000000000726563697069656e7400000000000000000000000301000000000000000000000000000000000000000000000000000000757573640600000000000000000000000000000000000000000000000000000000000f42400000000000000000000000000000000000000000000000000000000000000000000b7b2273776170223a7b7d7d";
+    // Native transfers with payload that try to mint or transfer 1000000 tokens to "attacker", sent
+    // to the wrapped asset "wrappedasset" and to the custody token LOCK_ASSET_ADDR
+    const VAA_NATIVE_PAYLOAD_WRAPPED_MINT: &str = "01000000000100971aaaa6d9308d929065ff003bbcc904f20eb4cbcfb3104b84b13c207c3055dc7a4b4b7d1199f66cf4bc18f65adfb28dc3d6ccfe318549b5054e13817b77662101000007d013000000010103000000000000000000000000010203040506070809000102030405060708090000000000000000000000000077726170706564617373657400000000000000000301000000000000000000000000000000000000000000000000000000757573640600000000000000000000000000000000000000000000000000000000000f4240000000000000000000000000000000000000000000000000000000000000000000347b226d696e74223a7b22726563697069656e74223a2261747461636b6572222c22616d6f756e74223a2231303030303030227d7d";
+    const VAA_NATIVE_PAYLOAD_WRAPPED_TRANSFER: &str = "010000000001004cdc61ed390fab93a4e1df4991df5c65d64aaf523df8cb33fff3f512ae7b1f832978d19ca78538fb3fd2d14b16514d3cb2d70e73b3ebdcb4357a650f609edfe600000007d013000000010103000000000000000000000000010203040506070809000102030405060708090000000000000000000000000077726170706564617373657400000000000000000301000000000000000000000000000000000000000000000000000000757573640600000000000000000000000000000000000000000000000000000000000f4240000000000000000000000000000000000000000000000000000000000000000000387b227472616e73666572223a7b22726563697069656e74223a2261747461636b6572222c22616d6f756e74223a2231303030303030227d7d";
+    const VAA_NATIVE_PAYLOAD_CUSTODY_MINT: &str = "01000000000100881f553ed1d2b8c80fb64003746b29a2d21c27faeeadd55c10068ce03d40192f02382c42f81e28d28631f2189cab8df9b6dd3d90e8be520483d92169f919644e01000007d01300000001010300000000000000000000000001020304050607080900010203040506070809000000000000000000000000006c6f636b617373657461646472000000000000000301000000000000000000000000000000000000000000000000000000757573640600000000000000000000000000000000000000000000000000000000000f4240000000000000000000000000000000000000000000000000000000000000000000347b226d696e74223a7b22726563697069656e74223a2261747461636b6572222c22616d6f756e74223a2231303030303030227d7d";
+    const VAA_NATIVE_PAYLOAD_CUSTODY_TRANSFER: &str = "01000000000100c68739ff45c9f965ad595b6e3e3a74976a44e6b7f035cfcce8e4795e877643aa38042efa140dab504590f6d8838801cf57460c50075801c41375ec7751f615ff01000007d01300000001010300000000000000000000000001020304050607080900010203040506070809000000000000000000000000006c6f636b617373657461646472000000000000000301000000000000000000000000000000000000000000000000000000757573640600000000000000000000000000000000000000000000000000000000000f4240000000000000000000000000000000000000000000000000000000000000000000387b227472616e73666572223a7b22726563697069656e74223a2261747461636b6572222c22616d6f756e74223a2231303030303030227d7d";
+    const VAA_GOVERNANCE_CODE_ID: &str = "010000000001005f3d3600d6ef6056be2e10ac5ac29ee87a778b37982259e0349791a4e571868d02510fb53e3fdcb7ff6cb4eb1c1c1c3a58b9d2bfb079339e555bb8965bf068b10100000bb802030100000000000003e8";
+    const VAA_GOVERNANCE_EXPIRITY: &str = "0100000000010075a2032bb1f0f50a4521382132102992ad0a888f9ce6e0b76cac30e94300193f659416066df93b9426c95247f99956d84ac48c9272873f5032927e7e02d144160000000bb80203020000000000000064";
+    // Sets the VAA expiration time to DEFAULT_VAA_EXPIRATION_TIME
+    const VAA_GOVERNANCE_VAA_EXPIRATION: &str = "0100000000010064cdcbdc26f559de7e2a9c683248e117ab1dd40899ca11d27d234399518cf69c6e9a93a97b1bec2fc97601c67fa305b09de76361a8c4c18e93ffec4b01f4b5560000000bb80203090000000000093a80";
+    const VAA_GOVERNANCE_OWNER: &str = "010000000001007560490c5a3adeae9ee0407d57af14999e721a5d3445f1657f3c3dafa54155404732f4d3f6c67cb7dbaf6d5ef99475b067e6a6c3dc2663c0da214a669834a0840000000bb80203030000000000000000000000006e65776f776e6572000000000000000000000000";
+    const VAA_GOVERNANCE_PAUSE: &str = "01000000000100f993754ad118d11d42f11426785d581fb8f5dc6e1b84a9ff1fcf004d4d776cdb3b7983b31465868ddf71dbc648a458ce253a8f462f6a09bfa433424c2e535f460000000bb802030400";
+    const VAA_GOVERNANCE_UNPAUSE: &str = "01000000000100992b9a0913fb1a15568a24691f0dda331ebc442b336ce7533de6ea990c2f6e230b64197cfca94157f5d0fd45b2b24d1d9aed2828aa4f7d9d09aeba4dc9a5f6a30100000bb902030401";
+    const VAA_GOVERNANCE_WRONG_CHAIN: &str = "01000000000100cc8feb2225cd15c46b98b1a40d07820c4e9bd223319cad1ac93e5cdcca9c92e3076afc0df7205bb6cc01c69c31f8da329f6a66cbb83efbc5520f9dd7ae4f2eca0100000bb802010400";
+    // Governance change of the unknown kind 0x7f
+    const VAA_GOVERNANCE_UNKNOWN: &str = "010000000001002527e189225d0d804f75673edbae266674b983d984e5b59f6a53764860c2fcb811dfa941fefeba37c558fdf7dce2282034ff41868cab73bcb12a74fc4af67f3e0100000bba02037f010203";
+    // Metadata and transfer of the asset of VAA_VALID_TRANSFER_1_SIG
+    const VAA_ASSET_METADATA: &str = "0100000000010077ffb4f6337a6ad081c349a9f5b1360d24d59709f9d748e97f2ac315d3b91b0d11d013a438c65e90912109521d08f379679f803b77ea379e0e3f33da9587a9ed0000000fa011010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e99880857455448000000000000000000000000000000000000000000000000000000005772617070656420457468657200000000000000000000000000000000000000";
+    const VAA_ASSET_METADATA_UPDATE: &str = "01000000000100989a717c7886887adada04eaa469ee1363b71e4903df9a558576364d1ef4a4271968b8662e404856935476ce6b6687e2aceb36bda06b329f1a2425a19fa79801010000138811010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e998808774554480000000000000000000000000000000000000000000000000000000045746865722028576f726d686f6c652900000000000000000000000000000000";
+    const VAA_ASSET_METADATA_TRANSFER: &str = "0100000000010040f4741ec17ff9901a496351ba35e96d1a775a68661b6226de51e384d642b12f2073222b054fb16617b0a453f4b776e5bfea832f2eb308753c25721dae3edcec01000007d01000000038010302010400000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a7640000";
+    // Transfer of the same asset with the payload {"swap":{}}
+    const VAA_TRANSFER_WITH_PAYLOAD: &str = "01000000000100e2a867ab7a66ab1149e28c9abde7d8fb43e7b5c4fc37d48f365c5ad89e7ac3f0198a7f237f65c87bc89ea4121b8f4f9fd9e750f5522e0948446fafb3430a9cd100000007d01300000038010302010400000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a76400000000000000000000000000000000000000000000000000000000000000000000000b7b2273776170223a7b7d7d";
+    // Wrapped asset transfer of VAA_VALID_TRANSFER_1_SIG with a relayer fee of 1000
+    const VAA_TRANSFER_RELAYER_FEE: &str = "01000000000100b5e86487c23a59825283b8c97b8caab97a9796a7f989c44cbe1315402bcbbb68743b0ca087b603ba1ca1e4f915e52d49afb42382a812619cc80ff7774a1c380f01000007d01400000038010302010400000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a764000000000000000000000000000000000000000000000000000000000000000003e8";
+
     const CANONICAL_LENGTH: usize = 20;
 
     const CREATOR_ADDR: &str = "creator";
@@ -660,17 +1414,37 @@ mod tests {
         do_init(deps, &ALL_GUARDIANS[..number_of_guardians], expiration_time);
     }
 
+    fn do_init_with_test_guardian<S: Storage, A: Api, Q: Querier>(deps: &mut Extern<S, A, Q>) {
+        do_init(
+            deps,
+            &vec![GuardianAddress::from(ADDR_TEST)],
+            unix_timestamp() + 1000,
+        );
+    }
+
     fn do_init<S: Storage, A: Api, Q: Querier>(
         deps: &mut Extern<S, A, Q>,
         guardians: &[GuardianAddress],
         expiration_time: u64,
+    ) {
+        do_init_with_threshold(deps, guardians, expiration_time, None);
+    }
+
+    fn do_init_with_threshold<S: Storage, A: Api, Q: Querier>(
+        deps: &mut Extern<S, A, Q>,
+        guardians: &[GuardianAddress],
+        expiration_time: u64,
+        threshold: Option<u8>,
     ) {
         let init_msg = InitMsg {
             initial_guardian_set: GuardianSetInfo {
                 addresses: guardians.to_vec(),
                 expiration_time,
+                threshold,
             },
             guardian_set_expirity: 50,
+            // Test VAAs are timestamped shortly after the epoch
+            vaa_expiration_time: unix_timestamp(),
             wrapped_asset_code_id: 999,
         };
         let mut env = mock_env(&HumanAddr::from(CREATOR_ADDR), &[]);
@@ -692,7 +1466,16 @@ mod tests {
         msg: HandleMsg,
         sender: &HumanAddr,
     ) -> StdResult<HandleResponse> {
-        let mut env = mock_env(sender, &[]);
+        submit_msg_with_funds(deps, msg, sender, &[])
+    }
+
+    fn submit_msg_with_funds<S: Storage, A: Api, Q: Querier>(
+        deps: &mut Extern<S, A, Q>,
+        msg: HandleMsg,
+        sender: &HumanAddr,
+        funds: &[Coin],
+    ) -> StdResult<HandleResponse> {
+        let mut env = mock_env(sender, funds);
         env.block.time = unix_timestamp();
 
         handle(deps, env, msg)
@@ -744,6 +1527,174 @@ mod tests {
         }
     }
 
+    #[test]
+    fn valid_vaa_asset_metadata() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+
+        // Metadata of an asset that is not deployed yet is stored for its instantiation
+        let result = submit_vaa(&mut deps, VAA_ASSET_METADATA).unwrap();
+        assert_eq!(0, result.messages.len());
+        assert_eq!(result.log[2], log("name", "Wrapped Ether"));
+        assert_eq!(result.log[3], log("symbol", "WETH"));
+
+        let messages = submit_vaa(&mut deps, VAA_ASSET_METADATA_TRANSFER)
+            .unwrap()
+            .messages;
+        match &messages[0] {
+            CosmosMsg::Wasm(WasmMsg::Instantiate { msg, .. }) => {
+                let init: WrappedInit = serde_json::from_slice(msg.as_slice()).unwrap();
+                assert_eq!(init.name, "Wrapped Ether");
+                assert_eq!(init.symbol, "WETH");
+            }
+            _ => panic!("Wrong message type"),
+        }
+
+        // Metadata of a deployed asset is pushed to the wrapped asset contract
+        let mut address = [0u8; 32];
+        address[12..]
+            .copy_from_slice(&hex::decode("0347ef34687bdc9f189e87a9200658d9c40e9988").unwrap());
+        let register_msg = HandleMsg::RegisterAssetHook {
+            asset_id: build_asset_id(1, &address).into(),
+            transfer: None,
+        };
+        submit_msg_with_sender(&mut deps, register_msg, &HumanAddr::from("wrapped")).unwrap();
+
+        let messages = submit_vaa(&mut deps, VAA_ASSET_METADATA_UPDATE)
+            .unwrap()
+            .messages;
+        assert_eq!(
+            messages,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: HumanAddr::from("wrapped"),
+                msg: to_binary(&WrappedMsg::UpdateMetadata {
+                    name: "Ether (Wormhole)".to_string(),
+                    symbol: "wETH".to_string(),
+                })
+                .unwrap(),
+                send: vec![],
+            })]
+        );
+    }
+
+    // Executes the registration hook of the wrapped asset instantiated by `messages` as "wrapped"
+    fn register_instantiated_asset<S: Storage, A: Api, Q: Querier>(
+        deps: &mut Extern<S, A, Q>,
+        messages: &[CosmosMsg],
+    ) -> StdResult<HandleResponse> {
+        match messages {
+            [CosmosMsg::Wasm(WasmMsg::Instantiate { msg, .. })] => {
+                let init: WrappedInit = serde_json::from_slice(msg.as_slice()).unwrap();
+                // Nothing is minted before the asset is registered
+                assert_eq!(init.mint, None);
+                let hook = init.init_hook.unwrap();
+                assert_eq!(hook.contract_addr, HumanAddr::from(MOCK_CONTRACT_ADDR));
+                let register_msg: HandleMsg = serde_json::from_slice(hook.msg.as_slice()).unwrap();
+                submit_msg_with_sender(deps, register_msg, &HumanAddr::from("wrapped"))
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn valid_vaa_transfer_with_payload() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+
+        // The asset is deployed first, the payload is delivered once it is registered
+        let messages = submit_vaa(&mut deps, VAA_TRANSFER_WITH_PAYLOAD)
+            .unwrap()
+            .messages;
+        let messages = register_instantiated_asset(&mut deps, &messages)
+            .unwrap()
+            .messages;
+
+        // The tokens are minted to the bridge and sent to the target contract with the payload
+        let target = deps
+            .api
+            .human_address(&CanonicalAddr::from(
+                hex::decode("0001020304050607080900010203040506070809").unwrap(),
+            ))
+            .unwrap();
+        let amount = Uint128::from(1_000_000_000_000_000_000u128);
+        assert_eq!(
+            messages,
+            vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: HumanAddr::from("wrapped"),
+                    msg: to_binary(&WrappedMsg::Mint {
+                        recipient: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                        amount,
+                    })
+                    .unwrap(),
+                    send: vec![],
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: HumanAddr::from("wrapped"),
+                    msg: to_binary(&WrappedMsg::Send {
+                        contract: target,
+                        amount,
+                        msg: Some(Binary::from(br#"{"swap":{}}"#.to_vec())),
+                    })
+                    .unwrap(),
+                    send: vec![],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn valid_vaa_transfer_relayer_fee_undeployed_asset() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+
+        // The first transfer of an asset deploys it, the fee is minted once it is registered
+        let messages = submit_vaa(&mut deps, VAA_TRANSFER_RELAYER_FEE)
+            .unwrap()
+            .messages;
+        let messages = register_instantiated_asset(&mut deps, &messages)
+            .unwrap()
+            .messages;
+
+        let target = deps
+            .api
+            .human_address(&CanonicalAddr::from(
+                hex::decode("0001020304050607080900010203040506070809").unwrap(),
+            ))
+            .unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: HumanAddr::from("wrapped"),
+                    msg: to_binary(&WrappedMsg::Mint {
+                        recipient: target,
+                        amount: Uint128::from(1_000_000_000_000_000_000u128 - 1000),
+                    })
+                    .unwrap(),
+                    send: vec![],
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: HumanAddr::from("wrapped"),
+                    msg: to_binary(&WrappedMsg::Mint {
+                        recipient: HumanAddr::from(SENDER_ADDR),
+                        amount: Uint128::from(1000u128),
+                    })
+                    .unwrap(),
+                    send: vec![],
+                }),
+            ]
+        );
+
+        let mut address = [0u8; 32];
+        address[12..]
+            .copy_from_slice(&hex::decode("0347ef34687bdc9f189e87a9200658d9c40e9988").unwrap());
+        assert_eq!(
+            query_wrapped_registry(&deps, 1, &address).unwrap().address,
+            HumanAddr::from("wrapped")
+        );
+    }
+
     #[test]
     fn valid_vaa_2_signatures() {
         let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
@@ -772,6 +1723,58 @@ mod tests {
         assert_eq!(e, ContractError::VaaAlreadyExecuted.std());
     }
 
+    #[test]
+    fn init_guardian_set_size() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let init_with = |deps: &mut Extern<_, _, _>, len: usize| {
+            let init_msg = InitMsg {
+                initial_guardian_set: GuardianSetInfo {
+                    addresses: vec![GuardianAddress::from(ADDR_TEST); len],
+                    expiration_time: 0,
+                    threshold: None,
+                },
+                guardian_set_expirity: 50,
+                vaa_expiration_time: DEFAULT_VAA_EXPIRATION_TIME,
+                wrapped_asset_code_id: 999,
+            };
+            init(
+                deps,
+                mock_env(&HumanAddr::from(CREATOR_ADDR), &[]),
+                init_msg,
+            )
+        };
+
+        assert_eq!(
+            init_with(&mut deps, 0).unwrap_err(),
+            ContractError::InvalidGuardianSetSize.std()
+        );
+        assert_eq!(
+            init_with(&mut deps, MAX_LEN_GUARDIAN_KEYS + 1).unwrap_err(),
+            ContractError::InvalidGuardianSetSize.std()
+        );
+        assert!(init_with(&mut deps, MAX_LEN_GUARDIAN_KEYS).is_ok());
+    }
+
+    #[test]
+    fn guardian_set_change_beyond_20() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+
+        assert_eq!(
+            submit_vaa(&mut deps, VAA_GUARDIAN_SET_CHANGE_TO_EMPTY).unwrap_err(),
+            ContractError::InvalidGuardianSetSize.std()
+        );
+
+        submit_vaa(&mut deps, VAA_GUARDIAN_SET_CHANGE_TO_25).unwrap();
+        let guardian_set_info = guardian_set_get(&deps.storage, 1).unwrap();
+        assert_eq!(guardian_set_info.addresses.len(), 25);
+        assert_eq!(
+            guardian_set_info.addresses[24],
+            GuardianAddress::from("1919191919191919191919191919191919191919")
+        );
+        assert_eq!(guardian_set_info.quorum(), 17);
+    }
+
     #[test]
     fn valid_vaa_guardian_set_change() {
         let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
@@ -793,7 +1796,8 @@ mod tests {
             guardian_set_info,
             GuardianSetInfo {
                 addresses: vec![GuardianAddress::from(ADDR_2)],
-                expiration_time: 0
+                expiration_time: 0,
+                threshold: None,
             }
         );
     }
@@ -832,6 +1836,51 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn valid_threshold_quorum() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let expiration_time = unix_timestamp() + 1000;
+        do_init_with_threshold(&mut deps, &ALL_GUARDIANS[..2], expiration_time, Some(1));
+
+        // A single signature is enough for a set with a threshold of 1
+        let result = submit_vaa(&mut deps, VAA_VALID_TRANSFER_1_SIG);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn guardian_set_change_threshold() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+
+        submit_vaa(&mut deps, VAA_GUARDIAN_SET_CHANGE_THRESHOLD).unwrap();
+        let guardian_set_info = guardian_set_get(&deps.storage, 1).unwrap();
+        assert_eq!(guardian_set_info.addresses.len(), 3);
+        assert_eq!(guardian_set_info.threshold, Some(2));
+        assert_eq!(guardian_set_info.quorum(), 2);
+    }
+
+    #[test]
+    fn init_guardian_set_threshold() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let init_msg = InitMsg {
+            initial_guardian_set: GuardianSetInfo {
+                addresses: ALL_GUARDIANS[..2].to_vec(),
+                expiration_time: 0,
+                threshold: Some(3),
+            },
+            guardian_set_expirity: 50,
+            vaa_expiration_time: DEFAULT_VAA_EXPIRATION_TIME,
+            wrapped_asset_code_id: 999,
+        };
+        let e = init(
+            &mut deps,
+            mock_env(&HumanAddr::from(CREATOR_ADDR), &[]),
+            init_msg,
+        )
+        .unwrap_err();
+        assert_eq!(e, ContractError::InvalidGuardianSetThreshold.std());
+    }
+
     #[test]
     fn error_vaa_wrong_guardian_index_order() {
         let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
@@ -937,6 +1986,28 @@ mod tests {
         assert_eq!(result, ContractError::WrongTargetChain.std_err());
     }
 
+    #[test]
+    fn error_vaa_truncated() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+
+        let result = submit_vaa(&mut deps, &VAA_VALID_TRANSFER_1_SIG[..200]);
+        assert_eq!(result, ContractError::InvalidVAALength.std_err());
+
+        let result = submit_vaa(&mut deps, "");
+        assert_eq!(result, ContractError::InvalidVAALength.std_err());
+    }
+
+    #[test]
+    fn error_vaa_trailing_bytes() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+
+        let vaa = format!("{}00", VAA_VALID_TRANSFER_1_SIG);
+        let result = submit_vaa(&mut deps, &vaa);
+        assert_eq!(result, ContractError::InvalidVAAPayloadLength.std_err());
+    }
+
     #[test]
     fn valid_transfer_after_guardian_set_change() {
         let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
@@ -972,6 +2043,7 @@ mod tests {
             recipient: Binary::from(hex::decode(LOCK_RECIPIENT).unwrap()),
             target_chain: LOCK_TARGET,
             nonce: LOCK_NONCE,
+            fee: None,
         };
     }
 
@@ -1038,7 +2110,9 @@ mod tests {
             ),
             log("locked.recipient", LOCK_RECIPIENT),
             log("locked.amount", LOCK_AMOUNT),
+            log("locked.fee", 0),
             log("locked.nonce", LOCK_NONCE),
+            log("locked.sequence", 0),
             log("locked.block_time", unix_timestamp()),
         ];
         assert_eq!(result.log, expected_logs);
@@ -1085,6 +2159,7 @@ mod tests {
 
         let register_msg = HandleMsg::RegisterAssetHook {
             asset_id: Binary::from(LOCK_ASSET_ID),
+            transfer: None,
         };
 
         let result = submit_msg_with_sender(
@@ -1107,7 +2182,9 @@ mod tests {
             ),
             log("locked.recipient", LOCK_RECIPIENT),
             log("locked.amount", LOCK_AMOUNT),
+            log("locked.fee", 0),
             log("locked.nonce", LOCK_NONCE),
+            log("locked.sequence", 0),
             log("locked.block_time", unix_timestamp()),
         ];
         assert_eq!(result.log, expected_logs);
@@ -1167,6 +2244,19 @@ mod tests {
         assert_eq!(result, ContractError::AmountTooLow.std_err());
     }
 
+    #[test]
+    fn error_lock_fee_too_high() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+
+        let mut msg = MSG_LOCK.clone();
+        if let HandleMsg::LockAssets { ref mut fee, .. } = msg {
+            *fee = Some(Uint128::from(LOCK_AMOUNT + 1));
+        }
+        let result = submit_msg(&mut deps, msg);
+        assert_eq!(result, ContractError::FeeTooHigh.std_err());
+    }
+
     #[test]
     fn error_lock_contract_inactive() {
         let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
@@ -1183,29 +2273,686 @@ mod tests {
         assert_eq!(result, ContractError::ContractInactive.std_err());
     }
 
-    #[test]
-    fn valid_set_active() {
-        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
-        do_init_with_guardians(&mut deps, 1);
+    const LOCK_NATIVE_DENOM: &str = "uusd";
+    const LOCK_NATIVE_DENOM_ADDRESS: &str =
+        "0100000000000000000000000000000000000000000000000000000075757364";
 
-        let result = submit_msg_with_sender(
-            &mut deps,
-            HandleMsg::SetActive { is_active: false },
-            &HumanAddr::from(CREATOR_ADDR),
-        );
-        assert!(result.is_ok());
+    lazy_static! {
+        static ref MSG_LOCK_NATIVE: HandleMsg = HandleMsg::LockNativeAssets {
+            recipient: Binary::from(hex::decode(LOCK_RECIPIENT).unwrap()),
+            target_chain: LOCK_TARGET,
+            nonce: LOCK_NONCE,
+            fee: None,
+        };
     }
 
     #[test]
-    fn error_set_active_not_owner() {
+    fn valid_lock_native_asset() {
         let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
         do_init_with_guardians(&mut deps, 1);
 
-        let result = submit_msg(&mut deps, HandleMsg::SetActive { is_active: false });
-        assert_eq!(result, ContractError::PermissionDenied.std_err());
-    }
+        let result = submit_msg_with_funds(
+            &mut deps,
+            MSG_LOCK_NATIVE.clone(),
+            &HumanAddr::from(SENDER_ADDR),
+            &coins(LOCK_AMOUNT, LOCK_NATIVE_DENOM),
+        )
+        .unwrap();
 
-    #[test]
+        let expected_logs = vec![
+            log("locked.target_chain", LOCK_TARGET),
+            log("locked.token_chain", CHAIN_ID),
+            log("locked.token_decimals", NATIVE_DENOM_DECIMALS),
+            log("locked.token", LOCK_NATIVE_DENOM_ADDRESS),
+            log(
+                "locked.sender",
+                format!("{}{}", ADDRESS_EXTENSION, SENDER_ADDR_HEX),
+            ),
+            log("locked.recipient", LOCK_RECIPIENT),
+            log("locked.amount", LOCK_AMOUNT),
+            log("locked.fee", 0),
+            log("locked.nonce", LOCK_NONCE),
+            log("locked.sequence", 0),
+            log("locked.block_time", unix_timestamp()),
+        ];
+        assert_eq!(result.log, expected_logs);
+        // Coins are already held by the contract
+        assert_eq!(result.messages.len(), 0);
+
+        // Every lock is assigned the next sequence
+        let result = submit_msg_with_funds(
+            &mut deps,
+            MSG_LOCK_NATIVE.clone(),
+            &HumanAddr::from(SENDER_ADDR),
+            &coins(LOCK_AMOUNT, LOCK_NATIVE_DENOM),
+        )
+        .unwrap();
+        assert!(result.log.contains(&log("locked.sequence", 1)));
+    }
+
+    #[test]
+    fn valid_lock_native_asset_with_fee() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+
+        let mut msg = MSG_LOCK_NATIVE.clone();
+        if let HandleMsg::LockNativeAssets { ref mut fee, .. } = msg {
+            *fee = Some(Uint128::from(LOCK_AMOUNT / 10));
+        }
+        let result = submit_msg_with_funds(
+            &mut deps,
+            msg.clone(),
+            &HumanAddr::from(SENDER_ADDR),
+            &coins(LOCK_AMOUNT, LOCK_NATIVE_DENOM),
+        )
+        .unwrap();
+        assert!(result.log.contains(&log("locked.amount", LOCK_AMOUNT)));
+        assert!(result.log.contains(&log("locked.fee", LOCK_AMOUNT / 10)));
+
+        // The fee is part of the locked coins, not paid on top of them
+        let result = submit_msg_with_funds(
+            &mut deps,
+            msg,
+            &HumanAddr::from(SENDER_ADDR),
+            &coins(LOCK_AMOUNT / 20, LOCK_NATIVE_DENOM),
+        );
+        assert_eq!(result, ContractError::FeeTooHigh.std_err());
+    }
+
+    #[test]
+    fn error_lock_native_invalid_funds() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+
+        let result = submit_msg(&mut deps, MSG_LOCK_NATIVE.clone());
+        assert_eq!(result, ContractError::InvalidNativeFunds.std_err());
+
+        let funds = vec![coin(LOCK_AMOUNT, "uluna"), coin(LOCK_AMOUNT, "uusd")];
+        let result = submit_msg_with_funds(
+            &mut deps,
+            MSG_LOCK_NATIVE.clone(),
+            &HumanAddr::from(SENDER_ADDR),
+            &funds,
+        );
+        assert_eq!(result, ContractError::InvalidNativeFunds.std_err());
+
+        let result = submit_msg_with_funds(
+            &mut deps,
+            MSG_LOCK_NATIVE.clone(),
+            &HumanAddr::from(SENDER_ADDR),
+            &coins(0, LOCK_NATIVE_DENOM),
+        );
+        assert_eq!(result, ContractError::AmountTooLow.std_err());
+
+        let result = submit_msg_with_funds(
+            &mut deps,
+            MSG_LOCK_NATIVE.clone(),
+            &HumanAddr::from(SENDER_ADDR),
+            &coins(LOCK_AMOUNT, &"u".repeat(32)),
+        );
+        assert_eq!(result, ContractError::InvalidNativeDenom.std_err());
+    }
+
+    // Answers the treasury queries of native denom releases
+    struct TaxQuerier {
+        rate: Decimal,
+        cap: Uint128,
+    }
+
+    impl Querier for TaxQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let query_request: QueryRequest<TerraQueryWrapper> =
+                serde_json::from_slice(bin_request).unwrap();
+            let query = match query_request {
+                QueryRequest::Custom(TerraQueryWrapper { query_data, .. }) => query_data,
+                // Targets of transfers with payload are not token contracts
+                QueryRequest::Wasm(WasmQuery::Smart { contract_addr, .. }) => {
+                    return Err(SystemError::NoSuchContract {
+                        addr: contract_addr,
+                    })
+                }
+                _ => panic!("Wrong request type"),
+            };
+            let response = match query {
+                TerraQuery::TaxRate {} => to_binary(&TaxRateResponse { rate: self.rate }),
+                TerraQuery::TaxCap { denom } => {
+                    assert_eq!(denom, LOCK_NATIVE_DENOM);
+                    to_binary(&TaxCapResponse { cap: self.cap })
+                }
+                _ => panic!("Wrong query type"),
+            };
+            Ok(Ok(response.unwrap()))
+        }
+    }
+
+    fn mock_dependencies_with_tax(rate: Decimal) -> Extern<MockStorage, MockApi, TaxQuerier> {
+        let deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        Extern {
+            storage: deps.storage,
+            api: deps.api,
+            querier: TaxQuerier {
+                rate,
+                cap: Uint128::from(1000000000u128),
+            },
+        }
+    }
+
+    #[test]
+    fn valid_vaa_native_transfer() {
+        let mut deps = mock_dependencies_with_tax(Decimal::zero());
+        do_init_with_test_guardian(&mut deps);
+
+        let messages = submit_vaa(&mut deps, VAA_VALID_NATIVE_TRANSFER)
+            .unwrap()
+            .messages;
+        assert_eq!(
+            messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                to_address: HumanAddr::from("recipient"),
+                amount: coins(1000000, LOCK_NATIVE_DENOM),
+            })]
+        );
+    }
+
+    #[test]
+    fn valid_vaa_native_transfer_relayer_fee() {
+        let mut deps = mock_dependencies_with_tax(Decimal::zero());
+        do_init_with_test_guardian(&mut deps);
+
+        let messages = submit_vaa(&mut deps, VAA_NATIVE_TRANSFER_RELAYER_FEE)
+            .unwrap()
+            .messages;
+        assert_eq!(
+            messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                    to_address: HumanAddr::from("recipient"),
+                    amount: coins(999000, LOCK_NATIVE_DENOM),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                    to_address: HumanAddr::from(SENDER_ADDR),
+                    amount: coins(1000, LOCK_NATIVE_DENOM),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn valid_vaa_native_transfer_tax_deducted() {
+        let mut deps = mock_dependencies_with_tax(Decimal::percent(1));
+        do_init_with_test_guardian(&mut deps);
+
+        let messages = submit_vaa(&mut deps, VAA_VALID_NATIVE_TRANSFER)
+            .unwrap()
+            .messages;
+        assert_eq!(
+            messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                to_address: HumanAddr::from("recipient"),
+                amount: coins(990099, LOCK_NATIVE_DENOM),
+            })]
+        );
+
+        // The relayer fee is taxed separately
+        let messages = submit_vaa(&mut deps, VAA_NATIVE_TRANSFER_RELAYER_FEE)
+            .unwrap()
+            .messages;
+        assert_eq!(
+            messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                    to_address: HumanAddr::from("recipient"),
+                    amount: coins(989108, LOCK_NATIVE_DENOM),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                    to_address: HumanAddr::from(SENDER_ADDR),
+                    amount: coins(990, LOCK_NATIVE_DENOM),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn valid_vaa_native_transfer_with_payload() {
+        let mut deps = mock_dependencies_with_tax(Decimal::zero());
+        do_init_with_test_guardian(&mut deps);
+
+        let messages = submit_vaa(&mut deps, VAA_NATIVE_TRANSFER_WITH_PAYLOAD)
+            .unwrap()
+            .messages;
+        let mut sender = vec![0u8; 12];
+        sender.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0]);
+        assert_eq!(
+            messages,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: HumanAddr::from("recipient"),
+                msg: to_binary(&ReceiverHandleMsg::ReceiveNative {
+                    source_chain: 1,
+                    sender: Binary::from(sender),
+                    payload: Binary::from(br#"{"swap":{}}"#.to_vec()),
+                })
+                .unwrap(),
+                send: coins(1000000, LOCK_NATIVE_DENOM),
+            })]
+        );
+    }
+
+    #[test]
+    fn error_vaa_native_payload_to_wrapped_asset() {
+        let mut deps = mock_dependencies_with_tax(Decimal::zero());
+        do_init_with_test_guardian(&mut deps);
+        let wrapped = deps
+            .api
+            .canonical_address(&HumanAddr::from("wrappedasset"))
+            .unwrap();
+        wrapped_asset_address(&mut deps.storage)
+            .save(wrapped.as_slice(), &vec![1u8; 32])
+            .unwrap();
+
+        // The bridge is a minter of wrapped assets, the payload must not be sent in its name
+        let result = submit_vaa(&mut deps, VAA_NATIVE_PAYLOAD_WRAPPED_MINT);
+        assert_eq!(result, ContractError::InvalidPayloadTarget.std_err());
+        let result = submit_vaa(&mut deps, VAA_NATIVE_PAYLOAD_WRAPPED_TRANSFER);
+        assert_eq!(result, ContractError::InvalidPayloadTarget.std_err());
+    }
+
+    #[test]
+    fn error_vaa_native_payload_to_custody_token() {
+        let deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        let mut deps = Extern {
+            storage: deps.storage,
+            api: deps.api,
+            querier: LockAssetQuerier {},
+        };
+        do_init_with_test_guardian(&mut deps);
+
+        // The bridge holds balances of CW20 tokens, the payload must not be sent in its name
+        let result = submit_vaa(&mut deps, VAA_NATIVE_PAYLOAD_CUSTODY_MINT);
+        assert_eq!(result, ContractError::InvalidPayloadTarget.std_err());
+        let result = submit_vaa(&mut deps, VAA_NATIVE_PAYLOAD_CUSTODY_TRANSFER);
+        assert_eq!(result, ContractError::InvalidPayloadTarget.std_err());
+    }
+
+    #[test]
+    fn native_denom_address_round_trip() {
+        let address = extend_denom_to_32(LOCK_NATIVE_DENOM).unwrap();
+        assert_eq!(hex::encode(&address), LOCK_NATIVE_DENOM_ADDRESS);
+
+        let mut fixed = [0u8; 32];
+        fixed.copy_from_slice(&address);
+        assert_eq!(denom_from_32(&fixed).unwrap(), LOCK_NATIVE_DENOM);
+
+        // CW20 addresses are never decoded as denoms
+        let cw20 = extend_address_to_32(&CanonicalAddr::from(vec![0xffu8; 20]));
+        fixed.copy_from_slice(&cw20);
+        assert_eq!(denom_from_32(&fixed), None);
+
+        assert_eq!(extend_denom_to_32(""), None);
+    }
+
+    const LIMIT_RELEASE_DELAY: u64 = 3600;
+
+    fn set_native_transfer_limit<S: Storage, A: Api, Q: Querier>(
+        deps: &mut Extern<S, A, Q>,
+        single_transfer: u128,
+        daily: u128,
+    ) {
+        let msg = HandleMsg::SetTransferLimit {
+            asset_chain: CHAIN_ID,
+            asset_address: Binary::from(hex::decode(LOCK_NATIVE_DENOM_ADDRESS).unwrap()),
+            limit: Some(TransferLimitInfo {
+                single_transfer: Uint128::from(single_transfer),
+                daily: Uint128::from(daily),
+                release_delay: LIMIT_RELEASE_DELAY,
+            }),
+        };
+        submit_msg_with_sender(deps, msg, &HumanAddr::from(CREATOR_ADDR)).unwrap();
+    }
+
+    fn query_native_capacity<S: Storage, A: Api, Q: Querier>(
+        deps: &Extern<S, A, Q>,
+    ) -> TransferCapacityResponse {
+        let result = query(
+            deps,
+            QueryMsg::TransferCapacity {
+                asset_chain: CHAIN_ID,
+                asset_address: Binary::from(hex::decode(LOCK_NATIVE_DENOM_ADDRESS).unwrap()),
+                block_time: unix_timestamp(),
+            },
+        )
+        .unwrap();
+        serde_json::from_slice(result.as_slice()).unwrap()
+    }
+
+    fn submit_msg_at<S: Storage, A: Api, Q: Querier>(
+        deps: &mut Extern<S, A, Q>,
+        msg: HandleMsg,
+        sender: &HumanAddr,
+        block_time: u64,
+    ) -> StdResult<HandleResponse> {
+        let mut env = mock_env(sender, &[]);
+        env.block.time = block_time;
+        handle(deps, env, msg)
+    }
+
+    #[test]
+    fn error_set_transfer_limit_not_owner() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+
+        let msg = HandleMsg::SetTransferLimit {
+            asset_chain: CHAIN_ID,
+            asset_address: Binary::from(hex::decode(LOCK_NATIVE_DENOM_ADDRESS).unwrap()),
+            limit: None,
+        };
+        let result = submit_msg(&mut deps, msg);
+        assert_eq!(result, ContractError::PermissionDenied.std_err());
+    }
+
+    #[test]
+    fn error_lock_transfer_limits() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+        set_native_transfer_limit(&mut deps, LOCK_AMOUNT, LOCK_AMOUNT * 3 / 2);
+
+        let lock = |deps: &mut _, amount| {
+            submit_msg_with_funds(
+                deps,
+                MSG_LOCK_NATIVE.clone(),
+                &HumanAddr::from(SENDER_ADDR),
+                &coins(amount, LOCK_NATIVE_DENOM),
+            )
+        };
+
+        let result = lock(&mut deps, LOCK_AMOUNT + 1);
+        assert_eq!(result, ContractError::TransferLimitExceeded.std_err());
+
+        assert!(lock(&mut deps, LOCK_AMOUNT).is_ok());
+        assert_eq!(
+            query_native_capacity(&deps).outbound_remaining,
+            Some(Uint128::from(LOCK_AMOUNT / 2))
+        );
+        assert_eq!(
+            query_native_capacity(&deps).inbound_remaining,
+            Some(Uint128::from(LOCK_AMOUNT * 3 / 2))
+        );
+
+        let result = lock(&mut deps, LOCK_AMOUNT);
+        assert_eq!(result, ContractError::DailyLimitExceeded.std_err());
+        assert!(lock(&mut deps, LOCK_AMOUNT / 2).is_ok());
+    }
+
+    #[test]
+    fn valid_vaa_transfer_over_limit_queued() {
+        let mut deps = mock_dependencies_with_tax(Decimal::zero());
+        do_init_with_test_guardian(&mut deps);
+        set_native_transfer_limit(&mut deps, 500000, 10000000);
+
+        let result = submit_vaa(&mut deps, VAA_VALID_NATIVE_TRANSFER).unwrap();
+        assert_eq!(result.messages.len(), 0);
+        assert_eq!(result.log[0], log("action", "transfer_queued"));
+
+        // Queued VAAs cannot be submitted again
+        let e = submit_vaa(&mut deps, VAA_VALID_NATIVE_TRANSFER).unwrap_err();
+        assert_eq!(e, ContractError::VaaAlreadyExecuted.std());
+
+        let hash = ParsedVAA::deserialize(&hex::decode(VAA_VALID_NATIVE_TRANSFER).unwrap())
+            .unwrap()
+            .hash;
+        let release_time = unix_timestamp() + LIMIT_RELEASE_DELAY;
+        let pending: PendingTransferResponse = serde_json::from_slice(
+            query(
+                &deps,
+                QueryMsg::PendingTransfer {
+                    hash: Binary::from(&hash[..]),
+                },
+            )
+            .unwrap()
+            .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(pending.release_time, release_time);
+        assert_eq!(pending.relayer, Some(HumanAddr::from(SENDER_ADDR)));
+
+        let release = HandleMsg::ReleasePendingTransfer {
+            hash: Binary::from(&hash[..]),
+        };
+        let sender = HumanAddr::from(SENDER_ADDR);
+        let result = submit_msg_at(&mut deps, release.clone(), &sender, release_time - 1);
+        assert_eq!(result, ContractError::PendingTransferLocked.std_err());
+
+        let messages = submit_msg_at(&mut deps, release.clone(), &sender, release_time)
+            .unwrap()
+            .messages;
+        assert_eq!(
+            messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                to_address: HumanAddr::from("recipient"),
+                amount: coins(1000000, LOCK_NATIVE_DENOM),
+            })]
+        );
+
+        let result = submit_msg_at(&mut deps, release, &sender, release_time);
+        assert_eq!(result, ContractError::PendingTransferNotFound.std_err());
+    }
+
+    #[test]
+    fn valid_cancel_pending_transfer() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+        set_native_transfer_limit(&mut deps, 500000, 10000000);
+        submit_vaa(&mut deps, VAA_VALID_NATIVE_TRANSFER).unwrap();
+
+        let hash = ParsedVAA::deserialize(&hex::decode(VAA_VALID_NATIVE_TRANSFER).unwrap())
+            .unwrap()
+            .hash;
+        let cancel = HandleMsg::CancelPendingTransfer {
+            hash: Binary::from(&hash[..]),
+        };
+        let result = submit_msg(&mut deps, cancel.clone());
+        assert_eq!(result, ContractError::PermissionDenied.std_err());
+
+        let result = submit_msg_with_sender(&mut deps, cancel, &HumanAddr::from(CREATOR_ADDR));
+        assert!(result.is_ok());
+
+        let result = submit_msg_at(
+            &mut deps,
+            HandleMsg::ReleasePendingTransfer {
+                hash: Binary::from(&hash[..]),
+            },
+            &HumanAddr::from(SENDER_ADDR),
+            unix_timestamp() + LIMIT_RELEASE_DELAY,
+        );
+        assert_eq!(result, ContractError::PendingTransferNotFound.std_err());
+
+        // The transfer is not lost, its VAA can be submitted again
+        assert_eq!(
+            query_vaa_status_of(&deps, &hash),
+            VaaStatusResponse {
+                executed: false,
+                block_time: None,
+            }
+        );
+        let result = submit_vaa(&mut deps, VAA_VALID_NATIVE_TRANSFER).unwrap();
+        assert_eq!(result.log[0], log("action", "transfer_queued"));
+    }
+
+    #[test]
+    fn valid_release_pending_transfer_pays_relayer() {
+        let mut deps = mock_dependencies_with_tax(Decimal::zero());
+        do_init_with_test_guardian(&mut deps);
+        set_native_transfer_limit(&mut deps, 500000, 10000000);
+        submit_vaa(&mut deps, VAA_NATIVE_TRANSFER_RELAYER_FEE).unwrap();
+
+        let hash = ParsedVAA::deserialize(&hex::decode(VAA_NATIVE_TRANSFER_RELAYER_FEE).unwrap())
+            .unwrap()
+            .hash;
+        let messages = submit_msg_at(
+            &mut deps,
+            HandleMsg::ReleasePendingTransfer {
+                hash: Binary::from(&hash[..]),
+            },
+            &HumanAddr::from("releaser"),
+            unix_timestamp() + LIMIT_RELEASE_DELAY,
+        )
+        .unwrap()
+        .messages;
+
+        // The fee goes to the submitter of the VAA, not to the sender of the release
+        assert_eq!(
+            messages,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                    to_address: HumanAddr::from("recipient"),
+                    amount: coins(999000, LOCK_NATIVE_DENOM),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    from_address: HumanAddr::from(MOCK_CONTRACT_ADDR),
+                    to_address: HumanAddr::from(SENDER_ADDR),
+                    amount: coins(1000, LOCK_NATIVE_DENOM),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn valid_governance_config_changes() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+
+        let result = submit_vaa(&mut deps, VAA_GOVERNANCE_CODE_ID).unwrap();
+        assert_eq!(result.log[1], log("wrapped_asset_code_id", 1000));
+        let result = submit_vaa(&mut deps, VAA_GOVERNANCE_EXPIRITY).unwrap();
+        assert_eq!(result.log[1], log("guardian_set_expirity", 100));
+
+        let state = config_read(&deps.storage).load().unwrap();
+        assert_eq!(state.wrapped_asset_code_id, 1000);
+        assert_eq!(state.guardian_set_expirity, 100);
+    }
+
+    #[test]
+    fn error_vaa_transfer_expired() {
+        let mut deps = mock_dependencies_with_tax(Decimal::zero());
+        do_init_with_test_guardian(&mut deps);
+
+        let result = submit_vaa(&mut deps, VAA_GOVERNANCE_VAA_EXPIRATION).unwrap();
+        assert_eq!(
+            result.log[1],
+            log("vaa_expiration_time", DEFAULT_VAA_EXPIRATION_TIME)
+        );
+        let state = config_read(&deps.storage).load().unwrap();
+        assert_eq!(state.vaa_expiration_time, DEFAULT_VAA_EXPIRATION_TIME);
+
+        // A transfer VAA that was signed but not redeemed in time is rejected for good, as the
+        // transfer may be refunded on the source chain afterwards
+        let result = submit_vaa(&mut deps, VAA_VALID_NATIVE_TRANSFER);
+        assert_eq!(result, ContractError::VaaExpired.std_err());
+        let result = submit_vaa(&mut deps, VAA_NATIVE_TRANSFER_UNEXPIRED);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn valid_governance_owner_change() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+
+        let result = submit_vaa(&mut deps, VAA_GOVERNANCE_OWNER).unwrap();
+        assert_eq!(result.log[1], log("owner", "newowner"));
+
+        let set_active = HandleMsg::SetActive { is_active: false };
+        let result = submit_msg_with_sender(
+            &mut deps,
+            set_active.clone(),
+            &HumanAddr::from(CREATOR_ADDR),
+        );
+        assert_eq!(result, ContractError::PermissionDenied.std_err());
+        let result = submit_msg_with_sender(&mut deps, set_active, &HumanAddr::from("newowner"));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn valid_governance_pause() {
+        let mut deps = mock_dependencies_with_tax(Decimal::zero());
+        do_init_with_test_guardian(&mut deps);
+
+        submit_vaa(&mut deps, VAA_GOVERNANCE_PAUSE).unwrap();
+        let result = submit_vaa(&mut deps, VAA_VALID_NATIVE_TRANSFER);
+        assert_eq!(result, ContractError::ContractInactive.std_err());
+
+        // Governance VAAs are still processed while paused
+        let result = submit_vaa(&mut deps, VAA_GOVERNANCE_UNPAUSE).unwrap();
+        assert_eq!(result.log[1], log("is_active", true));
+        let result = submit_vaa(&mut deps, VAA_VALID_NATIVE_TRANSFER);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn error_governance_wrong_target_chain() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+
+        let result = submit_vaa(&mut deps, VAA_GOVERNANCE_WRONG_CHAIN);
+        assert_eq!(result, ContractError::WrongTargetChain.std_err());
+    }
+
+    #[test]
+    fn error_governance_unknown_change() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+
+        let result = submit_vaa(&mut deps, VAA_GOVERNANCE_UNKNOWN);
+        assert_eq!(result, ContractError::InvalidVAAAction.std_err());
+    }
+
+    #[test]
+    fn error_governance_not_current_guardian_set() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_test_guardian(&mut deps);
+
+        // Guardian set 0 is replaced but has not expired yet
+        let mut guardian_set = guardian_set_get(&deps.storage, 0).unwrap();
+        guardian_set_set(&mut deps.storage, 1, &guardian_set).unwrap();
+        let mut state = config_read(&deps.storage).load().unwrap();
+        state.guardian_set_index = 1;
+        config(&mut deps.storage).save(&state).unwrap();
+        guardian_set.expiration_time = unix_timestamp() + 1000;
+        guardian_set_set(&mut deps.storage, 0, &guardian_set).unwrap();
+
+        let result = submit_vaa(&mut deps, VAA_GOVERNANCE_PAUSE);
+        assert_eq!(result, ContractError::NotCurrentGuardianSet.std_err());
+    }
+
+    #[test]
+    fn valid_set_active() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+
+        let result = submit_msg_with_sender(
+            &mut deps,
+            HandleMsg::SetActive { is_active: false },
+            &HumanAddr::from(CREATOR_ADDR),
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn error_set_active_not_owner() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+
+        let result = submit_msg(&mut deps, HandleMsg::SetActive { is_active: false });
+        assert_eq!(result, ContractError::PermissionDenied.std_err());
+    }
+
+    #[test]
     fn valid_query_guardian_set() {
         let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
         do_init_with_guardians(&mut deps, 3);
@@ -1231,4 +2978,86 @@ mod tests {
             }
         )
     }
+
+    fn query_vaa_status_of<S: Storage, A: Api, Q: Querier>(
+        deps: &Extern<S, A, Q>,
+        hash: &[u8],
+    ) -> VaaStatusResponse {
+        let result = query(
+            deps,
+            QueryMsg::VaaStatus {
+                hash: Binary::from(hash),
+            },
+        )
+        .unwrap();
+        serde_json::from_slice(result.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn valid_query_vaa_status() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+
+        let hash = ParsedVAA::deserialize(&hex::decode(VAA_VALID_TRANSFER_1_SIG).unwrap())
+            .unwrap()
+            .hash;
+        assert_eq!(
+            query_vaa_status_of(&deps, &hash),
+            VaaStatusResponse {
+                executed: false,
+                block_time: None,
+            }
+        );
+
+        submit_vaa(&mut deps, VAA_VALID_TRANSFER_1_SIG).unwrap();
+        assert_eq!(
+            query_vaa_status_of(&deps, &hash),
+            VaaStatusResponse {
+                executed: true,
+                block_time: Some(unix_timestamp()),
+            }
+        );
+    }
+
+    #[test]
+    fn valid_legacy_vaa_archive_migration() {
+        let mut deps = mock_dependencies(CANONICAL_LENGTH, &[]);
+        do_init_with_guardians(&mut deps, 1);
+
+        // VAA executed by a previous version of the contract
+        let hash = ParsedVAA::deserialize(&hex::decode(VAA_VALID_TRANSFER_1_SIG).unwrap())
+            .unwrap()
+            .hash;
+        bucket(GUARDIAN_SET_KEY, &mut deps.storage)
+            .save(&hash, &true)
+            .unwrap();
+
+        let legacy_status = VaaStatusResponse {
+            executed: true,
+            block_time: None,
+        };
+        assert_eq!(query_vaa_status_of(&deps, &hash), legacy_status);
+        let e = submit_vaa(&mut deps, VAA_VALID_TRANSFER_1_SIG).unwrap_err();
+        assert_eq!(e, ContractError::VaaAlreadyExecuted.std());
+
+        let res = migrate(
+            &mut deps,
+            mock_env(&HumanAddr::from(CREATOR_ADDR), &[]),
+            MigrateMsg {
+                vaa_hashes: vec![Binary::from(&hash[..]), Binary::from(&[1u8; 32][..])],
+            },
+        )
+        .unwrap();
+        assert_eq!(res.log[1], log("vaa_archive.migrated", 1));
+
+        // Moved out of the guardian set namespace, still protected against replay
+        let legacy: StdResult<bool> = bucket_read(GUARDIAN_SET_KEY, &deps.storage).load(&hash);
+        assert!(legacy.is_err());
+        assert_eq!(query_vaa_status_of(&deps, &hash), legacy_status);
+        let e = submit_vaa(&mut deps, VAA_VALID_TRANSFER_1_SIG).unwrap_err();
+        assert_eq!(e, ContractError::VaaAlreadyExecuted.std());
+
+        // Guardian sets are untouched
+        assert!(guardian_set_get(&deps.storage, 0).is_ok());
+    }
 }
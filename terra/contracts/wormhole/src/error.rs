@@ -3,6 +3,18 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ContractError {
+    /// VAA could not be decoded
+    #[error("CannotParseVAA")]
+    CannotParseVAA,
+
+    /// VAA is shorter than its header, signatures and payload require
+    #[error("InvalidVAALength")]
+    InvalidVAALength,
+
+    /// Payload of a known VAA action is followed by unexpected bytes
+    #[error("InvalidVAAPayloadLength")]
+    InvalidVAAPayloadLength,
+
     /// Invalid VAA version
     #[error("InvalidVersion")]
     InvalidVersion,
@@ -11,6 +23,14 @@ pub enum ContractError {
     #[error("InvalidGuardianSetIndex")]
     InvalidGuardianSetIndex,
 
+    /// Guardian set is empty or has more than MAX_LEN_GUARDIAN_KEYS guardians
+    #[error("InvalidGuardianSetSize")]
+    InvalidGuardianSetSize,
+
+    /// Guardian set threshold is zero or larger than the number of guardians
+    #[error("InvalidGuardianSetThreshold")]
+    InvalidGuardianSetThreshold,
+
     /// Guardian set expiration date is zero or in the past
     #[error("GuardianSetExpired")]
     GuardianSetExpired,
@@ -71,6 +91,10 @@ pub enum ContractError {
     #[error("VaaAlreadyExecuted")]
     VaaAlreadyExecuted,
 
+    /// Transfer VAA is older than the VAA expiration time
+    #[error("VaaExpired")]
+    VaaExpired,
+
     /// Message sender not permitted to execute this operation
     #[error("PermissionDenied")]
     PermissionDenied,
@@ -90,6 +114,42 @@ pub enum ContractError {
     /// Wrapped asset not found in the registry
     #[error("AssetNotFound")]
     AssetNotFound,
+
+    /// Exactly one native coin must be sent to lock native assets
+    #[error("InvalidNativeFunds")]
+    InvalidNativeFunds,
+
+    /// Native denom cannot be encoded into or decoded from an asset address
+    #[error("InvalidNativeDenom")]
+    InvalidNativeDenom,
+
+    /// Amount is higher than the single transfer limit of the asset
+    #[error("TransferLimitExceeded")]
+    TransferLimitExceeded,
+
+    /// Amount would exceed the daily limit of the asset
+    #[error("DailyLimitExceeded")]
+    DailyLimitExceeded,
+
+    /// No queued transfer found for this VAA hash
+    #[error("PendingTransferNotFound")]
+    PendingTransferNotFound,
+
+    /// Queued transfer cannot be released before its release time
+    #[error("PendingTransferLocked")]
+    PendingTransferLocked,
+
+    /// Metadata is attested for a native asset or is not valid UTF-8
+    #[error("InvalidAssetMetadata")]
+    InvalidAssetMetadata,
+
+    /// Target of a transfer with payload is a wrapped asset or a token held by the bridge
+    #[error("InvalidPayloadTarget")]
+    InvalidPayloadTarget,
+
+    /// Relayer fee of a lockup exceeds its amount
+    #[error("FeeTooHigh")]
+    FeeTooHigh,
 }
 
 impl ContractError {
@@ -11,4 +11,4 @@ pub mod state;
 pub use crate::error::ContractError;
 
 #[cfg(all(target_arch = "wasm32", not(feature = "library")))]
-cosmwasm_std::create_entry_points!(contract);
+cosmwasm_std::create_entry_points_with_migration!(contract);
@@ -2,12 +2,13 @@ use cosmwasm_std::{Binary, HumanAddr, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::state::{GuardianAddress, GuardianSetInfo};
+use crate::state::{GuardianAddress, GuardianSetInfo, TransferLimitInfo};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InitMsg {
     pub initial_guardian_set: GuardianSetInfo,
     pub guardian_set_expirity: u64,
+    pub vaa_expiration_time: u64,
     pub wrapped_asset_code_id: u64,
 }
 
@@ -19,6 +20,7 @@ pub enum HandleMsg {
     },
     RegisterAssetHook {
         asset_id: Binary,
+        transfer: Option<WrappedTransfer>, // Transfer that deployed the asset, if any
     },
     LockAssets {
         asset: HumanAddr,
@@ -26,17 +28,78 @@ pub enum HandleMsg {
         recipient: Binary,
         target_chain: u8,
         nonce: u32,
+        fee: Option<Uint128>, // Part of the amount paid to the relayer on the target chain
+    },
+    LockNativeAssets {
+        recipient: Binary,
+        target_chain: u8,
+        nonce: u32,
+        fee: Option<Uint128>,
     },
     SetActive {
         is_active: bool,
     },
+    SetTransferLimit {
+        asset_chain: u8,
+        asset_address: Binary,
+        limit: Option<TransferLimitInfo>,
+    },
+    ReleasePendingTransfer {
+        hash: Binary,
+    },
+    CancelPendingTransfer {
+        hash: Binary,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct WrappedTransfer {
+    pub recipient: HumanAddr,    // Target of the transfer
+    pub amount: Uint128,         // Amount after the relayer fee
+    pub payload: Option<Binary>, // Payload sent to the target contract with the tokens, if any
+    pub relayer: HumanAddr,      // Sender of the VAA
+    pub relayer_fee: Uint128,    // Fee minted to the relayer
+}
+
+// Message the target contract of a native denom transfer with payload is called with, the
+// transferred coins are attached
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiverHandleMsg {
+    ReceiveNative {
+        source_chain: u8, // Chain the transfer was initiated on
+        sender: Binary,   // Address of the sender on the source chain
+        payload: Binary,  // Payload of the transfer
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    // Hashes of executed VAAs to move out of the legacy archive
+    pub vaa_hashes: Vec<Binary>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     GuardianSetInfo {},
-    WrappedRegistry { chain: u8, address: Binary },
+    WrappedRegistry {
+        chain: u8,
+        address: Binary,
+    },
+    VaaStatus {
+        hash: Binary,
+    },
+    // Queries have no access to the block time, it has to be provided by the caller
+    TransferCapacity {
+        asset_chain: u8,
+        asset_address: Binary,
+        block_time: u64,
+    },
+    PendingTransfer {
+        hash: Binary,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -51,3 +114,28 @@ pub struct GuardianSetInfoResponse {
 pub struct WrappedRegistryResponse {
     pub address: HumanAddr,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct VaaStatusResponse {
+    pub executed: bool,          // True if a VAA with this body hash was executed
+    pub block_time: Option<u64>, // Block time of the execution, if known
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TransferCapacityResponse {
+    pub limit: Option<TransferLimitInfo>, // Limits of the asset, None if it is not limited
+    pub inbound_remaining: Option<Uint128>, // Amount that can be released before transfers are queued
+    pub outbound_remaining: Option<Uint128>, // Amount that can be locked within the window
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PendingTransferResponse {
+    pub payload: Binary,            // Encoded transfer payload of the queued VAA
+    pub message: Option<Binary>,    // Payload for the target contract, if any
+    pub relayer_fee: Uint128,       // Fee paid to the relayer
+    pub relayer: Option<HumanAddr>, // Submitter of the VAA, if known
+    pub release_time: u64,          // Block time after which the transfer can be released
+}
@@ -1,16 +1,36 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Binary, CanonicalAddr, HumanAddr, StdResult, Storage};
+use cosmwasm_std::{Binary, CanonicalAddr, HumanAddr, StdResult, Storage, Uint128};
 use cosmwasm_storage::{
     bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
     Singleton,
 };
+use wormhole_vaa::{default_quorum, Error as VAAError, Signature, VAABody, VAA};
+
+use crate::error::ContractError;
 
 pub static CONFIG_KEY: &[u8] = b"config";
 pub static GUARDIAN_SET_KEY: &[u8] = b"guardian_set";
 pub static WRAPPED_ASSET_KEY: &[u8] = b"wrapped_asset";
 pub static WRAPPED_ASSET_ADDRESS_KEY: &[u8] = b"wrapped_asset_address";
+pub static VAA_ARCHIVE_KEY: &[u8] = b"vaa_archive";
+pub static TRANSFER_LIMIT_KEY: &[u8] = b"transfer_limit";
+pub static TRANSFER_WINDOW_KEY: &[u8] = b"transfer_window";
+pub static PENDING_TRANSFER_KEY: &[u8] = b"pending_transfer";
+pub static WRAPPED_ASSET_META_KEY: &[u8] = b"wrapped_asset_meta";
+
+// Upper bound of the size of guardian sets. Matches the Solana program, where guardian set
+// updates have to fit into a single transaction.
+pub const MAX_LEN_GUARDIAN_KEYS: usize = 32;
+
+// Length of the rolling window of the daily transfer limit
+pub const TRANSFER_WINDOW_LENGTH: u64 = 24 * 60 * 60;
+// Transferred amounts are accounted in buckets of this length
+pub const TRANSFER_WINDOW_BUCKET: u64 = 60 * 60;
+
+// Transfer VAAs expire after a week unless configured otherwise, like on Solana
+pub const DEFAULT_VAA_EXPIRATION_TIME: u64 = 7 * 24 * 60 * 60;
 
 // Guardian set information
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -29,6 +49,19 @@ pub struct ConfigInfo {
 
     // If true the contract is active and functioning
     pub is_active: bool,
+
+    // Sequence number of the next lockup, incremented on every lock
+    #[serde(default)]
+    pub sequence: u64,
+
+    // Age after which transfer VAAs are rejected. Transfers out of Solana can only be refunded
+    // once their VAA has expired, so this must not exceed the expiration on Solana.
+    #[serde(default = "default_vaa_expiration_time")]
+    pub vaa_expiration_time: u64,
+}
+
+fn default_vaa_expiration_time() -> u64 {
+    DEFAULT_VAA_EXPIRATION_TIME
 }
 
 // Guardian address
@@ -53,11 +86,182 @@ impl GuardianAddress {
 pub struct GuardianSetInfo {
     pub addresses: Vec<GuardianAddress>, // List of guardian addresses
     pub expiration_time: u64,            // Guardian set expiration time
+    #[serde(default)]
+    pub threshold: Option<u8>, // Number of signatures required, 2/3 + 1 of the guardians if unset
 }
 
 impl GuardianSetInfo {
     pub fn quorum(&self) -> usize {
-        ((self.addresses.len() * 10 / 3) * 2) / 10 + 1
+        match self.threshold {
+            Some(threshold) => threshold as usize,
+            None => default_quorum(self.addresses.len()),
+        }
+    }
+
+    // Guardian sets have at least one and at most MAX_LEN_GUARDIAN_KEYS guardians
+    pub fn is_valid_size(&self) -> bool {
+        !self.addresses.is_empty() && self.addresses.len() <= MAX_LEN_GUARDIAN_KEYS
+    }
+
+    // An explicit threshold must be reachable by the guardians of the set
+    pub fn is_valid_threshold(&self) -> bool {
+        match self.threshold {
+            Some(threshold) => threshold != 0 && threshold as usize <= self.addresses.len(),
+            None => true,
+        }
+    }
+}
+
+// Executed VAA information
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaaArchiveInfo {
+    // Block time of the execution, unknown for VAAs migrated from the legacy archive
+    pub block_time: Option<u64>,
+}
+
+// Owner-configured limits of an asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferLimitInfo {
+    // Maximum amount of a single transfer
+    pub single_transfer: Uint128,
+
+    // Maximum amount transferred within the rolling window, per direction
+    pub daily: Uint128,
+
+    // Delay after which an inbound transfer over the limits can be released
+    pub release_delay: u64,
+}
+
+// Amounts transferred within the rolling window
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TransferWindow {
+    pub buckets: Vec<TransferWindowBucket>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferWindowBucket {
+    pub start: u64,      // Block time at the start of the bucket
+    pub amount: Uint128, // Amount transferred within the bucket
+}
+
+impl TransferWindow {
+    // Amount transferred within the window ending at `now`
+    pub fn used(&self, now: u64) -> u128 {
+        self.buckets
+            .iter()
+            .filter(|b| b.start + TRANSFER_WINDOW_LENGTH > now)
+            .fold(0u128, |sum, b| sum.saturating_add(b.amount.u128()))
+    }
+
+    pub fn add(&mut self, now: u64, amount: u128) {
+        self.buckets
+            .retain(|b| b.start + TRANSFER_WINDOW_LENGTH > now);
+
+        let start = now - now % TRANSFER_WINDOW_BUCKET;
+        match self.buckets.last_mut() {
+            Some(bucket) if bucket.start == start => {
+                bucket.amount = Uint128::from(bucket.amount.u128().saturating_add(amount));
+            }
+            _ => self.buckets.push(TransferWindowBucket {
+                start,
+                amount: Uint128::from(amount),
+            }),
+        }
+    }
+}
+
+// Transfer accounting of an asset
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TransferWindowInfo {
+    pub inbound: TransferWindow,  // Transfers released by VAAs
+    pub outbound: TransferWindow, // Locked assets
+}
+
+// Inbound transfer over the limits of its asset, waiting to be released
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingTransferInfo {
+    // Encoded transfer payload of the VAA
+    pub payload: Binary,
+
+    // Payload for the target contract of a transfer with payload
+    #[serde(default)]
+    pub message: Option<Binary>,
+
+    // Relayer fee paid to the submitter of the VAA, not included in the payload
+    #[serde(default)]
+    pub relayer_fee: Uint128,
+
+    // Sender of the VAA, transfers queued without one pay the fee to the sender of the release
+    #[serde(default)]
+    pub relayer: Option<HumanAddr>,
+
+    // Block time after which the transfer can be released
+    pub release_time: u64,
+}
+
+// Token metadata attested by the guardians for a wrapped asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WrappedAssetMetaInfo {
+    pub name: String,
+    pub symbol: String,
+}
+
+// Validated VAA submitted to the contract
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedVAA {
+    pub version: u8,
+    pub guardian_set_index: u32,
+    pub signatures: Vec<Signature>,
+    pub timestamp: u32,
+    pub payload: VAABody,
+
+    // Keccak256 hash of the signed body
+    pub hash: [u8; 32],
+}
+
+impl ParsedVAA {
+    /* VAA format:
+
+    header (length 6):
+    0   uint8   version (0x01)
+    1   uint32  guardian set index
+    5   uint8   len signatures
+
+    per signature (length 66):
+    0   uint8       index of the signer (in guardian keys)
+    1   [65]uint8   signature
+
+    body:
+    0   uint32  unix seconds
+    4   uint8   action
+    5   [payload_size]uint8 payload */
+
+    pub fn deserialize(data: &[u8]) -> StdResult<Self> {
+        match data.first() {
+            None => return ContractError::InvalidVAALength.std_err(),
+            Some(1) => {}
+            Some(_) => return ContractError::InvalidVersion.std_err(),
+        }
+
+        let vaa = VAA::deserialize(data).or_else(|e| match e {
+            VAAError::UnexpectedEnd => ContractError::InvalidVAALength.std_err(),
+            VAAError::TrailingBytes => ContractError::InvalidVAAPayloadLength.std_err(),
+            _ => ContractError::CannotParseVAA.std_err(),
+        })?;
+        let hash = vaa
+            .body_hash()
+            .or_else(|_| ContractError::CannotParseVAA.std_err())?;
+
+        Ok(ParsedVAA {
+            version: vaa.version,
+            guardian_set_index: vaa.guardian_set_index,
+            signatures: vaa.signatures,
+            timestamp: vaa.timestamp,
+            payload: vaa
+                .payload
+                .ok_or_else(|| ContractError::CannotParseVAA.std())?,
+            hash,
+        })
     }
 }
 
@@ -88,17 +292,53 @@ pub fn guardian_set_get<S: Storage>(storage: &S, index: u32) -> StdResult<Guardi
     bucket_read(GUARDIAN_SET_KEY, storage).load(&index.to_le_bytes())
 }
 
-pub fn vaa_archive_add<S: Storage>(storage: &mut S, hash: &[u8]) -> StdResult<()> {
-    bucket(GUARDIAN_SET_KEY, storage).save(hash, &true)
+pub fn vaa_archive_add<S: Storage>(storage: &mut S, hash: &[u8], block_time: u64) -> StdResult<()> {
+    bucket(VAA_ARCHIVE_KEY, storage).save(
+        hash,
+        &VaaArchiveInfo {
+            block_time: Some(block_time),
+        },
+    )
+}
+
+pub fn vaa_archive_get<S: Storage>(storage: &S, hash: &[u8]) -> Option<VaaArchiveInfo> {
+    if let Ok(info) = bucket_read(VAA_ARCHIVE_KEY, storage).load(hash) {
+        return Some(info);
+    }
+    // VAAs executed before the archive had its own namespace are still honored
+    if vaa_archive_legacy_check(storage, hash) {
+        return Some(VaaArchiveInfo { block_time: None });
+    }
+    None
 }
 
 pub fn vaa_archive_check<S: Storage>(storage: &S, hash: &[u8]) -> bool {
+    vaa_archive_get(storage, hash).is_some()
+}
+
+// Allows a VAA to be executed again, only used for VAAs that had no effect
+pub fn vaa_archive_remove<S: Storage>(storage: &mut S, hash: &[u8]) {
+    bucket::<S, VaaArchiveInfo>(VAA_ARCHIVE_KEY, storage).remove(hash);
+}
+
+// Executed VAA hashes used to be stored next to the guardian sets
+fn vaa_archive_legacy_check<S: Storage>(storage: &S, hash: &[u8]) -> bool {
     bucket_read(GUARDIAN_SET_KEY, storage)
         .load(&hash)
         .or::<bool>(Ok(false))
         .unwrap()
 }
 
+// Moves a hash out of the legacy archive, returns false if it was not found there
+pub fn vaa_archive_migrate<S: Storage>(storage: &mut S, hash: &[u8]) -> StdResult<bool> {
+    if !vaa_archive_legacy_check(storage, hash) {
+        return Ok(false);
+    }
+    bucket(VAA_ARCHIVE_KEY, storage).save(hash, &VaaArchiveInfo { block_time: None })?;
+    bucket::<S, bool>(GUARDIAN_SET_KEY, storage).remove(hash);
+    Ok(true)
+}
+
 pub fn wrapped_asset<S: Storage>(storage: &mut S) -> Bucket<S, HumanAddr> {
     bucket(WRAPPED_ASSET_KEY, storage)
 }
@@ -115,6 +355,38 @@ pub fn wrapped_asset_address_read<S: Storage>(storage: &S) -> ReadonlyBucket<S,
     bucket_read(WRAPPED_ASSET_ADDRESS_KEY, storage)
 }
 
+pub fn wrapped_asset_meta<S: Storage>(storage: &mut S) -> Bucket<S, WrappedAssetMetaInfo> {
+    bucket(WRAPPED_ASSET_META_KEY, storage)
+}
+
+pub fn wrapped_asset_meta_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, WrappedAssetMetaInfo> {
+    bucket_read(WRAPPED_ASSET_META_KEY, storage)
+}
+
+pub fn transfer_limit<S: Storage>(storage: &mut S) -> Bucket<S, TransferLimitInfo> {
+    bucket(TRANSFER_LIMIT_KEY, storage)
+}
+
+pub fn transfer_limit_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, TransferLimitInfo> {
+    bucket_read(TRANSFER_LIMIT_KEY, storage)
+}
+
+pub fn transfer_window<S: Storage>(storage: &mut S) -> Bucket<S, TransferWindowInfo> {
+    bucket(TRANSFER_WINDOW_KEY, storage)
+}
+
+pub fn transfer_window_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, TransferWindowInfo> {
+    bucket_read(TRANSFER_WINDOW_KEY, storage)
+}
+
+pub fn pending_transfer<S: Storage>(storage: &mut S) -> Bucket<S, PendingTransferInfo> {
+    bucket(PENDING_TRANSFER_KEY, storage)
+}
+
+pub fn pending_transfer_read<S: Storage>(storage: &S) -> ReadonlyBucket<S, PendingTransferInfo> {
+    bucket_read(PENDING_TRANSFER_KEY, storage)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,9 +402,85 @@ mod tests {
         GuardianSetInfo {
             addresses,
             expiration_time: 0,
+            threshold: None,
         }
     }
 
+    const VAA_GUARDIAN_SET_CHANGE: &str = "01000000000100a33c022217ccb87a5bc83b71e6377fff6639e7904d9e9995a42dc0867dc2b0bc5d1aacc3752ea71cf4d85278526b5dd40b0343667a2d4434a44cbf7844181a1000000007d0010000000101e06a9adfeb38a8ee4d00e89307c016d0749679bd";
+
+    #[test]
+    fn parse_vaa() {
+        let data = hex::decode(VAA_GUARDIAN_SET_CHANGE).unwrap();
+        let vaa = ParsedVAA::deserialize(&data).unwrap();
+
+        assert_eq!(vaa.version, 1);
+        assert_eq!(vaa.guardian_set_index, 0);
+        assert_eq!(vaa.signatures.len(), 1);
+        assert_eq!(vaa.timestamp, 2000);
+        assert_eq!(vaa.hash, wormhole_vaa::body_hash(&data[6 + 66..]));
+        match vaa.payload {
+            VAABody::UpdateGuardianSet(v) => {
+                assert_eq!(v.new_index, 1);
+                assert_eq!(v.new_keys.len(), 1);
+            }
+            _ => panic!("Wrong payload type"),
+        }
+    }
+
+    #[test]
+    fn parse_vaa_truncated() {
+        let data = hex::decode(VAA_GUARDIAN_SET_CHANGE).unwrap();
+        for len in 0..data.len() {
+            assert_eq!(
+                ParsedVAA::deserialize(&data[..len]),
+                ContractError::InvalidVAALength.std_err()
+            );
+        }
+    }
+
+    #[test]
+    fn parse_vaa_trailing_bytes() {
+        let mut data = hex::decode(VAA_GUARDIAN_SET_CHANGE).unwrap();
+        data.push(0);
+        assert_eq!(
+            ParsedVAA::deserialize(&data),
+            ContractError::InvalidVAAPayloadLength.std_err()
+        );
+    }
+
+    #[test]
+    fn parse_vaa_wrong_version() {
+        let mut data = hex::decode(VAA_GUARDIAN_SET_CHANGE).unwrap();
+        data[0] = 2;
+        assert_eq!(
+            ParsedVAA::deserialize(&data),
+            ContractError::InvalidVersion.std_err()
+        );
+    }
+
+    #[test]
+    fn transfer_window_rolls() {
+        let mut window = TransferWindow::default();
+        let now = 1608803487u64;
+
+        window.add(now, 100);
+        window.add(now + 1, 50);
+        assert_eq!(window.buckets.len(), 1);
+        assert_eq!(window.used(now + 1), 150);
+
+        window.add(now + TRANSFER_WINDOW_BUCKET, 10);
+        assert_eq!(window.buckets.len(), 2);
+        assert_eq!(window.used(now + TRANSFER_WINDOW_BUCKET), 160);
+
+        // First bucket leaves the window a day after its start
+        let later = now + TRANSFER_WINDOW_LENGTH;
+        assert_eq!(window.used(later), 10);
+        window.add(later, 5);
+        assert_eq!(window.buckets.len(), 2);
+        assert_eq!(window.used(later), 15);
+        assert_eq!(window.used(later + TRANSFER_WINDOW_LENGTH), 0);
+    }
+
     #[test]
     fn quardian_set_quorum() {
         assert_eq!(build_guardian_set(1).quorum(), 1);
@@ -150,5 +498,34 @@ mod tests {
         assert_eq!(build_guardian_set(20).quorum(), 14);
         assert_eq!(build_guardian_set(25).quorum(), 17);
         assert_eq!(build_guardian_set(100).quorum(), 67);
+        assert_eq!(build_guardian_set(255).quorum(), 171);
+    }
+
+    #[test]
+    fn guardian_set_size() {
+        assert!(!build_guardian_set(0).is_valid_size());
+        assert!(build_guardian_set(1).is_valid_size());
+        assert!(build_guardian_set(25).is_valid_size());
+        assert!(build_guardian_set(MAX_LEN_GUARDIAN_KEYS).is_valid_size());
+        assert!(!build_guardian_set(MAX_LEN_GUARDIAN_KEYS + 1).is_valid_size());
+    }
+
+    #[test]
+    fn guardian_set_threshold() {
+        let mut guardian_set = build_guardian_set(4);
+        assert!(guardian_set.is_valid_threshold());
+
+        guardian_set.threshold = Some(1);
+        assert!(guardian_set.is_valid_threshold());
+        assert_eq!(guardian_set.quorum(), 1);
+
+        guardian_set.threshold = Some(4);
+        assert!(guardian_set.is_valid_threshold());
+        assert_eq!(guardian_set.quorum(), 4);
+
+        guardian_set.threshold = Some(0);
+        assert!(!guardian_set.is_valid_threshold());
+        guardian_set.threshold = Some(5);
+        assert!(!guardian_set.is_valid_threshold());
     }
 }
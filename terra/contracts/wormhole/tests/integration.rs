@@ -48,8 +48,10 @@ fn do_init(
         initial_guardian_set: GuardianSetInfo {
             addresses: guardians.clone(),
             expiration_time: 100,
+            threshold: None,
         },
         guardian_set_expirity: 50,
+        vaa_expiration_time: 100,
         wrapped_asset_code_id: 999,
     };
     let env = mock_env_height(&TestAddress::INITIALIZER.value(), height, 0);
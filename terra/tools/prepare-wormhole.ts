@@ -18,6 +18,7 @@ async function script() {
             expiration_time: Math.floor(Date.now() / 1000) + 1000 * 60 * 60
         },
         guardian_set_expirity: 0,
+        vaa_expiration_time: 7 * 24 * 60 * 60,
         wrapped_asset_code_id: wrapped_code_id,
     });
     console.log(`Wormhole instance created at ${contract_address}`);
@@ -0,0 +1,651 @@
+use alloc::vec::Vec;
+
+use primitive_types::U256;
+
+use crate::{reader::Reader, Error, ForeignAddress};
+
+/// A typed VAA payload. Every action that is understood by the bridge implements this trait and
+/// is registered in [VAABody](enum.VAABody.html).
+pub trait VAAPayload: Sized {
+    /// Action id identifying the payload on the wire
+    const ACTION: u8;
+
+    /// Decodes the payload (without the action byte)
+    fn deserialize(data: &mut Reader) -> Result<Self, Error>;
+
+    /// Encodes the payload (without the action byte)
+    fn serialize(&self) -> Result<Vec<u8>, Error>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum VAABody {
+    UpdateGuardianSet(BodyUpdateGuardianSet),
+    Governance(BodyGovernance),
+    Transfer(BodyTransfer),
+    TransferWithPayload(BodyTransferWithPayload),
+    AssetMetadata(BodyAssetMetadata),
+    Message(BodyMessage),
+    Refund(BodyRefund),
+    /// Payload of an action that is not known to this version of the bridge. The raw bytes are
+    /// retained so the VAA can be inspected, hashed and relayed without loss.
+    Unknown {
+        action: u8,
+        bytes: Vec<u8>,
+    },
+}
+
+impl VAABody {
+    pub fn action_id(&self) -> u8 {
+        match self {
+            VAABody::UpdateGuardianSet(u) => u.action(),
+            VAABody::Governance(_) => BodyGovernance::ACTION,
+            VAABody::Transfer(t) => t.action(),
+            VAABody::TransferWithPayload(_) => BodyTransferWithPayload::ACTION,
+            VAABody::AssetMetadata(_) => BodyAssetMetadata::ACTION,
+            VAABody::Message(_) => BodyMessage::ACTION,
+            VAABody::Refund(_) => BodyRefund::ACTION,
+            VAABody::Unknown { action, .. } => *action,
+        }
+    }
+
+    /// Returns whether the action of this payload is known to the bridge
+    pub fn is_known(&self) -> bool {
+        !matches!(self, VAABody::Unknown { .. })
+    }
+
+    /// Decodes the action byte and the payload. Known payloads must span the remaining data
+    /// exactly.
+    pub fn deserialize(data: &[u8]) -> Result<VAABody, Error> {
+        let mut payload_data = Reader::new(data);
+        let action = payload_data.read_u8()?;
+
+        let payload = match action {
+            BodyUpdateGuardianSet::ACTION => {
+                VAABody::UpdateGuardianSet(BodyUpdateGuardianSet::deserialize(&mut payload_data)?)
+            }
+            BodyUpdateGuardianSet::ACTION_WITH_THRESHOLD => VAABody::UpdateGuardianSet(
+                BodyUpdateGuardianSet::deserialize_with_threshold(&mut payload_data)?,
+            ),
+            BodyGovernance::ACTION => {
+                VAABody::Governance(BodyGovernance::deserialize(&mut payload_data)?)
+            }
+            BodyTransfer::ACTION => {
+                VAABody::Transfer(BodyTransfer::deserialize(&mut payload_data)?)
+            }
+            BodyTransfer::ACTION_WITH_FEE => {
+                VAABody::Transfer(BodyTransfer::deserialize_with_fee(&mut payload_data)?)
+            }
+            BodyTransferWithPayload::ACTION => VAABody::TransferWithPayload(
+                BodyTransferWithPayload::deserialize(&mut payload_data)?,
+            ),
+            BodyAssetMetadata::ACTION => {
+                VAABody::AssetMetadata(BodyAssetMetadata::deserialize(&mut payload_data)?)
+            }
+            BodyMessage::ACTION => VAABody::Message(BodyMessage::deserialize(&mut payload_data)?),
+            BodyRefund::ACTION => VAABody::Refund(BodyRefund::deserialize(&mut payload_data)?),
+            _ => {
+                let bytes = payload_data.read_to_end().to_vec();
+                VAABody::Unknown { action, bytes }
+            }
+        };
+
+        if !payload_data.is_empty() {
+            return Err(Error::TrailingBytes);
+        }
+
+        Ok(payload)
+    }
+
+    /// Encodes the payload (without the action byte)
+    pub fn serialize(&self) -> Result<Vec<u8>, Error> {
+        match self {
+            VAABody::Transfer(b) => b.serialize(),
+            VAABody::TransferWithPayload(b) => b.serialize(),
+            VAABody::AssetMetadata(b) => b.serialize(),
+            VAABody::Message(b) => b.serialize(),
+            VAABody::Refund(b) => b.serialize(),
+            VAABody::UpdateGuardianSet(b) => b.serialize(),
+            VAABody::Governance(b) => b.serialize(),
+            VAABody::Unknown { bytes, .. } => Ok(bytes.clone()),
+        }
+    }
+}
+
+/// Metadata about an asset
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AssetMeta {
+    /// Address of the token
+    pub address: ForeignAddress,
+
+    /// Chain of the token
+    pub chain: u8,
+
+    /// Number of decimals of the token
+    pub decimals: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BodyUpdateGuardianSet {
+    pub new_index: u32,
+    pub new_keys: Vec<[u8; 20]>,
+    /// Number of signatures required by the new set, the default quorum if `None`
+    pub threshold: Option<u8>,
+}
+
+/// Change of the bridge configuration on a single chain, approved by the guardians
+#[derive(Clone, Debug, PartialEq)]
+pub struct BodyGovernance {
+    pub target_chain: u8,
+    pub change: GovernanceChange,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GovernanceChange {
+    /// Code id used to instantiate wrapped assets
+    WrappedAssetCodeId(u64),
+    /// Period for which a guardian set stays active after it has been replaced
+    GuardianSetExpiration(u64),
+    /// New owner of the bridge
+    Owner(ForeignAddress),
+    /// Pauses (`false`) or unpauses (`true`) the bridge
+    Active(bool),
+    /// Pauses transfers into (`inbound`) and out of (`outbound`) the chain independently
+    Pause { inbound: bool, outbound: bool },
+    /// Fee charged for a transfer out of the chain
+    TransferFee(u64),
+    /// Transaction fee refunded to the submitter of a VAA
+    VAARefund(u64),
+    /// Withdraws collected fees to `recipient`
+    WithdrawFees {
+        recipient: ForeignAddress,
+        amount: u64,
+    },
+    /// Period after which a VAA is no longer accepted and its claim can be evicted
+    VAAExpiration(u64),
+    /// Change of a kind that is not known to this version of the bridge. The raw bytes are
+    /// retained like those of unknown actions, it is up to the chains to reject it.
+    Unknown { kind: u8, bytes: Vec<u8> },
+}
+
+impl GovernanceChange {
+    fn kind(&self) -> u8 {
+        match self {
+            GovernanceChange::WrappedAssetCodeId(_) => 0x01,
+            GovernanceChange::GuardianSetExpiration(_) => 0x02,
+            GovernanceChange::Owner(_) => 0x03,
+            GovernanceChange::Active(_) => 0x04,
+            GovernanceChange::TransferFee(_) => 0x05,
+            GovernanceChange::VAARefund(_) => 0x06,
+            GovernanceChange::WithdrawFees { .. } => 0x07,
+            GovernanceChange::Pause { .. } => 0x08,
+            GovernanceChange::VAAExpiration(_) => 0x09,
+            GovernanceChange::Unknown { kind, .. } => *kind,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BodyTransfer {
+    pub nonce: u32,
+    pub source_chain: u8,
+    pub target_chain: u8,
+    pub source_address: ForeignAddress,
+    pub target_address: ForeignAddress,
+    pub asset: AssetMeta,
+    pub amount: U256,
+    /// Part of `amount` that is paid to the submitter of the VAA, zero if no relayer is paid
+    pub fee: U256,
+}
+
+impl BodyTransfer {
+    /// Action id of transfers that pay a relayer fee. The fee is appended to the payload of a
+    /// regular transfer.
+    pub const ACTION_WITH_FEE: u8 = 0x14;
+
+    /// Action id the transfer is encoded with, depending on whether it pays a relayer fee
+    pub fn action(&self) -> u8 {
+        if self.fee.is_zero() {
+            Self::ACTION
+        } else {
+            Self::ACTION_WITH_FEE
+        }
+    }
+
+    /// Decodes the payload of a transfer that pays a relayer fee
+    pub fn deserialize_with_fee(data: &mut Reader) -> Result<BodyTransfer, Error> {
+        let mut transfer = BodyTransfer::deserialize(data)?;
+        transfer.fee = read_fee(data, transfer.amount)?;
+
+        // Transfers without a fee have a single encoding
+        if transfer.fee.is_zero() {
+            return Err(Error::InvalidValue);
+        }
+
+        Ok(transfer)
+    }
+
+    /// Encodes the transfer without the relayer fee
+    fn serialize_fields(&self) -> Vec<u8> {
+        let mut v = Vec::with_capacity(136);
+        v.extend_from_slice(&self.nonce.to_be_bytes());
+        v.push(self.source_chain);
+        v.push(self.target_chain);
+        v.extend_from_slice(&self.source_address);
+        v.extend_from_slice(&self.target_address);
+        v.push(self.asset.chain);
+        v.extend_from_slice(&self.asset.address);
+        v.push(self.asset.decimals);
+        v.extend_from_slice(&u256_bytes(self.amount));
+
+        v
+    }
+}
+
+/// Number of signatures out of `len_keys` guardians that a VAA needs if the guardian set does
+/// not specify a threshold (2/3 + 1)
+pub fn default_quorum(len_keys: usize) -> usize {
+    ((len_keys * 10 / 3) * 2) / 10 + 1
+}
+
+impl BodyUpdateGuardianSet {
+    /// Action id of guardian set updates that carry an explicit threshold. The threshold is
+    /// appended to the payload of a regular guardian set update.
+    pub const ACTION_WITH_THRESHOLD: u8 = 0x03;
+
+    /// Action id the update is encoded with, depending on whether it carries a threshold
+    pub fn action(&self) -> u8 {
+        match self.threshold {
+            None => Self::ACTION,
+            Some(_) => Self::ACTION_WITH_THRESHOLD,
+        }
+    }
+
+    /// Number of signatures required by the new guardian set
+    pub fn quorum(&self) -> usize {
+        match self.threshold {
+            Some(threshold) => threshold as usize,
+            None => default_quorum(self.new_keys.len()),
+        }
+    }
+
+    /// Decodes the payload of a guardian set update that carries a threshold
+    pub fn deserialize_with_threshold(data: &mut Reader) -> Result<BodyUpdateGuardianSet, Error> {
+        let mut update = BodyUpdateGuardianSet::deserialize(data)?;
+        let threshold = data.read_u8()?;
+        check_threshold(threshold, update.new_keys.len())?;
+        update.threshold = Some(threshold);
+
+        Ok(update)
+    }
+}
+
+/// The threshold must be reachable by the new set
+fn check_threshold(threshold: u8, len_keys: usize) -> Result<(), Error> {
+    if threshold == 0 || threshold as usize > len_keys {
+        return Err(Error::InvalidValue);
+    }
+    Ok(())
+}
+
+impl VAAPayload for BodyUpdateGuardianSet {
+    const ACTION: u8 = 0x01;
+
+    fn deserialize(data: &mut Reader) -> Result<BodyUpdateGuardianSet, Error> {
+        let new_index = data.read_u32()?;
+
+        let keys_len = data.read_u8()?;
+        let mut keys = Vec::with_capacity(keys_len as usize);
+        for _ in 0..keys_len {
+            let mut key: [u8; 20] = [0; 20];
+            data.read_exact(&mut key)?;
+            keys.push(key);
+        }
+
+        Ok(BodyUpdateGuardianSet {
+            new_index,
+            new_keys: keys,
+            threshold: None,
+        })
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Error> {
+        let mut v = Vec::with_capacity(6 + 20 * self.new_keys.len());
+        v.extend_from_slice(&self.new_index.to_be_bytes());
+        v.push(len_u8(self.new_keys.len())?);
+
+        for k in self.new_keys.iter() {
+            v.extend_from_slice(k);
+        }
+
+        if let Some(threshold) = self.threshold {
+            check_threshold(threshold, self.new_keys.len())?;
+            v.push(threshold);
+        }
+
+        Ok(v)
+    }
+}
+
+impl VAAPayload for BodyGovernance {
+    const ACTION: u8 = 0x02;
+
+    fn deserialize(data: &mut Reader) -> Result<BodyGovernance, Error> {
+        let target_chain = data.read_u8()?;
+        let change = match data.read_u8()? {
+            0x01 => GovernanceChange::WrappedAssetCodeId(data.read_u64()?),
+            0x02 => GovernanceChange::GuardianSetExpiration(data.read_u64()?),
+            0x03 => {
+                let mut owner = ForeignAddress::default();
+                data.read_exact(&mut owner)?;
+                GovernanceChange::Owner(owner)
+            }
+            0x04 => GovernanceChange::Active(read_bool(data)?),
+            0x05 => GovernanceChange::TransferFee(data.read_u64()?),
+            0x06 => GovernanceChange::VAARefund(data.read_u64()?),
+            0x07 => {
+                let mut recipient = ForeignAddress::default();
+                data.read_exact(&mut recipient)?;
+                GovernanceChange::WithdrawFees {
+                    recipient,
+                    amount: data.read_u64()?,
+                }
+            }
+            0x08 => GovernanceChange::Pause {
+                inbound: read_bool(data)?,
+                outbound: read_bool(data)?,
+            },
+            0x09 => GovernanceChange::VAAExpiration(data.read_u64()?),
+            kind => GovernanceChange::Unknown {
+                kind,
+                bytes: data.read_to_end().to_vec(),
+            },
+        };
+
+        Ok(BodyGovernance {
+            target_chain,
+            change,
+        })
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Error> {
+        let mut v = Vec::with_capacity(42);
+        v.push(self.target_chain);
+        v.push(self.change.kind());
+
+        match &self.change {
+            GovernanceChange::WrappedAssetCodeId(value)
+            | GovernanceChange::GuardianSetExpiration(value)
+            | GovernanceChange::TransferFee(value)
+            | GovernanceChange::VAARefund(value)
+            | GovernanceChange::VAAExpiration(value) => v.extend_from_slice(&value.to_be_bytes()),
+            GovernanceChange::Owner(owner) => v.extend_from_slice(owner),
+            GovernanceChange::Active(active) => v.push(*active as u8),
+            GovernanceChange::WithdrawFees { recipient, amount } => {
+                v.extend_from_slice(recipient);
+                v.extend_from_slice(&amount.to_be_bytes());
+            }
+            GovernanceChange::Pause { inbound, outbound } => {
+                v.push(*inbound as u8);
+                v.push(*outbound as u8);
+            }
+            GovernanceChange::Unknown { bytes, .. } => v.extend_from_slice(bytes),
+        }
+
+        Ok(v)
+    }
+}
+
+impl VAAPayload for BodyTransfer {
+    const ACTION: u8 = 0x10;
+
+    fn deserialize(data: &mut Reader) -> Result<BodyTransfer, Error> {
+        let nonce = data.read_u32()?;
+        let source_chain = data.read_u8()?;
+        let target_chain = data.read_u8()?;
+        let mut source_address: ForeignAddress = ForeignAddress::default();
+        data.read_exact(&mut source_address)?;
+        let mut target_address: ForeignAddress = ForeignAddress::default();
+        data.read_exact(&mut target_address)?;
+        let token_chain = data.read_u8()?;
+        let mut token_address: ForeignAddress = ForeignAddress::default();
+        data.read_exact(&mut token_address)?;
+        let token_decimals = data.read_u8()?;
+
+        let amount = U256::from_big_endian(data.read_slice(32)?);
+
+        Ok(BodyTransfer {
+            nonce,
+            source_chain,
+            target_chain,
+            source_address,
+            target_address,
+            asset: AssetMeta {
+                address: token_address,
+                chain: token_chain,
+                decimals: token_decimals,
+            },
+            amount,
+            fee: U256::zero(),
+        })
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Error> {
+        let mut v = self.serialize_fields();
+        if !self.fee.is_zero() {
+            if self.fee > self.amount {
+                return Err(Error::InvalidValue);
+            }
+            v.extend_from_slice(&u256_bytes(self.fee));
+        }
+
+        Ok(v)
+    }
+}
+
+/// Transfer that carries an opaque payload for the recipient, which is a program or contract
+/// that is invoked with the transferred tokens. The relayer fee is always encoded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BodyTransferWithPayload {
+    pub transfer: BodyTransfer,
+    pub payload: Vec<u8>,
+}
+
+impl VAAPayload for BodyTransferWithPayload {
+    const ACTION: u8 = 0x13;
+
+    fn deserialize(data: &mut Reader) -> Result<BodyTransferWithPayload, Error> {
+        let mut transfer = BodyTransfer::deserialize(data)?;
+        transfer.fee = read_fee(data, transfer.amount)?;
+        let payload_len = data.read_u16()?;
+        let payload = data.read_slice(payload_len as usize)?.to_vec();
+
+        Ok(BodyTransferWithPayload { transfer, payload })
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Error> {
+        if self.payload.len() > u16::MAX as usize {
+            return Err(Error::LengthOverflow);
+        }
+
+        if self.transfer.fee > self.transfer.amount {
+            return Err(Error::InvalidValue);
+        }
+
+        let mut v = self.transfer.serialize_fields();
+        v.extend_from_slice(&u256_bytes(self.transfer.fee));
+        v.extend_from_slice(&(self.payload.len() as u16).to_be_bytes());
+        v.extend_from_slice(&self.payload);
+
+        Ok(v)
+    }
+}
+
+/// Metadata of an asset as attested by the guardians
+#[derive(Clone, Debug, PartialEq)]
+pub struct BodyAssetMetadata {
+    pub asset: AssetMeta,
+    /// Symbol of the token, UTF-8 and right zero-padded
+    pub symbol: [u8; 32],
+    /// Name of the token, UTF-8 and right zero-padded
+    pub name: [u8; 32],
+}
+
+impl BodyAssetMetadata {
+    pub fn symbol_str(&self) -> Result<&str, Error> {
+        padded_str(&self.symbol)
+    }
+
+    pub fn name_str(&self) -> Result<&str, Error> {
+        padded_str(&self.name)
+    }
+}
+
+impl VAAPayload for BodyAssetMetadata {
+    const ACTION: u8 = 0x11;
+
+    fn deserialize(data: &mut Reader) -> Result<BodyAssetMetadata, Error> {
+        let chain = data.read_u8()?;
+        let mut address = ForeignAddress::default();
+        data.read_exact(&mut address)?;
+        let decimals = data.read_u8()?;
+        let mut symbol = [0u8; 32];
+        data.read_exact(&mut symbol)?;
+        let mut name = [0u8; 32];
+        data.read_exact(&mut name)?;
+
+        Ok(BodyAssetMetadata {
+            asset: AssetMeta {
+                address,
+                chain,
+                decimals,
+            },
+            symbol,
+            name,
+        })
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Error> {
+        let mut v = Vec::with_capacity(98);
+        v.push(self.asset.chain);
+        v.extend_from_slice(&self.asset.address);
+        v.push(self.asset.decimals);
+        v.extend_from_slice(&self.symbol);
+        v.extend_from_slice(&self.name);
+
+        Ok(v)
+    }
+}
+
+/// Refund of a transfer out of the source chain that was never completed. The tokens are returned
+/// to the source address and the transfer can no longer be redeemed on the target chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BodyRefund {
+    /// The refunded transfer, which never pays a relayer fee
+    pub transfer: BodyTransfer,
+}
+
+impl VAAPayload for BodyRefund {
+    const ACTION: u8 = 0x15;
+
+    fn deserialize(data: &mut Reader) -> Result<BodyRefund, Error> {
+        let transfer = BodyTransfer::deserialize(data)?;
+
+        Ok(BodyRefund { transfer })
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Error> {
+        if !self.transfer.fee.is_zero() {
+            return Err(Error::InvalidValue);
+        }
+
+        Ok(self.transfer.serialize_fields())
+    }
+}
+
+/// Arbitrary message published by an emitter on a chain
+#[derive(Clone, Debug, PartialEq)]
+pub struct BodyMessage {
+    pub emitter_chain: u8,
+    pub emitter_address: ForeignAddress,
+    pub nonce: u32,
+    /// Sequence number assigned to the message by the bridge of the emitter chain
+    pub sequence: u64,
+    pub payload: Vec<u8>,
+}
+
+impl VAAPayload for BodyMessage {
+    const ACTION: u8 = 0x12;
+
+    fn deserialize(data: &mut Reader) -> Result<BodyMessage, Error> {
+        let emitter_chain = data.read_u8()?;
+        let mut emitter_address = ForeignAddress::default();
+        data.read_exact(&mut emitter_address)?;
+        let nonce = data.read_u32()?;
+        let sequence = data.read_u64()?;
+        let payload_len = data.read_u16()?;
+        let payload = data.read_slice(payload_len as usize)?.to_vec();
+
+        Ok(BodyMessage {
+            emitter_chain,
+            emitter_address,
+            nonce,
+            sequence,
+            payload,
+        })
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Error> {
+        if self.payload.len() > u16::MAX as usize {
+            return Err(Error::LengthOverflow);
+        }
+
+        let mut v = Vec::with_capacity(47 + self.payload.len());
+        v.push(self.emitter_chain);
+        v.extend_from_slice(&self.emitter_address);
+        v.extend_from_slice(&self.nonce.to_be_bytes());
+        v.extend_from_slice(&self.sequence.to_be_bytes());
+        v.extend_from_slice(&(self.payload.len() as u16).to_be_bytes());
+        v.extend_from_slice(&self.payload);
+
+        Ok(v)
+    }
+}
+
+/// Reads a flag that is encoded as `0` or `1`
+fn read_bool(data: &mut Reader) -> Result<bool, Error> {
+    match data.read_u8()? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(Error::InvalidValue),
+    }
+}
+
+/// Reads a relayer fee, which cannot exceed the transferred amount
+fn read_fee(data: &mut Reader, amount: U256) -> Result<U256, Error> {
+    let fee = U256::from_big_endian(data.read_slice(32)?);
+    if fee > amount {
+        return Err(Error::InvalidValue);
+    }
+    Ok(fee)
+}
+
+fn u256_bytes(value: U256) -> [u8; 32] {
+    let mut data = [0u8; 32];
+    value.to_big_endian(&mut data);
+    data
+}
+
+/// Decodes a right zero-padded UTF-8 string
+fn padded_str(bytes: &[u8]) -> Result<&str, Error> {
+    let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    core::str::from_utf8(&bytes[..len]).map_err(|_| Error::InvalidValue)
+}
+
+/// Converts a list length into its single byte wire representation
+pub(crate) fn len_u8(len: usize) -> Result<u8, Error> {
+    if len > u8::MAX as usize {
+        return Err(Error::LengthOverflow);
+    }
+    Ok(len as u8)
+}
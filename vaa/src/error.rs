@@ -0,0 +1,38 @@
+use core::fmt;
+
+/// Errors that may be returned when encoding or decoding a VAA.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The data ended before the VAA was fully decoded
+    UnexpectedEnd,
+    /// There is data left after a payload of a known action
+    TrailingBytes,
+    /// The VAA has no payload
+    MissingPayload,
+    /// A list is too long to have its length encoded in a single byte
+    LengthOverflow,
+    /// A payload field holds a value that is not defined for it
+    InvalidValue,
+    /// A signature could not be decoded
+    InvalidSignature,
+    /// No public key could be recovered from a signature
+    RecoveryFailed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            Error::UnexpectedEnd => "unexpected end of data",
+            Error::TrailingBytes => "trailing bytes after payload",
+            Error::MissingPayload => "missing payload",
+            Error::LengthOverflow => "length does not fit into a byte",
+            Error::InvalidValue => "invalid payload value",
+            Error::InvalidSignature => "invalid signature",
+            Error::RecoveryFailed => "could not recover public key",
+        };
+        f.write_str(msg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
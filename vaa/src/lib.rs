@@ -0,0 +1,141 @@
+//! Encoding, hashing and signature recovery of Wormhole VAAs (verifiable action approvals).
+//!
+//! This crate is the single implementation of the VAA wire format (see `docs/protocol.md`) and is
+//! used by the Solana program, the Terra contract and the Solana agent. It is `no_std` compatible
+//! when built without the default `std` feature.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use sha3::{Digest, Keccak256};
+
+pub use primitive_types::U256;
+
+pub use crate::{
+    body::{
+        default_quorum, AssetMeta, BodyAssetMetadata, BodyGovernance, BodyMessage, BodyRefund,
+        BodyTransfer, BodyTransferWithPayload, BodyUpdateGuardianSet, GovernanceChange, VAABody,
+        VAAPayload,
+    },
+    error::Error,
+    reader::Reader,
+};
+
+mod body;
+mod error;
+mod reader;
+#[cfg(feature = "recover")]
+mod recover;
+
+pub type ForeignAddress = [u8; 32];
+
+/// Size of the VAA header (version, guardian set index, number of signatures)
+pub const HEADER_LEN: usize = 6;
+/// Size of a single signature including the index of the signer
+pub const SIGNATURE_LEN: usize = 66;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VAA {
+    // Header part
+    pub version: u8,
+    pub guardian_set_index: u32,
+    pub signatures: Vec<Signature>,
+
+    // Body part
+    pub timestamp: u32,
+    pub payload: Option<VAABody>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Signature {
+    pub index: u8,
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+    pub v: u8,
+}
+
+impl VAA {
+    pub fn new() -> VAA {
+        VAA {
+            version: 0,
+            guardian_set_index: 0,
+            signatures: Vec::new(),
+            timestamp: 0,
+            payload: None,
+        }
+    }
+
+    /// Keccak256 hash of the signed body, this is the digest signed by the guardians
+    pub fn body_hash(&self) -> Result<[u8; 32], Error> {
+        Ok(body_hash(&self.signature_body()?))
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, Error> {
+        let mut v = Vec::with_capacity(HEADER_LEN + SIGNATURE_LEN * self.signatures.len());
+
+        v.push(self.version);
+        v.extend_from_slice(&self.guardian_set_index.to_be_bytes());
+
+        v.push(body::len_u8(self.signatures.len())?);
+        for s in self.signatures.iter() {
+            v.push(s.index);
+            v.extend_from_slice(&s.r);
+            v.extend_from_slice(&s.s);
+            v.push(s.v);
+        }
+
+        v.extend_from_slice(&self.signature_body()?);
+
+        Ok(v)
+    }
+
+    pub fn signature_body(&self) -> Result<Vec<u8>, Error> {
+        let payload = self.payload.as_ref().ok_or(Error::MissingPayload)?;
+        let payload_data = payload.serialize()?;
+
+        let mut v = Vec::with_capacity(5 + payload_data.len());
+        v.extend_from_slice(&self.timestamp.to_be_bytes());
+        v.push(payload.action_id());
+        v.extend_from_slice(&payload_data);
+
+        Ok(v)
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<VAA, Error> {
+        let mut rdr = Reader::new(data);
+
+        let version = rdr.read_u8()?;
+        let guardian_set_index = rdr.read_u32()?;
+
+        let len_sig = rdr.read_u8()?;
+        let mut signatures: Vec<Signature> = Vec::with_capacity(len_sig as usize);
+        for _i in 0..len_sig {
+            let index = rdr.read_u8()?;
+            let mut r = [0u8; 32];
+            rdr.read_exact(&mut r)?;
+            let mut s = [0u8; 32];
+            rdr.read_exact(&mut s)?;
+            let v = rdr.read_u8()?;
+
+            signatures.push(Signature { index, r, s, v });
+        }
+
+        let timestamp = rdr.read_u32()?;
+        let payload = Some(VAABody::deserialize(rdr.read_to_end())?);
+
+        Ok(VAA {
+            version,
+            guardian_set_index,
+            signatures,
+            timestamp,
+            payload,
+        })
+    }
+}
+
+/// Keccak256 hash of an encoded VAA body
+pub fn body_hash(body: &[u8]) -> [u8; 32] {
+    Keccak256::digest(body).into()
+}
@@ -0,0 +1,63 @@
+use crate::Error;
+
+/// Bounds-checked big endian reader over a byte slice.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader { data, pos: 0 }
+    }
+
+    /// Number of bytes consumed so far
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.data.len()
+    }
+
+    pub fn read_slice(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self.pos.checked_add(len).ok_or(Error::UnexpectedEnd)?;
+        let slice = self.data.get(self.pos..end).ok_or(Error::UnexpectedEnd)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        buf.copy_from_slice(self.read_slice(buf.len())?);
+        Ok(())
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, Error> {
+        let mut bytes = [0u8; 2];
+        self.read_exact(&mut bytes)?;
+        Ok(u16::from_be_bytes(bytes))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, Error> {
+        let mut bytes = [0u8; 4];
+        self.read_exact(&mut bytes)?;
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, Error> {
+        let mut bytes = [0u8; 8];
+        self.read_exact(&mut bytes)?;
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    /// Consumes all remaining bytes
+    pub fn read_to_end(&mut self) -> &'a [u8] {
+        let rest = &self.data[self.pos..];
+        self.pos = self.data.len();
+        rest
+    }
+}
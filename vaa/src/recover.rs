@@ -0,0 +1,41 @@
+use core::convert::TryFrom;
+
+use k256::{
+    ecdsa::{
+        recoverable::{Id as RecoverableId, Signature as RecoverableSignature},
+        Signature as EcdsaSignature, VerifyKey,
+    },
+    EncodedPoint,
+};
+use sha3::{Digest, Keccak256};
+
+use crate::{Error, Signature};
+
+impl Signature {
+    /// Recovers the address of the guardian that created this signature over `hash`. Guardian
+    /// addresses are the last 20 bytes of the keccak256 hash of the uncompressed public key.
+    pub fn recover(&self, hash: &[u8; 32]) -> Result<[u8; 20], Error> {
+        let mut rs = [0u8; 64];
+        rs[..32].copy_from_slice(&self.r);
+        rs[32..].copy_from_slice(&self.s);
+
+        let signature = EcdsaSignature::try_from(&rs[..]).map_err(|_| Error::InvalidSignature)?;
+        let id = RecoverableId::new(self.v).map_err(|_| Error::InvalidSignature)?;
+        let recoverable_signature =
+            RecoverableSignature::new(&signature, id).map_err(|_| Error::InvalidSignature)?;
+
+        let verify_key: VerifyKey = recoverable_signature
+            .recover_verify_key_from_digest_bytes(hash.into())
+            .map_err(|_| Error::RecoveryFailed)?;
+
+        let point = EncodedPoint::from(&verify_key).decompress();
+        if bool::from(point.is_none()) {
+            return Err(Error::RecoveryFailed);
+        }
+        let point = point.unwrap();
+
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&Keccak256::digest(&point.as_bytes()[1..])[12..]);
+        Ok(address)
+    }
+}
@@ -0,0 +1,595 @@
+//! Golden vectors for the VAA wire format.
+//!
+//! The VAAs are taken from the test suites of the Solana program and the Terra contract. Both
+//! chains decode VAAs with this crate, so every vector must decode, re-encode byte for byte and
+//! hash to the digest of its raw body.
+
+use wormhole_vaa::{
+    body_hash, default_quorum, BodyAssetMetadata, BodyGovernance, BodyMessage, BodyRefund,
+    BodyTransfer, BodyTransferWithPayload, BodyUpdateGuardianSet, Error, GovernanceChange, VAABody,
+    VAAPayload, HEADER_LEN, SIGNATURE_LEN, U256, VAA,
+};
+
+// Guardian addresses generated by bridge/cmd/vaa-test-terra/main.go
+const ADDR_1: &str = "befa429d57cd18b7f8a4d91a2da9ab4af05d0fbe";
+const ADDR_2: &str = "e06a9adfeb38a8ee4d00e89307c016d0749679bd";
+const ADDR_3: &str = "8575df9b3c97b4e267deb92d93137844a97a0132";
+const ADDR_4: &str = "0427cda59902dc6eb0c1bd2b6d38f87c5552b348";
+const ADDR_5: &str = "bfea822f75c42e1764c791b8fe04a7b10ddb3857";
+const ADDR_6: &str = "2f5fe0b158147e7260f14062556afc94eece55ff";
+// Test guardian with private key 0x1111..11, used by the Terra governance tests
+const ADDR_TEST: &str = "19e7e376e7c213b7e7e7e46cc70a5dd086daff2a";
+
+struct Vector {
+    name: &'static str,
+    vaa: &'static str,
+    /// Guardians that signed the VAA, in signature order. `None` if the signing keys are unknown.
+    #[cfg_attr(not(feature = "recover"), allow(dead_code))]
+    signers: Option<&'static [&'static str]>,
+}
+
+const VECTORS: &[Vector] = &[
+    Vector {
+        name: "solana_transfer",
+        vaa: "0100000000010092737a1504f3b3df8c93cb85c64a4860bb270e26026b6e37f095356a406f6af439c6b2e9775fa1c6669525f06edab033ba5d447308f4e3bdb33c0f361dc32ec3015f37000810000000350102020104000000000000000000000000000000000000000000000000000000000000000000000000000000000090f8bf6a479f320ead074411a4b0e7944ea8c9c1010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000004563918244f40000",
+        signers: None,
+    },
+    Vector {
+        name: "solana_guardian_set_update",
+        vaa: "010000000001003382c71a4c79e1518a6ce29c91569f6427a60a95696a3515b8c2340b6acffd723315bd1011aa779f22573882a4edfe1b8206548e134871a23f8ba0c1c7d0b5ed0100000bb8010000000101befa429d57cd18b7f8a4d91a2da9ab4af05d0fbe",
+        signers: None,
+    },
+    Vector {
+        name: "terra_transfer_1_sig",
+        vaa: "01000000000100d106d4f363c6e3d0bf8ebf3cf8ef1ba35e66687b7613a826b5f5b68e0c346e1e0fdd6ceb332c87dad7d170ee6736571c0b75173787a8dcf41a492075e18a9a9601000007d01000000038010302010400000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a7640000",
+        signers: Some(&[ADDR_1]),
+    },
+    Vector {
+        name: "terra_transfer_2_sigs",
+        vaa: "0100000000020040d91705d211c52c9f120adb1b794355ba10ec1ff855295e677c5b341b2e5449684179f8ca4087e88de2cba0e6cbf6e0c7a353529800ccf96e5fdd80a85a59220001efb8a4825c87ab68190e1b184eeda5c45f82b22450ff113f2581a2f1bd3aeca60798392405cd4d3b523a5c3426d09b963c195c842a0040e93651cb700785d0e600000007d0100000003801030201040000000000000000000000000000000000000000000000000000000000000000000000000000000000010203040506070809000102030405060708090002000000000000000000000000d833215cbcc3f914bd1c9ece3ee7bf8b14f841bb080000000000000000000000000000000000000000000000000de0b6b3a7640000",
+        signers: Some(&[ADDR_1, ADDR_2]),
+    },
+    Vector {
+        name: "terra_transfer_3_sigs",
+        vaa: "0100000000030040d91705d211c52c9f120adb1b794355ba10ec1ff855295e677c5b341b2e5449684179f8ca4087e88de2cba0e6cbf6e0c7a353529800ccf96e5fdd80a85a59220001efb8a4825c87ab68190e1b184eeda5c45f82b22450ff113f2581a2f1bd3aeca60798392405cd4d3b523a5c3426d09b963c195c842a0040e93651cb700785d0e60002a5fb92ff2b5a5eed98e2909ed932e5d9328cb2527027cce8f40c4f5677c341c83fe9fac7bf39af60fe47ecfb6f52b22b9d817d24d4147684b08e2fe19ff3a3ef01000007d0100000003801030201040000000000000000000000000000000000000000000000000000000000000000000000000000000000010203040506070809000102030405060708090002000000000000000000000000d833215cbcc3f914bd1c9ece3ee7bf8b14f841bb080000000000000000000000000000000000000000000000000de0b6b3a7640000",
+        signers: Some(&[ADDR_1, ADDR_2, ADDR_3]),
+    },
+    Vector {
+        name: "terra_transfer_5_sigs_gs_1",
+        vaa: "01000000010500027eb7e87a9d0ab91ec53bb073c0f0acf189900139daa652666fd4cfe32a4ee42383c1a66e3a397c2de8ae485225357feb52f665952b1e384ef6dfcea1ba9f920001cfcacfad444ac3202f8f0d2252c69ee90d18c9105f7be3b5d361b7fcb0fbf7fa7287bac5de9cb02f86a28fdd7f24015991020431b0048aa3bbb29daed625e416000372f6c239ddeccded04a95a0cf0bfefe6e168148f1fe3b93e797eb2e74e098b890f2be341dd0f3c8172c2050154407cfdd1ea7bd6cce0b31f020ec7530ffb6109000449c025fe0630268983d57c4bd1546497788f810e427b6fd436cb1f048152375e1063422b4d1cc668a0612814c550ea7e3d1aa93404a0b6e089d210d4c937023a000548bf474fb350d5e482378c37404fb4d1421e262d13ebf6b11977214c789a246a6c278a522a9be4beba008f3d481b1ee35c5b0559bef474eb34b9e3e681947c230100000fa01000000039010302010500000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a7640000",
+        signers: Some(&[ADDR_1, ADDR_2, ADDR_4, ADDR_5, ADDR_6]),
+    },
+    Vector {
+        name: "terra_signature_sequence",
+        vaa: "01000000000201efb8a4825c87ab68190e1b184eeda5c45f82b22450ff113f2581a2f1bd3aeca60798392405cd4d3b523a5c3426d09b963c195c842a0040e93651cb700785d0e6000040d91705d211c52c9f120adb1b794355ba10ec1ff855295e677c5b341b2e5449684179f8ca4087e88de2cba0e6cbf6e0c7a353529800ccf96e5fdd80a85a592200000007d0100000003801030201040000000000000000000000000000000000000000000000000000000000000000000000000000000000010203040506070809000102030405060708090002000000000000000000000000d833215cbcc3f914bd1c9ece3ee7bf8b14f841bb080000000000000000000000000000000000000000000000000de0b6b3a7640000",
+        signers: Some(&[ADDR_2, ADDR_1]),
+    },
+    Vector {
+        name: "terra_invalid_target_address",
+        vaa: "0100000000010092f32c76aa3a8d83de59b3f2281cfbf70af33d9bcfbaa78bd3e9cafc512335ab40b126a894f0182ee8c69f5324496eb681c1780ed39bcc80f589cfc0a5df144a01000007d01000000038010302010400000000000000000000000000000000000000000000000000000000000000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a7640000",
+        signers: Some(&[ADDR_1]),
+    },
+    Vector {
+        name: "terra_amount_too_high",
+        vaa: "0100000000010055fdf76a64b779ac5b7a54dc181cf430f4d14a499b7933049d8bc94db529ed0a2d12d50ec2026883e59a5c64f2189b60c84a53b66113e8b52da66fd89f70495f00000007d01000000038010302010400000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000100000000000000000000000000000000",
+        signers: Some(&[ADDR_1]),
+    },
+    Vector {
+        name: "terra_same_source_and_target",
+        vaa: "010000000001004c53dfce8fc9e781f0cfdc6592c00c337c1e109168ff17ee3bf4cf69ddb8a0a52a3c215093301d5459d282d625dc5125592609f06f14a57f61121e668b0ec10500000003e81000000038030302010400000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a7640000",
+        signers: Some(&[ADDR_1]),
+    },
+    Vector {
+        name: "terra_wrong_target",
+        vaa: "01000000000100b19a265b1407e9619ffc29be9562161ed2c155db5ba68e01265a250a677eb0c62bb91e468da827e9ec4c1e9428ade97129126f56500c4a3c9f9803cc85f656d200000003e81000000038010202010400000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a7640000",
+        signers: Some(&[ADDR_1]),
+    },
+    Vector {
+        name: "terra_guardian_set_change_from_0",
+        vaa: "01000000000100a33c022217ccb87a5bc83b71e6377fff6639e7904d9e9995a42dc0867dc2b0bc5d1aacc3752ea71cf4d85278526b5dd40b0343667a2d4434a44cbf7844181a1000000007d0010000000101e06a9adfeb38a8ee4d00e89307c016d0749679bd",
+        signers: Some(&[ADDR_1]),
+    },
+    Vector {
+        name: "terra_guardian_set_change_from_0_diff",
+        vaa: "01000000000100d90d6f9cbc0458599cbe4d267bc9221b54955b94cb5cb338aeb845bdc9dd275f558871ea479de9cc0b44cfb2a07344431a3adbd2f98aa86f4e12ff4aba061b7f00000007d00100000001018575df9b3c97b4e267deb92d93137844a97a0132",
+        signers: Some(&[ADDR_1]),
+    },
+    Vector {
+        name: "terra_guardian_set_change_jump",
+        vaa: "010000000001004b179853b36b76446c72944d50551be814ab34f23da2124615315da71505df801b38355d741cdd65e856792e2a1435270abfe52ae005c4e3671c0b7aac36445a01000007d00100000002018575df9b3c97b4e267deb92d93137844a97a0132",
+        signers: Some(&[ADDR_1]),
+    },
+    Vector {
+        name: "terra_guardian_set_change_to_6",
+        vaa: "01000000000100a5defbd912ef327d07afff71e0da9c2e2a13e5516255c62e249a6761afe2465c7b6fc1032451559551e76eb4a029474fd791b2250c4fd40a8b3f5d4f5f58e5a30000000fa0010000000106befa429d57cd18b7f8a4d91a2da9ab4af05d0fbee06a9adfeb38a8ee4d00e89307c016d0749679bd8575df9b3c97b4e267deb92d93137844a97a01320427cda59902dc6eb0c1bd2b6d38f87c5552b348bfea822f75c42e1764c791b8fe04a7b10ddb38572f5fe0b158147e7260f14062556afc94eece55ff",
+        signers: Some(&[ADDR_1]),
+    },
+    Vector {
+        name: "terra_governance_owner",
+        vaa: "010000000001007560490c5a3adeae9ee0407d57af14999e721a5d3445f1657f3c3dafa54155404732f4d3f6c67cb7dbaf6d5ef99475b067e6a6c3dc2663c0da214a669834a0840000000bb80203030000000000000000000000006e65776f776e6572000000000000000000000000",
+        signers: Some(&[ADDR_TEST]),
+    },
+    Vector {
+        name: "terra_governance_pause",
+        vaa: "01000000000100f993754ad118d11d42f11426785d581fb8f5dc6e1b84a9ff1fcf004d4d776cdb3b7983b31465868ddf71dbc648a458ce253a8f462f6a09bfa433424c2e535f460000000bb802030400",
+        signers: Some(&[ADDR_TEST]),
+    },
+    Vector {
+        name: "terra_asset_metadata",
+        vaa: "0100000000010077ffb4f6337a6ad081c349a9f5b1360d24d59709f9d748e97f2ac315d3b91b0d11d013a438c65e90912109521d08f379679f803b77ea379e0e3f33da9587a9ed0000000fa011010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e99880857455448000000000000000000000000000000000000000000000000000000005772617070656420457468657200000000000000000000000000000000000000",
+        signers: Some(&[ADDR_TEST]),
+    },
+    Vector {
+        name: "solana_governance_withdraw_fees",
+        vaa: "010000000001003e39769e127c306359bb5ee0aa1be01b2414927e9f8de76f85fe913502418a233f56cad0c421ace4727fee1fc493959c21f951a5aede4cdb6450fa59685c6fdc00000017700201070102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20000000003b9aca00",
+        signers: Some(&[ADDR_TEST]),
+    },
+    Vector {
+        name: "solana_message",
+        vaa: "01000000000100758075748635286638357408aa579beee82642e878a0911cb82ad9125e67bd154886cae847464165e838195008760961cb97e7f0908a29ae97f2d682d2a1e1890100001b5812010102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20000000050000000000000003000568656c6c6f",
+        signers: Some(&[ADDR_TEST]),
+    },
+    Vector {
+        name: "terra_transfer_with_payload",
+        vaa: "01000000000100e2a867ab7a66ab1149e28c9abde7d8fb43e7b5c4fc37d48f365c5ad89e7ac3f0198a7f237f65c87bc89ea4121b8f4f9fd9e750f5522e0948446fafb3430a9cd100000007d01300000038010302010400000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a76400000000000000000000000000000000000000000000000000000000000000000000000b7b2273776170223a7b7d7d",
+        signers: Some(&[ADDR_TEST]),
+    },
+    Vector {
+        name: "terra_native_transfer_relayer_fee",
+        vaa: "01000000000100032f29abcbda487465325f5a3b6272623e8dddd9e94becf84d443f7c813b3a1f7a688728c34e22c0ca51749e88fef6fabdb5ea244fc9fcc7f34428aceeadf49600000007d0140000000101030000000000000000000000000102030405060708090001020304050607080900000000000000000000000000726563697069656e7400000000000000000000000301000000000000000000000000000000000000000000000000000000757573640600000000000000000000000000000000000000000000000000000000000f424000000000000000000000000000000000000000000000000000000000000003e8",
+        signers: Some(&[ADDR_TEST]),
+    },
+    Vector {
+        name: "guardian_set_update_threshold",
+        vaa: "010000000001005e885ee4dcd4711a70e6cbd19ec0811e727bd9e233b9eeddc39ba63aa46d379d249f4c375706e840017e40bd52e90c8dba780931cc4efac346dc626ab140bedb01000007d0030000000103befa429d57cd18b7f8a4d91a2da9ab4af05d0fbee06a9adfeb38a8ee4d00e89307c016d0749679bd8575df9b3c97b4e267deb92d93137844a97a013202",
+        signers: Some(&[ADDR_TEST]),
+    },
+    Vector {
+        name: "solana_governance_pause_inbound",
+        vaa: "01000000000100995e3ea85b966ef0eccf6e5ebd95fded3c176e0acfad7ffe30577339533e310f725764cef0bb50c44b9b49975f67efe7a83dba6642387abef5c9676a85d9b0e30000000fa00201080100",
+        signers: Some(&[ADDR_TEST]),
+    },
+    Vector {
+        name: "solana_refund",
+        vaa: "010000000001008bb0e8d83dd42ca0b442b65bf3b1455e8dd6d8e5ac44f2508b98cdb8fefa541f1912e9a0fe4d747f21adc365c7912a9265531970a7269b8c99233cb0d977282c0000000bb815000000070102040404040404040404040404040404040404040404040404040404040404040400000000000000000000000090f8bf6a479f320ead074411a4b0e7944ea8c9c10105050505050505050505050505050505050505050505050505050505050505050800000000000000000000000000000000000000000000000000000000000f4240",
+        signers: Some(&[ADDR_TEST]),
+    },
+    Vector {
+        name: "solana_governance_vaa_expiration",
+        vaa: "01000000000100b5a4c6740604ac12e8c0befa673f446e031e2a95d62413812efc6ab3adc22e685106496a7a7141c9758bede1dbbe987a49c647a00dde6ea91c95b72ad2f3611c0000000fa00201090000000000278d00",
+        signers: Some(&[ADDR_TEST]),
+    },
+];
+
+/// Signed by a key that is not part of any guardian set
+#[cfg_attr(not(feature = "recover"), allow(dead_code))]
+const VAA_WRONG_SIGNATURE: &str = "0100000000010075c1b20fb59adc55a08f9778bc525507a36a29d1f0e2cb3fcc9c90f7331786263c4bd53ce5d3865b4f63cddeafb2c1026b5e13f1b66af7dabbd1f1af9f34fd3f01000007d01000000038010302010400000000000000000000000000000000000000000000000000000000000000000000000000000000000102030405060708090001020304050607080900010000000000000000000000000347ef34687bdc9f189e87a9200658d9c40e9988080000000000000000000000000000000000000000000000000de0b6b3a7640000";
+
+/// Looks up a vector by name, so that tests keep checking the same case when vectors are added
+fn vector(name: &str) -> &'static Vector {
+    let mut matches = VECTORS.iter().filter(|v| v.name == name);
+    let vector = matches
+        .next()
+        .unwrap_or_else(|| panic!("no vector {}", name));
+    assert!(matches.next().is_none(), "duplicate vector {}", name);
+    vector
+}
+
+fn decode(vector: &Vector) -> (Vec<u8>, VAA) {
+    let data = hex::decode(vector.vaa).unwrap();
+    let vaa = VAA::deserialize(&data).unwrap_or_else(|e| panic!("{}: {}", vector.name, e));
+    (data, vaa)
+}
+
+#[test]
+fn golden_round_trip() {
+    for vector in VECTORS {
+        let (data, vaa) = decode(vector);
+        assert!(vaa.payload.as_ref().unwrap().is_known(), "{}", vector.name);
+        assert_eq!(vaa.serialize().unwrap(), data, "{}", vector.name);
+
+        let body = &data[HEADER_LEN + SIGNATURE_LEN * vaa.signatures.len()..];
+        assert_eq!(vaa.signature_body().unwrap(), body, "{}", vector.name);
+        assert_eq!(vaa.body_hash().unwrap(), body_hash(body), "{}", vector.name);
+    }
+}
+
+#[test]
+fn golden_truncated() {
+    for vector in VECTORS {
+        let data = hex::decode(vector.vaa).unwrap();
+        for len in 0..data.len() {
+            assert_eq!(
+                VAA::deserialize(&data[..len]),
+                Err(Error::UnexpectedEnd),
+                "{} truncated to {} bytes",
+                vector.name,
+                len
+            );
+        }
+    }
+}
+
+#[test]
+fn golden_trailing_bytes() {
+    for vector in VECTORS {
+        let mut data = hex::decode(vector.vaa).unwrap();
+        data.push(0);
+        assert_eq!(
+            VAA::deserialize(&data),
+            Err(Error::TrailingBytes),
+            "{}",
+            vector.name
+        );
+    }
+}
+
+#[test]
+fn golden_transfer() {
+    let (_, vaa) = decode(vector("terra_transfer_1_sig"));
+    assert_eq!(vaa.version, 1);
+    assert_eq!(vaa.guardian_set_index, 0);
+    assert_eq!(vaa.timestamp, 2000);
+
+    let t = match vaa.payload.unwrap() {
+        VAABody::Transfer(t) => t,
+        _ => panic!("expected transfer"),
+    };
+    assert_eq!(t.nonce, 56);
+    assert_eq!(t.source_chain, 1);
+    assert_eq!(t.target_chain, 3);
+    assert_eq!(
+        hex::encode(t.target_address),
+        "0000000000000000000000000102030405060708090001020304050607080900"
+    );
+    assert_eq!(t.asset.chain, 1);
+    assert_eq!(
+        hex::encode(&t.asset.address[12..]),
+        "0347ef34687bdc9f189e87a9200658d9c40e9988"
+    );
+    assert_eq!(t.asset.decimals, 8);
+    assert_eq!(t.amount, U256::from(1_000_000_000_000_000_000u64));
+
+    // Amounts are full 256 bit values on the wire
+    let (_, vaa) = decode(vector("terra_amount_too_high"));
+    match vaa.payload.unwrap() {
+        VAABody::Transfer(t) => assert_eq!(t.amount, U256::from(u128::MAX) + 1),
+        _ => panic!("expected transfer"),
+    }
+}
+
+#[test]
+fn golden_guardian_set_update() {
+    let (_, vaa) = decode(vector("terra_guardian_set_change_to_6"));
+    assert_eq!(vaa.timestamp, 4000);
+
+    let u = match vaa.payload.unwrap() {
+        VAABody::UpdateGuardianSet(u) => u,
+        _ => panic!("expected guardian set update"),
+    };
+    assert_eq!(u.new_index, 1);
+    let keys: Vec<String> = u.new_keys.iter().map(hex::encode).collect();
+    assert_eq!(keys, vec![ADDR_1, ADDR_2, ADDR_3, ADDR_4, ADDR_5, ADDR_6]);
+    assert_eq!(u.threshold, None);
+    assert_eq!(u.quorum(), 5);
+}
+
+#[test]
+fn golden_guardian_set_update_threshold() {
+    let (data, vaa) = decode(vector("guardian_set_update_threshold"));
+    let payload = vaa.payload.unwrap();
+    assert_eq!(
+        payload.action_id(),
+        BodyUpdateGuardianSet::ACTION_WITH_THRESHOLD
+    );
+    let u = match payload {
+        VAABody::UpdateGuardianSet(u) => u,
+        other => panic!("unexpected payload {:?}", other),
+    };
+    assert_eq!(u.new_index, 1);
+    assert_eq!(u.new_keys.len(), 3);
+    assert_eq!(u.threshold, Some(2));
+    assert_eq!(u.quorum(), 2);
+
+    // The threshold must be reachable by the new set
+    let threshold_offset = data.len() - 1;
+    for threshold in [0, 4].iter() {
+        let mut invalid = data.clone();
+        invalid[threshold_offset] = *threshold;
+        assert_eq!(VAA::deserialize(&invalid), Err(Error::InvalidValue));
+
+        let unreachable = VAABody::UpdateGuardianSet(BodyUpdateGuardianSet {
+            threshold: Some(*threshold),
+            ..u.clone()
+        });
+        assert_eq!(unreachable.serialize(), Err(Error::InvalidValue));
+    }
+
+    let without_threshold = BodyUpdateGuardianSet {
+        threshold: None,
+        ..u
+    };
+    assert_eq!(without_threshold.quorum(), default_quorum(3));
+    assert_eq!(
+        VAABody::UpdateGuardianSet(without_threshold).action_id(),
+        BodyUpdateGuardianSet::ACTION
+    );
+}
+
+#[test]
+fn default_quorum_sizes() {
+    let quorums: Vec<usize> = (1..=7).map(default_quorum).collect();
+    assert_eq!(quorums, vec![1, 2, 3, 3, 4, 5, 5]);
+    assert_eq!(default_quorum(19), 13);
+}
+
+#[cfg(feature = "recover")]
+#[test]
+fn golden_signers() {
+    for vector in VECTORS {
+        let signers = match vector.signers {
+            Some(s) => s,
+            None => continue,
+        };
+        let (_, vaa) = decode(vector);
+        let hash = vaa.body_hash().unwrap();
+
+        let recovered: Vec<String> = vaa
+            .signatures
+            .iter()
+            .map(|s| hex::encode(s.recover(&hash).unwrap()))
+            .collect();
+        assert_eq!(recovered, signers, "{}", vector.name);
+    }
+
+    let vaa = VAA::deserialize(&hex::decode(VAA_WRONG_SIGNATURE).unwrap()).unwrap();
+    let hash = vaa.body_hash().unwrap();
+    let recovered = hex::encode(vaa.signatures[0].recover(&hash).unwrap());
+    assert_ne!(recovered, ADDR_1);
+}
+
+#[test]
+fn golden_governance() {
+    let (_, vaa) = decode(vector("terra_governance_owner"));
+    assert_eq!(vaa.timestamp, 3000);
+    assert_eq!(
+        vaa.payload.unwrap(),
+        VAABody::Governance(BodyGovernance {
+            target_chain: 3,
+            change: GovernanceChange::Owner(
+                *b"\0\0\0\0\0\0\0\0\0\0\0\0newowner\0\0\0\0\0\0\0\0\0\0\0\0"
+            ),
+        })
+    );
+
+    let (mut data, vaa) = decode(vector("terra_governance_pause"));
+    assert_eq!(
+        vaa.payload.unwrap(),
+        VAABody::Governance(BodyGovernance {
+            target_chain: 3,
+            change: GovernanceChange::Active(false),
+        })
+    );
+
+    let (_, vaa) = decode(vector("solana_governance_withdraw_fees"));
+    let mut recipient = [0u8; 32];
+    for (i, b) in recipient.iter_mut().enumerate() {
+        *b = i as u8 + 1;
+    }
+    assert_eq!(
+        vaa.payload.unwrap(),
+        VAABody::Governance(BodyGovernance {
+            target_chain: 1,
+            change: GovernanceChange::WithdrawFees {
+                recipient,
+                amount: 1_000_000_000,
+            },
+        })
+    );
+
+    // Flags other than 0 and 1 are rejected
+    *data.last_mut().unwrap() = 2;
+    assert_eq!(VAA::deserialize(&data), Err(Error::InvalidValue));
+
+    let (mut data, vaa) = decode(vector("solana_governance_pause_inbound"));
+    assert_eq!(
+        vaa.payload.unwrap(),
+        VAABody::Governance(BodyGovernance {
+            target_chain: 1,
+            change: GovernanceChange::Pause {
+                inbound: true,
+                outbound: false,
+            },
+        })
+    );
+    *data.last_mut().unwrap() = 2;
+    assert_eq!(VAA::deserialize(&data), Err(Error::InvalidValue));
+
+    // Changes of unknown kinds keep their raw bytes, like unknown actions
+    let (mut data, _) = decode(vector("solana_governance_pause_inbound"));
+    let kind_offset = data.len() - 3;
+    data[kind_offset] = 0x7f;
+    let vaa = VAA::deserialize(&data).unwrap();
+    assert_eq!(
+        vaa.payload.as_ref().unwrap(),
+        &VAABody::Governance(BodyGovernance {
+            target_chain: 1,
+            change: GovernanceChange::Unknown {
+                kind: 0x7f,
+                bytes: vec![1, 0],
+            },
+        })
+    );
+    assert_eq!(vaa.serialize().unwrap(), data);
+
+    let (_, vaa) = decode(vector("solana_governance_vaa_expiration"));
+    assert_eq!(
+        vaa.payload.unwrap(),
+        VAABody::Governance(BodyGovernance {
+            target_chain: 1,
+            change: GovernanceChange::VAAExpiration(30 * 24 * 60 * 60),
+        })
+    );
+}
+
+#[test]
+fn golden_asset_metadata() {
+    let (mut data, vaa) = decode(vector("terra_asset_metadata"));
+    assert_eq!(vaa.timestamp, 4000);
+    let meta = match vaa.payload.unwrap() {
+        VAABody::AssetMetadata(meta) => meta,
+        other => panic!("unexpected payload {:?}", other),
+    };
+    assert_eq!(meta.asset.chain, 1);
+    assert_eq!(
+        hex::encode(&meta.asset.address[12..]),
+        "0347ef34687bdc9f189e87a9200658d9c40e9988"
+    );
+    assert_eq!(meta.asset.decimals, 8);
+    assert_eq!(meta.symbol_str(), Ok("WETH"));
+    assert_eq!(meta.name_str(), Ok("Wrapped Ether"));
+
+    // Names must be valid UTF-8
+    let mut invalid: BodyAssetMetadata = meta.clone();
+    invalid.name[0] = 0xff;
+    assert_eq!(invalid.name_str(), Err(Error::InvalidValue));
+
+    // The payload has a fixed size
+    data.pop();
+    assert_eq!(VAA::deserialize(&data), Err(Error::UnexpectedEnd));
+}
+
+#[test]
+fn golden_message() {
+    let (data, vaa) = decode(vector("solana_message"));
+    assert_eq!(vaa.timestamp, 7000);
+    let message = match vaa.payload.unwrap() {
+        VAABody::Message(message) => message,
+        other => panic!("unexpected payload {:?}", other),
+    };
+    assert_eq!(message.emitter_chain, 1);
+    assert_eq!(message.emitter_address[0], 1);
+    assert_eq!(message.emitter_address[31], 32);
+    assert_eq!(message.nonce, 5);
+    assert_eq!(message.sequence, 3);
+    assert_eq!(message.payload, b"hello");
+
+    // The payload is length prefixed
+    let mut truncated = data.clone();
+    truncated.pop();
+    assert_eq!(VAA::deserialize(&truncated), Err(Error::UnexpectedEnd));
+
+    let too_long = BodyMessage {
+        payload: vec![0; u16::MAX as usize + 1],
+        ..message
+    };
+    assert_eq!(
+        VAABody::Message(too_long).serialize(),
+        Err(Error::LengthOverflow)
+    );
+}
+
+#[test]
+fn golden_transfer_with_payload() {
+    let (data, vaa) = decode(vector("terra_transfer_with_payload"));
+    let t = match vaa.payload.unwrap() {
+        VAABody::TransferWithPayload(t) => t,
+        other => panic!("unexpected payload {:?}", other),
+    };
+    assert_eq!(t.transfer.nonce, 56);
+    assert_eq!(t.transfer.source_chain, 1);
+    assert_eq!(t.transfer.target_chain, 3);
+    assert_eq!(t.transfer.asset.decimals, 8);
+    assert_eq!(t.transfer.amount, U256::from(1_000_000_000_000_000_000u64));
+    assert_eq!(t.payload, br#"{"swap":{}}"#);
+
+    // The transfer is encoded like a plain transfer, followed by the length prefixed payload
+    let (_, plain) = decode(vector("terra_transfer_1_sig"));
+    match plain.payload.unwrap() {
+        VAABody::Transfer(plain) => assert_eq!(t.transfer, plain),
+        other => panic!("unexpected payload {:?}", other),
+    }
+
+    let mut truncated = data.clone();
+    truncated.pop();
+    assert_eq!(VAA::deserialize(&truncated), Err(Error::UnexpectedEnd));
+
+    let too_long = BodyTransferWithPayload {
+        payload: vec![0; u16::MAX as usize + 1],
+        ..t
+    };
+    assert_eq!(
+        VAABody::TransferWithPayload(too_long).serialize(),
+        Err(Error::LengthOverflow)
+    );
+}
+
+#[test]
+fn golden_transfer_relayer_fee() {
+    let (data, vaa) = decode(vector("terra_native_transfer_relayer_fee"));
+    let payload = vaa.payload.unwrap();
+    assert_eq!(payload.action_id(), BodyTransfer::ACTION_WITH_FEE);
+    let t = match payload {
+        VAABody::Transfer(t) => t,
+        other => panic!("unexpected payload {:?}", other),
+    };
+    assert_eq!(t.amount, U256::from(1_000_000));
+    assert_eq!(t.fee, U256::from(1_000));
+
+    // Transfers without a fee must use the regular action
+    let fee_offset = data.len() - 32;
+    let mut zero_fee = data.clone();
+    zero_fee[fee_offset..].copy_from_slice(&[0; 32]);
+    assert_eq!(VAA::deserialize(&zero_fee), Err(Error::InvalidValue));
+
+    // The fee is carved out of the amount
+    let mut fee_too_high = data.clone();
+    U256::from(1_000_001).to_big_endian(&mut fee_too_high[fee_offset..]);
+    assert_eq!(VAA::deserialize(&fee_too_high), Err(Error::InvalidValue));
+
+    let without_fee = BodyTransfer {
+        fee: U256::zero(),
+        ..t
+    };
+    assert_eq!(
+        VAABody::Transfer(without_fee).action_id(),
+        BodyTransfer::ACTION
+    );
+}
+
+#[test]
+fn golden_refund() {
+    let (data, vaa) = decode(vector("solana_refund"));
+    let payload = vaa.payload.unwrap();
+    assert_eq!(payload.action_id(), BodyRefund::ACTION);
+    let r = match payload {
+        VAABody::Refund(r) => r,
+        other => panic!("unexpected payload {:?}", other),
+    };
+    assert_eq!(r.transfer.nonce, 7);
+    assert_eq!(r.transfer.source_chain, 1);
+    assert_eq!(r.transfer.target_chain, 2);
+    assert_eq!(r.transfer.source_address, [4; 32]);
+    assert_eq!(r.transfer.asset.chain, 1);
+    assert_eq!(r.transfer.asset.decimals, 8);
+    assert_eq!(r.transfer.amount, U256::from(1_000_000));
+
+    // The refunded transfer is encoded like a plain transfer
+    let body = &data[HEADER_LEN + SIGNATURE_LEN..];
+    assert_eq!(
+        VAABody::Transfer(r.transfer.clone()).serialize().unwrap(),
+        body[5..]
+    );
+
+    // Refunds never pay a relayer fee
+    let with_fee = BodyRefund {
+        transfer: BodyTransfer {
+            fee: U256::from(1),
+            ..r.transfer
+        },
+    };
+    assert_eq!(
+        VAABody::Refund(with_fee).serialize(),
+        Err(Error::InvalidValue)
+    );
+}
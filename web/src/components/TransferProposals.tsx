@@ -31,7 +31,8 @@ interface LockupWithStatus extends Lockup {
 enum LockupStatus {
     AWAITING_VAA,
     UNCLAIMED_VAA,
-    COMPLETED
+    COMPLETED,
+    REFUNDED
 }
 
 function TransferProposals() {
@@ -65,15 +66,13 @@ function TransferProposals() {
 
             let wormhole = WormholeFactory.connect(BRIDGE_ADDRESS, provider);
             for (let lockup of lockups) {
+                if (lockup.refunded) {
+                    lockup.status = LockupStatus.REFUNDED;
+                    continue;
+                }
                 if (lockup.vaaTime === undefined || lockup.vaaTime === 0) continue;
 
                 let signingData = lockup.vaa.slice(lockup.vaa[5] * 66 + 6)
-                for (let i = signingData.length; i > 0; i--) {
-                    if (signingData[i] == 0xff) {
-                        signingData = signingData.slice(0, i)
-                        break
-                    }
-                }
                 let hash = keccak256(signingData)
                 let submissionStatus = await wormhole.consumedVAAs(hash);
 
@@ -88,12 +87,6 @@ function TransferProposals() {
     let executeVAA = async (v: LockupWithStatus) => {
         let wh = WormholeFactory.connect(BRIDGE_ADDRESS, signer)
         let vaa = new Buffer(v.vaa);
-        for (let i = vaa.length; i > 0; i--) {
-            if (vaa[i] == 0xff) {
-                vaa = vaa.slice(0, i)
-                break
-            }
-        }
 
         let signatures = await b.fetchSignatureStatus(v.signatureAccount);
         let sigData = Buffer.of(...signatures.reduce((previousValue, currentValue) => {
@@ -141,6 +134,8 @@ function TransferProposals() {
                 }}>Execute</Button>);
             case LockupStatus.COMPLETED:
                 return ("Completed");
+            case LockupStatus.REFUNDED:
+                return ("Refunded");
         }
     }
 
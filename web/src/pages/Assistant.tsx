@@ -288,21 +288,14 @@ function Assistant() {
             if (completed) return;
 
             let lockup = bridge.parseLockup(transferProposal, a.data);
-            let vaa = lockup.vaa;
 
             console.log(lockup)
 
-            for (let i = vaa.length; i > 0; i--) {
-                if (vaa[i] == 0xff) {
-                    vaa = vaa.slice(0, i)
-                    break
-                }
-            }
-
             // Probably a poke
-            if (vaa.filter(v => v != 0).length == 0) {
+            if (lockup.vaaTime == 0) {
                 return
             }
+            let vaa = await bridge.fetchPostedVAA(lockup.postedVAA);
 
             completed = true;
             c.removeAccountChangeListener(accountChangeListener);
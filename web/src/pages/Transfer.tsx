@@ -162,22 +162,22 @@ function Transfer() {
             });
             setWrappedMint(wrappedMint.toString())
 
-            for (let account of tokenAccounts.balances) {
-                if (account.account.toString() == recipient) {
-                    setSolanaAccount({
-                        valid: true,
-                        message: ""
-                    })
-                    return;
-                }
+            // Tokens are delivered to the associated token account of the recipient wallet
+            try {
+                new PublicKey(recipient)
+                setSolanaAccount({
+                    valid: true,
+                    message: ""
+                })
+            } catch (e) {
+                setSolanaAccount({
+                    valid: false,
+                    message: "Not a valid Solana wallet address"
+                })
             }
-            setSolanaAccount({
-                valid: false,
-                message: "Not a valid wrapped token account"
-            })
         }
         getWrappedInfo();
-    }, [address, addressValid, tokenAccounts, bridge])
+    }, [address, addressValid, tokenAccounts, bridge, recipient])
 
     useEffect(() => {
         setAmountValid(amount.lte(coinInfo.balance))
@@ -225,7 +225,7 @@ function Transfer() {
                         </Form.Item>
                         <Form.Item name="recipient" validateStatus={solanaAccount.valid ? "success" : "error"}
                                    help={recipient === "" ? undefined : solanaAccount.message} label={"Recipient"}>
-                            <Input name="recipient" placeholder={"Address of the recipient"}
+                            <Input name="recipient" placeholder={"Wallet address of the recipient"}
                                    onFocus={(v) => {
                                        setRecipient(v.target.value)
                                    }}/>
@@ -262,9 +262,9 @@ function Transfer() {
                                             .map(v => (
                                                 <List.Item
                                                     actions={[(<Button size={"small"} type={"dashed"} onClick={() => {
-                                                        setRecipient(v.account.toString())
+                                                        setRecipient(k.publicKey.toString())
                                                         formRef.current?.setFieldsValue({
-                                                            "recipient": v.account.toString()
+                                                            "recipient": k.publicKey.toString()
                                                         })
                                                     }}>use</Button>)]}>
                                                     {v.account.toString()}
@@ -25,10 +25,13 @@ export interface Lockup {
     assetChain: number,
     assetDecimals: number,
     nonce: number,
+    sequence: number,
+    postedVAA: PublicKey,
     vaa: Uint8Array,
     vaaTime: number,
     pokeCounter: number,
     signatureAccount: PublicKey,
+    refunded: boolean,
     initialized: boolean,
 }
 
@@ -39,6 +42,12 @@ export interface Signature {
 
 export const CHAIN_ID_SOLANA = 1;
 
+// Reference state.rs::FeeState
+const FEE_BUCKET_SLOTS = 5;
+const FEE_BUCKETS = 15;
+const FEE_MAX_TPS = 10;
+const FEE_WINDOW_SECONDS = 30;
+
 class SolanaBridge {
     connection: solanaWeb3.Connection;
     programID: PublicKey;
@@ -107,8 +116,8 @@ class SolanaBridge {
             {pubkey: solanaWeb3.SYSVAR_CLOCK_PUBKEY, isSigner: false, isWritable: false},
             {pubkey: solanaWeb3.SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false},
             {pubkey: tokenAccount, isSigner: false, isWritable: true},
-            {pubkey: configKey, isSigner: false, isWritable: false},
-
+            {pubkey: configKey, isSigner: false, isWritable: true},
+            {pubkey: await this.getFeeStateKey(), isSigner: false, isWritable: true},
             {pubkey: transferKey, isSigner: false, isWritable: true},
             {pubkey: mint, isSigner: false, isWritable: true},
             {pubkey: payer, isSigner: true, isWritable: true},
@@ -118,6 +127,8 @@ class SolanaBridge {
             // @ts-ignore
             let custodyKey = (await solanaWeb3.PublicKey.findProgramAddress([Buffer.from("custody"), configKey.toBuffer(), mint.toBuffer()], this.programID))[0];
             keys.push({pubkey: custodyKey, isSigner: false, isWritable: true})
+        } else {
+            keys.push({pubkey: await this.getWrappedAssetMeta(mint), isSigner: false, isWritable: false})
         }
 
 
@@ -175,13 +186,14 @@ class SolanaBridge {
             const dataLayout = BufferLayout.struct([
                 BufferLayout.u8('assetChain'),
                 BufferLayout.blob(32, 'assetAddress'),
+                BufferLayout.u8('assetDecimals'),
             ]);
             let wrappedMeta = dataLayout.decode(metaInfo?.data);
 
             return {
                 address: wrappedMeta.assetAddress,
                 chain: wrappedMeta.assetChain,
-                decimals: 0
+                decimals: wrappedMeta.assetDecimals
             }
         }
     }
@@ -232,12 +244,13 @@ class SolanaBridge {
             BufferLayout.u8('assetDecimals'),
             BufferLayout.seq(BufferLayout.u8(), 1), // 4 byte alignment because a u32 is following
             BufferLayout.u32('nonce'),
-            BufferLayout.blob(1001, 'vaa'),
-            BufferLayout.seq(BufferLayout.u8(), 3), // 4 byte alignment because a u32 is following
+            BufferLayout.nu64('sequence'),
+            BufferLayout.blob(32, 'postedVAA'),
             BufferLayout.u32('vaaTime'),
             BufferLayout.u32('lockupTime'),
             BufferLayout.u8('pokeCounter'),
             BufferLayout.blob(32, 'signatureAccount'),
+            BufferLayout.u8('refunded'),
             BufferLayout.u8('initialized'),
         ]);
 
@@ -250,11 +263,14 @@ class SolanaBridge {
             assetChain: parsedAccount.assetChain,
             assetDecimals: parsedAccount.assetDecimals,
             initialized: parsedAccount.initialized == 1,
+            refunded: parsedAccount.refunded == 1,
             nonce: parsedAccount.nonce,
+            sequence: parsedAccount.sequence,
+            postedVAA: new PublicKey(parsedAccount.postedVAA),
             sourceAddress: new PublicKey(parsedAccount.sourceAddress),
             targetAddress: parsedAccount.targetAddress,
             toChain: parsedAccount.toChain,
-            vaa: parsedAccount.vaa,
+            vaa: new Uint8Array(),
             vaaTime: parsedAccount.vaaTime,
             signatureAccount: new PublicKey(parsedAccount.signatureAccount),
             pokeCounter: parsedAccount.pokeCounter
@@ -276,7 +292,7 @@ class SolanaBridge {
                 "method": "getProgramAccounts",
                 "params": [this.programID.toString(), {
                     "commitment": "single",
-                    "filters": [{"dataSize": 1184}, {
+                    "filters": [{"dataSize": 224}, {
                         "memcmp": {
                             "offset": 33,
                             "bytes": tokenAccount.toString()
@@ -290,12 +306,36 @@ class SolanaBridge {
         let accounts: Lockup[] = [];
         for (let acc of raw_accounts) {
             let pubkey = new PublicKey(acc.pubkey)
-            accounts.push(this.parseLockup(pubkey, bs58.decode(acc.account.data)))
+            let lockup = this.parseLockup(pubkey, bs58.decode(acc.account.data));
+            if (lockup.vaaTime != 0) {
+                lockup.vaa = await this.fetchPostedVAA(lockup.postedVAA)
+            }
+            accounts.push(lockup)
         }
 
         return accounts
     }
 
+    // fetchPostedVAA fetches the signed VAA stored in a PostedVAA account
+    async fetchPostedVAA(postedVAA: PublicKey): Promise<Buffer> {
+        let info = await this.connection.getAccountInfo(postedVAA);
+        if (info == null) {
+            throw new Error("posted vaa not found")
+        }
+
+        // Reference state.rs::PostedVAA
+        const headerLayout = BufferLayout.struct([
+            BufferLayout.blob(32, 'hash'),
+            BufferLayout.u32('vaaTime'),
+            BufferLayout.u32('len'),
+            BufferLayout.u8('initialized'),
+            BufferLayout.seq(BufferLayout.u8(), 3), // 4 byte alignment of the header
+        ]);
+        let header = headerLayout.decode(info.data);
+
+        return info.data.slice(headerLayout.span, headerLayout.span + header.len)
+    }
+
     AccountLayout = BufferLayout.struct([publicKey('mint'), publicKey('owner'), uint64('amount'), BufferLayout.u32('option'), publicKey('delegate'), BufferLayout.u8('is_initialized'), BufferLayout.u8('is_native'), BufferLayout.u16('padding'), uint64('delegatedAmount')]);
 
     async createWrappedAssetAndAccountInstructions(owner: PublicKey, mint: PublicKey, meta: AssetMeta): Promise<[TransactionInstruction[], solanaWeb3.Account]> {
@@ -342,54 +382,11 @@ class SolanaBridge {
 
         let ixs: TransactionInstruction[] = [];
 
-        let configKey = await this.getConfigKey();
+        // Wrapped assets are created by the guardians' asset attestation, not by users
         let wrappedKey = await this.getWrappedAssetMint(meta);
         let wrappedAcc = await this.connection.getAccountInfo(wrappedKey, "single");
         if (!wrappedAcc) {
-            let metaKey = await this.getWrappedAssetMeta(wrappedKey);
-            const wa_keys = [{
-                pubkey: solanaWeb3.SystemProgram.programId,
-                isSigner: false,
-                isWritable: false
-            }, {
-                pubkey: TOKEN_PROGRAM,
-                isSigner: false,
-                isWritable: false
-            }, {
-                pubkey: solanaWeb3.SYSVAR_RENT_PUBKEY,
-                isSigner: false,
-                isWritable: false
-            }, {
-                pubkey: configKey,
-                isSigner: false,
-                isWritable: false
-            }, {
-                pubkey: owner,
-                isSigner: true,
-                isWritable: true
-            }, {
-                pubkey: wrappedKey,
-                isSigner: false,
-                isWritable: true
-            }, {
-                pubkey: metaKey,
-                isSigner: false,
-                isWritable: true
-            }];
-            const wrappedDataLayout = BufferLayout.struct([BufferLayout.u8('instruction'), BufferLayout.blob(32, "assetAddress"), BufferLayout.u8('chain'), BufferLayout.u8('decimals')]);
-            const wrappedData = Buffer.alloc(wrappedDataLayout.span);
-            wrappedDataLayout.encode({
-                instruction: 7, // CreateWrapped instruction
-                assetAddress: padBuffer(meta.address, 32),
-                chain: meta.chain,
-                decimals: meta.decimals
-            }, wrappedData);
-            let ix_wrapped = {
-                keys: wa_keys,
-                programId: SOLANA_BRIDGE_PROGRAM,
-                data: wrappedData
-            }
-            ixs.push(ix_wrapped);
+            throw new Error("wrapped asset has not been attested yet")
         }
 
         ixs.push(create_ix, ix_init)
@@ -408,7 +405,7 @@ class SolanaBridge {
         }
 
         let configKey = await this.getConfigKey();
-        let seeds: Array<Buffer> = [Buffer.from("wrapped"), configKey.toBuffer(), Buffer.of(asset.chain), Buffer.of(asset.decimals),
+        let seeds: Array<Buffer> = [Buffer.from("wrapped"), configKey.toBuffer(), Buffer.of(asset.chain),
             padBuffer(asset.address, 32)];
         // @ts-ignore
         return (await solanaWeb3.PublicKey.findProgramAddress(seeds, this.programID))[0];
@@ -421,9 +418,56 @@ class SolanaBridge {
         return (await solanaWeb3.PublicKey.findProgramAddress(seeds, this.programID))[0];
     }
 
+    async getFeeStateKey(): Promise<PublicKey> {
+        let configKey = await this.getConfigKey();
+        let seeds: Array<Buffer> = [Buffer.from("fee"), configKey.toBuffer()];
+        // @ts-ignore
+        return (await solanaWeb3.PublicKey.findProgramAddress(seeds, this.programID))[0];
+    }
+
     async getTransferFee(): Promise<number> {
-        // Reference processor.rs::Bridge::transfer_fee
-        return (await this.connection.getMinimumBalanceForRentExemption((40 + 1340) * 2)) + 18 * 10000 * 2
+        let configKey = await this.getConfigKey();
+        let configInfo = await this.connection.getAccountInfo(configKey);
+        if (configInfo == null) {
+            throw new Error("bridge not found")
+        }
+
+        // Reference state.rs::BridgeConfig::transfer_fee
+        const configLayout = BufferLayout.struct([
+            BufferLayout.u32('guardianSetIndex'),
+            BufferLayout.blob(4),
+            BufferLayout.u32('guardianSetExpirationTime'),
+            BufferLayout.blob(32, 'tokenProgram'),
+            BufferLayout.blob(4),
+            BufferLayout.nu64('transferFee'),
+        ]);
+        let minFee: number = configLayout.decode(configInfo.data).transferFee;
+
+        // The fee state is created by the first transfer out
+        let feeStateInfo = await this.connection.getAccountInfo(await this.getFeeStateKey());
+        if (feeStateInfo == null) {
+            return minFee
+        }
+
+        // Reference state.rs::FeeState::transfer_fee
+        const feeStateLayout = BufferLayout.struct([
+            BufferLayout.seq(BufferLayout.nu64(), FEE_BUCKETS, 'buckets'),
+            BufferLayout.seq(BufferLayout.u32(), FEE_BUCKETS, 'counts'),
+        ]);
+        let feeState = feeStateLayout.decode(feeStateInfo.data);
+        let currentBucket = Math.floor(await this.connection.getSlot() / FEE_BUCKET_SLOTS);
+        let transfers = 0;
+        for (let i = 0; i < FEE_BUCKETS; i++) {
+            let bucket = feeState.buckets[i];
+            if (bucket <= currentBucket && bucket + FEE_BUCKETS > currentBucket) {
+                transfers += feeState.counts[i];
+            }
+        }
+
+        let maxTransfers = FEE_MAX_TPS * FEE_WINDOW_SECONDS;
+        transfers = Math.min(transfers, maxTransfers * 10);
+        let fee = Math.floor(solanaWeb3.LAMPORTS_PER_SOL * Math.pow(transfers / maxTransfers, 6));
+        return Math.max(fee, minFee)
     }
 }
 